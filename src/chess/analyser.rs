@@ -0,0 +1,243 @@
+//! Analysis of the displayed position by an engine running in the background.
+
+use std::fmt;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{Analysis, Board, Engine, Player, UciEngine, UciError, ANALYSIS_DEPTH};
+
+/// The last result of an [`Analyser`] on a position.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct AnalysisReport {
+    /// The analysed position.
+    pub board: Board,
+    /// The best lines found, from the best to the worst (empty if the game is over).
+    pub lines: Vec<Analysis>,
+    /// The number of positions searched.
+    pub nodes: u64,
+    /// The time spent on the position.
+    pub time: Duration,
+}
+
+impl AnalysisReport {
+    /// Get the depth of the search (in half-moves).
+    pub fn depth(&self) -> u32 {
+        self.lines.first().map_or(0, |line| line.depth)
+    }
+
+    /// Get the speed of the search (in positions per second).
+    pub fn nps(&self) -> u64 {
+        match self.time.as_millis() {
+            0 => 0,
+            millis => (self.nodes as u128 * 1000 / millis) as u64,
+        }
+    }
+}
+
+/// An engine that analyses a position continuously in another thread, until the position
+/// changes.
+///
+/// The built-in [`Engine`] deepens its search up to its depth (or [`ANALYSIS_DEPTH`] for
+/// a [`Player::Human`]), an [`UciEngine`] searches without limit. Both search the best
+/// `count` lines (MultiPV).
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use chess::{Analyser, Board, Player};
+///
+/// let mut analyser = Analyser::spawn(&Player::Engine(2), 3).unwrap();
+/// analyser.set_position(Board::default());
+/// while analyser.report().map_or(true, |report| report.depth() < 2) {
+///     std::thread::sleep(Duration::from_millis(10));
+///     analyser.update();
+/// }
+/// assert_eq!(analyser.report().unwrap().lines.len(), 3);
+/// ```
+pub struct Analyser {
+    name: String,
+    positions: mpsc::Sender<Board>,
+    reports: mpsc::Receiver<AnalysisReport>,
+    /// Stop the search of an [`UciEngine`] (the built-in engine stops between two depths).
+    stop: Option<Box<dyn Fn() + Send + Sync>>,
+    board: Option<Board>,
+    report: Option<AnalysisReport>,
+}
+
+impl Analyser {
+    /// Start the engine of the player in another thread, it waits for a
+    /// [position][Analyser::set_position].
+    pub fn spawn(player: &Player, count: usize) -> Result<Self, UciError> {
+        let (positions, receiver) = mpsc::channel();
+        let (sender, reports) = mpsc::channel();
+        let (name, stop): (String, Option<Box<dyn Fn() + Send + Sync>>) = match player {
+            Player::Uci(path) => {
+                let engine = UciEngine::spawn(path)?;
+                let name = engine.name().to_string();
+                let stop = Box::new(engine.stopper());
+                thread::spawn(move || run_uci(engine, count, receiver, sender));
+                (name, Some(stop))
+            }
+            Player::Human | Player::Engine(_) | Player::Level(_) => {
+                let depth = player.depth().unwrap_or(ANALYSIS_DEPTH);
+                thread::spawn(move || run_engine(depth, count, receiver, sender));
+                (format!("Engine (depth {depth})"), None)
+            }
+        };
+        Ok(Analyser {
+            name,
+            positions,
+            reports,
+            stop,
+            board: None,
+            report: None,
+        })
+    }
+
+    /// Get the name of the engine.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Analyse another position, the search of the previous one is stopped.
+    pub fn set_position(&mut self, board: Board) {
+        if self.board == Some(board) {
+            return;
+        }
+        self.board = Some(board);
+        self.report = None;
+        if let Some(stop) = &self.stop {
+            stop();
+        }
+        let _ = self.positions.send(board);
+    }
+
+    /// Receive the reports of the engine, without waiting.
+    ///
+    /// The reports of the previous positions are ignored.
+    pub fn update(&mut self) {
+        for report in self.reports.try_iter() {
+            if Some(report.board) == self.board {
+                self.report = Some(report);
+            }
+        }
+    }
+
+    /// Get the last report on the position (if the engine sent one).
+    pub fn report(&self) -> Option<&AnalysisReport> {
+        self.report.as_ref()
+    }
+}
+
+impl fmt::Debug for Analyser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Analyser")
+            .field("name", &self.name)
+            .field("board", &self.board)
+            .field("report", &self.report)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for Analyser {
+    fn drop(&mut self) {
+        // The thread ends with the search, once the positions are disconnected
+        if let Some(stop) = &self.stop {
+            stop();
+        }
+    }
+}
+
+/// Deepen the search of the built-in engine on every position received, a new position
+/// is looked for between two depths.
+fn run_engine(
+    max_depth: u32,
+    count: usize,
+    positions: mpsc::Receiver<Board>,
+    reports: mpsc::Sender<AnalysisReport>,
+) {
+    let mut next = positions.recv().ok();
+    while let Some(board) = next.take() {
+        let start = Instant::now();
+        let mut nodes = 0;
+        for depth in 1..=max_depth {
+            let (lines, searched) = Engine::new(depth).analyse_lines(&board, count);
+            nodes += searched;
+            let game_over = lines.is_empty();
+            let report = AnalysisReport {
+                board,
+                lines,
+                nodes,
+                time: start.elapsed(),
+            };
+            if reports.send(report).is_err() {
+                return;
+            }
+            next = positions.try_iter().last();
+            if next.is_some() || game_over {
+                break;
+            }
+        }
+        if next.is_none() {
+            next = positions.recv().ok();
+        }
+    }
+}
+
+/// Search every position received with an UCI engine, until the next one.
+fn run_uci(
+    mut engine: UciEngine,
+    count: usize,
+    positions: mpsc::Receiver<Board>,
+    reports: mpsc::Sender<AnalysisReport>,
+) {
+    let mut next = positions.recv().ok();
+    while let Some(board) = next.take() {
+        let start = Instant::now();
+        let result = engine.analyse_infinite(&board, count, |lines, nodes| {
+            next = positions.try_iter().last();
+            let report = AnalysisReport {
+                board,
+                lines: lines.to_vec(),
+                nodes,
+                time: start.elapsed(),
+            };
+            next.is_none() && reports.send(report).is_ok()
+        });
+        if let Err(_error) = result {
+            #[cfg(feature = "log")]
+            log::error!("{_error}");
+            return;
+        }
+        if next.is_none() {
+            next = positions.recv().ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn follow_the_position() {
+        let mut analyser = Analyser::spawn(&Player::Engine(1), 2).unwrap();
+        assert_eq!(analyser.name(), "Engine (depth 1)");
+        let mate = Board::from_str("k7/1R6/1K6/8/8/8/8/8 b - - 0 1").unwrap();
+        for board in [Board::default(), mate] {
+            analyser.set_position(board);
+            while analyser.report().is_none() {
+                thread::sleep(Duration::from_millis(10));
+                analyser.update();
+            }
+            assert_eq!(analyser.report().unwrap().board, board);
+        }
+        // No line when the game is over
+        assert_eq!(analyser.report().unwrap().lines, vec![]);
+        assert_eq!(analyser.report().unwrap().depth(), 0);
+    }
+}
@@ -0,0 +1,218 @@
+//! Describe the animations of the pieces on the board (GUI).
+
+use std::time::Duration;
+
+use crate::{Board, Color, Piece, Square, ALL_SQUARES};
+
+/// A [`Piece`] sliding from a [`Square`] to another one.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct MovingPiece {
+    pub piece: Piece,
+    pub color: Color,
+    pub from: Square,
+    pub to: Square,
+}
+
+/// An interpolated transition between two [`Board`].
+///
+/// The animation is computed from the difference between the two boards, so it handles
+/// every kind of move (castling, en passant, promotion) and undo the same way.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use chess::{Animation, Board, ChessMove, Square};
+///
+/// let before = Board::default();
+/// let mut after = before;
+/// after.update(ChessMove::new(Square::E2, Square::E4));
+///
+/// let mut animation = Animation::between(&before, &after, Duration::from_millis(200));
+/// assert_eq!(animation.moving().len(), 1);
+///
+/// animation.update(Duration::from_millis(100));
+/// assert_eq!(animation.progress(), 0.5);
+/// assert!(!animation.is_finished());
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct Animation {
+    moving: Vec<MovingPiece>,
+    vanishing: Vec<(Piece, Color, Square)>,
+    elapsed: Duration,
+    duration: Duration,
+}
+
+impl Animation {
+    /// Create the [`Animation`] that transform the `before` board into the `after` one.
+    pub fn between(before: &Board, after: &Board, duration: Duration) -> Self {
+        let mut moving = Vec::new();
+        let mut sources = Vec::new();
+
+        // A square that is left (or taken by the opponent) is the source of a moving piece
+        let is_source = |square: Square, color: Color| {
+            before.color_on_is(square, color) && !after.color_on_is(square, color)
+        };
+
+        for to in ALL_SQUARES {
+            if let Some((piece, color)) = after.on(to) {
+                if before.on(to) == Some((piece, color)) {
+                    continue;
+                }
+                // Look for the same piece first, then for any piece of the same color
+                // (i.e. a promotion or its undo).
+                let from = ALL_SQUARES
+                    .into_iter()
+                    .filter(|square| !sources.contains(square))
+                    .find(|&square| is_source(square, color) && before.piece_on_is(square, piece))
+                    .or_else(|| {
+                        ALL_SQUARES
+                            .into_iter()
+                            .filter(|square| !sources.contains(square))
+                            .find(|&square| is_source(square, color))
+                    });
+                if let Some(from) = from {
+                    sources.push(from);
+                    moving.push(MovingPiece {
+                        piece,
+                        color,
+                        from,
+                        to,
+                    });
+                }
+            }
+        }
+
+        // Every other piece that disappeared has been captured
+        let mut vanishing = Vec::new();
+        for square in ALL_SQUARES {
+            if let Some((piece, color)) = before.on(square) {
+                if !sources.contains(&square) && after.on(square) != Some((piece, color)) {
+                    vanishing.push((piece, color, square));
+                }
+            }
+        }
+
+        Animation {
+            moving,
+            vanishing,
+            elapsed: Duration::ZERO,
+            duration,
+        }
+    }
+
+    /// Get the pieces that are moving.
+    pub fn moving(&self) -> &[MovingPiece] {
+        &self.moving
+    }
+
+    /// Get the pieces that are captured, they are shown until the end of the animation.
+    pub fn vanishing(&self) -> &[(Piece, Color, Square)] {
+        &self.vanishing
+    }
+
+    /// Verify if the [`Square`] is the destination of a moving piece.
+    pub fn is_destination(&self, square: Square) -> bool {
+        self.moving.iter().any(|moving| moving.to == square)
+    }
+
+    /// Advance the animation.
+    pub fn update(&mut self, delta: Duration) {
+        self.elapsed = (self.elapsed + delta).min(self.duration);
+    }
+
+    /// Get the progress of the animation in the range 0.0..=1.0.
+    pub fn progress(&self) -> f32 {
+        if self.duration.is_zero() {
+            1.0
+        } else {
+            self.elapsed.as_secs_f32() / self.duration.as_secs_f32()
+        }
+    }
+
+    /// Verify if the animation is over.
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    /// Interpolate a position between two screen coordinates according to the progress.
+    ///
+    /// The movement is eased to start and end smoothly.
+    pub fn interpolate(&self, from: (f32, f32), to: (f32, f32)) -> (f32, f32) {
+        let t = self.progress();
+        let t = t * t * (3.0 - 2.0 * t);
+        (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::ChessMove;
+
+    const DURATION: Duration = Duration::from_millis(100);
+
+    #[test]
+    fn capture() {
+        let before = Board::from_str("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1").unwrap();
+        let mut after = before;
+        after.update(ChessMove::new(Square::E4, Square::D5));
+
+        let animation = Animation::between(&before, &after, DURATION);
+        assert_eq!(
+            animation.moving(),
+            &[MovingPiece {
+                piece: Piece::Pawn,
+                color: Color::White,
+                from: Square::E4,
+                to: Square::D5
+            }]
+        );
+        assert_eq!(
+            animation.vanishing(),
+            &[(Piece::Pawn, Color::Black, Square::D5)]
+        );
+    }
+
+    #[test]
+    fn castle() {
+        let before = Board::from_str("4k3/8/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+        let mut after = before;
+        after.update(ChessMove::new(Square::E1, Square::G1));
+
+        let animation = Animation::between(&before, &after, DURATION);
+        assert_eq!(animation.moving().len(), 2);
+        assert!(animation.is_destination(Square::G1));
+        assert!(animation.is_destination(Square::F1));
+        assert!(animation.vanishing().is_empty());
+    }
+
+    #[test]
+    fn undo() {
+        let after = Board::from_str("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1").unwrap();
+        let mut before = after;
+        before.update(ChessMove::new(Square::E4, Square::D5));
+
+        let animation = Animation::between(&before, &after, DURATION);
+        assert_eq!(animation.moving().len(), 1);
+        assert_eq!(animation.moving()[0].from, Square::D5);
+        assert_eq!(animation.moving()[0].to, Square::E4);
+        assert!(animation.vanishing().is_empty());
+    }
+
+    #[test]
+    fn progress() {
+        let mut animation = Animation::between(&Board::default(), &Board::default(), DURATION);
+        assert_eq!(animation.progress(), 0.0);
+        animation.update(Duration::from_millis(50));
+        assert_eq!(
+            animation.interpolate((0.0, 0.0), (100.0, 10.0)),
+            (50.0, 5.0)
+        );
+        animation.update(Duration::from_millis(80));
+        assert!(animation.is_finished());
+        assert_eq!(animation.progress(), 1.0);
+    }
+}
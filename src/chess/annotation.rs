@@ -0,0 +1,305 @@
+//! Arrows and circles drawn on the board, written in the PGN comments.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::{Error, Square};
+
+/// Number of colors of the annotations.
+pub const NUM_ANNOTATION_COLORS: usize = 4;
+
+/// The color of an arrow or a circle, with its letter in the PGN comment commands.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AnnotationColor {
+    /// `G`
+    Green,
+    /// `R`
+    Red,
+    /// `Y`
+    Yellow,
+    /// `B`
+    Blue,
+}
+
+/// List of all the colors of the annotations.
+pub const ALL_ANNOTATION_COLORS: [AnnotationColor; NUM_ANNOTATION_COLORS] = [
+    AnnotationColor::Green,
+    AnnotationColor::Red,
+    AnnotationColor::Yellow,
+    AnnotationColor::Blue,
+];
+
+impl AnnotationColor {
+    /// Get the letter of the color in the `[%cal]` and `[%csl]` commands.
+    pub fn to_char(self) -> char {
+        match self {
+            AnnotationColor::Green => 'G',
+            AnnotationColor::Red => 'R',
+            AnnotationColor::Yellow => 'Y',
+            AnnotationColor::Blue => 'B',
+        }
+    }
+
+    /// Get the color of a letter of the `[%cal]` and `[%csl]` commands.
+    pub fn from_char(c: char) -> Option<Self> {
+        ALL_ANNOTATION_COLORS
+            .into_iter()
+            .find(|color| color.to_char() == c)
+    }
+}
+
+/// An arrow drawn from a [`Square`] to another.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Arrow {
+    pub from: Square,
+    pub to: Square,
+    pub color: AnnotationColor,
+}
+
+/// A circle drawn around a [`Square`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Circle {
+    pub square: Square,
+    pub color: AnnotationColor,
+}
+
+/// The arrows and the circles drawn on a position.
+///
+/// They are written in a PGN comment with the commands used by the other tools:
+/// `[%csl Gd4,Re5]` for the circles and `[%cal Ge2e4]` for the arrows, the first letter is
+/// the color ([`AnnotationColor`]).
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+/// use chess::{AnnotationColor, Annotations, Square};
+///
+/// let mut annotations = Annotations::default();
+/// annotations.toggle_arrow(Square::E2, Square::E4, AnnotationColor::Green);
+/// annotations.toggle_circle(Square::D5, AnnotationColor::Red);
+/// assert_eq!(annotations.to_string(), "[%csl Rd5] [%cal Ge2e4]");
+///
+/// // The other text of a comment is ignored
+/// let parsed = Annotations::from_str("Good move [%cal Ge2e4] [%csl Rd5]").unwrap();
+/// assert_eq!(parsed, annotations);
+/// ```
+#[derive(Clone, Eq, PartialEq, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Annotations {
+    arrows: Vec<Arrow>,
+    circles: Vec<Circle>,
+}
+
+impl Annotations {
+    /// Get the arrows, in the order they were drawn.
+    pub fn arrows(&self) -> &[Arrow] {
+        &self.arrows
+    }
+
+    /// Get the circles, in the order they were drawn.
+    pub fn circles(&self) -> &[Circle] {
+        &self.circles
+    }
+
+    /// Verify if nothing is drawn.
+    pub fn is_empty(&self) -> bool {
+        self.arrows.is_empty() && self.circles.is_empty()
+    }
+
+    /// Remove all the arrows and the circles.
+    pub fn clear(&mut self) {
+        self.arrows.clear();
+        self.circles.clear();
+    }
+
+    /// Draw an arrow, or remove it if the same arrow is already drawn.
+    ///
+    /// An arrow between the same squares in another color is replaced.
+    pub fn toggle_arrow(&mut self, from: Square, to: Square, color: AnnotationColor) {
+        let arrow = Arrow { from, to, color };
+        match self
+            .arrows
+            .iter()
+            .position(|arrow| arrow.from == from && arrow.to == to)
+        {
+            Some(index) if self.arrows[index] == arrow => {
+                self.arrows.remove(index);
+            }
+            Some(index) => self.arrows[index] = arrow,
+            None => self.arrows.push(arrow),
+        }
+    }
+
+    /// Draw a circle, or remove it if the same circle is already drawn.
+    ///
+    /// A circle on the same square in another color is replaced.
+    pub fn toggle_circle(&mut self, square: Square, color: AnnotationColor) {
+        let circle = Circle { square, color };
+        match self
+            .circles
+            .iter()
+            .position(|circle| circle.square == square)
+        {
+            Some(index) if self.circles[index] == circle => {
+                self.circles.remove(index);
+            }
+            Some(index) => self.circles[index] = circle,
+            None => self.circles.push(circle),
+        }
+    }
+}
+
+impl fmt::Display for Annotations {
+    /// Write the `[%csl]` and `[%cal]` commands (nothing if no annotation).
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let circles: Vec<String> = self
+            .circles
+            .iter()
+            .map(|circle| format!("{}{}", circle.color.to_char(), circle.square))
+            .collect();
+        let arrows: Vec<String> = self
+            .arrows
+            .iter()
+            .map(|arrow| format!("{}{}{}", arrow.color.to_char(), arrow.from, arrow.to))
+            .collect();
+        let mut commands = Vec::new();
+        if !circles.is_empty() {
+            commands.push(format!("[%csl {}]", circles.join(",")));
+        }
+        if !arrows.is_empty() {
+            commands.push(format!("[%cal {}]", arrows.join(",")));
+        }
+        write!(f, "{}", commands.join(" "))
+    }
+}
+
+impl FromStr for Annotations {
+    type Err = Error;
+
+    /// Read the `[%csl]` and `[%cal]` commands of a PGN comment, the rest of the comment is
+    /// ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_comment(s).map(|(annotations, _)| annotations)
+    }
+}
+
+/// Read the `[%csl]` and `[%cal]` commands of a PGN comment, and the rest of the comment
+/// (trimmed, the other commands are kept).
+pub(crate) fn parse_comment(s: &str) -> Result<(Annotations, String), Error> {
+    let error = || Error::InvalidPgn {
+        reason: format!("invalid annotation {s}"),
+    };
+    let mut annotations = Annotations::default();
+    let mut text = String::new();
+    let mut rest = s;
+    while let Some(start) = rest.find("[%") {
+        let (command, end) = rest[start + 2..].split_once(']').ok_or_else(error)?;
+        let before = &rest[..start];
+        let whole = &rest[start..rest.len() - end.len()];
+        rest = end;
+        text.push_str(before);
+        let (name, values) = command
+            .trim()
+            .split_once(char::is_whitespace)
+            .unwrap_or((command, ""));
+        // The other commands (e.g. `[%clk 0:05:00]`) are kept in the text
+        if !matches!(name, "csl" | "cal") {
+            text.push_str(whole);
+            continue;
+        }
+        for value in values
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+        {
+            let mut chars = value.chars();
+            let color = chars
+                .next()
+                .and_then(AnnotationColor::from_char)
+                .ok_or_else(error)?;
+            let squares = chars.as_str();
+            let square = |range| {
+                squares
+                    .get(range)
+                    .and_then(|square| Square::from_str(square).ok())
+                    .ok_or_else(error)
+            };
+            match (name, squares.len()) {
+                ("csl", 2) => annotations.circles.push(Circle {
+                    square: square(0..2)?,
+                    color,
+                }),
+                ("cal", 4) => annotations.arrows.push(Arrow {
+                    from: square(0..2)?,
+                    to: square(2..4)?,
+                    color,
+                }),
+                _ => return Err(error()),
+            }
+        }
+    }
+    text.push_str(rest);
+    let text: Vec<&str> = text.split_whitespace().collect();
+    Ok((annotations, text.join(" ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toggle() {
+        let mut annotations = Annotations::default();
+        annotations.toggle_arrow(Square::G1, Square::F3, AnnotationColor::Green);
+        annotations.toggle_arrow(Square::G1, Square::F3, AnnotationColor::Blue);
+        annotations.toggle_circle(Square::E4, AnnotationColor::Yellow);
+        assert_eq!(
+            annotations.arrows(),
+            [Arrow {
+                from: Square::G1,
+                to: Square::F3,
+                color: AnnotationColor::Blue
+            }]
+        );
+        assert_eq!(annotations.to_string(), "[%csl Ye4] [%cal Bg1f3]");
+
+        annotations.toggle_arrow(Square::G1, Square::F3, AnnotationColor::Blue);
+        annotations.toggle_circle(Square::E4, AnnotationColor::Yellow);
+        assert!(annotations.is_empty());
+        assert_eq!(annotations.to_string(), "");
+    }
+
+    #[test]
+    fn parse() {
+        let annotations =
+            Annotations::from_str("[%clk 0:04:59] [%cal Gg1f3, Rd1h5] text [%csl Ye4]").unwrap();
+        assert_eq!(annotations.arrows().len(), 2);
+        assert_eq!(
+            annotations.circles(),
+            [Circle {
+                square: Square::E4,
+                color: AnnotationColor::Yellow
+            }]
+        );
+        assert_eq!(annotations.to_string(), "[%csl Ye4] [%cal Gg1f3,Rd1h5]");
+        assert_eq!(
+            Annotations::from_str("Only text").unwrap(),
+            Annotations::default()
+        );
+        let (annotations, text) =
+            parse_comment(" Good [%cal Ge2e4]  move [%clk 0:05:00] ").unwrap();
+        assert_eq!(annotations.arrows().len(), 1);
+        assert_eq!(text, "Good move [%clk 0:05:00]");
+
+        for invalid in ["[%cal Xe2e4]", "[%cal Ge2]", "[%csl Gz9]", "[%csl Ge4"] {
+            assert!(Annotations::from_str(invalid).is_err(), "{invalid}");
+        }
+    }
+}
@@ -1,8 +1,10 @@
 //! Describe the board and interaction with it.
 
-use std::fmt;
-use std::ops::{Index, IndexMut};
-use std::str::FromStr;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::{Index, IndexMut};
+use core::str::FromStr;
 
 use crate::*;
 
@@ -111,7 +113,7 @@ impl Board {
         let mut is_valid = false;
         if let Some(side) = self.color_on(m.from) {
             if side == self.side_to_move {
-                if self.get_valid_moves(m.from).contains(&m.to) {
+                if self.get_valid_moves_fixed(m.from).contains(&m.to) {
                     is_valid = true;
                 }
             }
@@ -124,7 +126,7 @@ impl Board {
         let mut is_legal = false;
         if let Some(side) = self.color_on(m.from) {
             if side == self.side_to_move {
-                if self.get_legal_moves(m.from).contains(&m.to) {
+                if self.get_legal_moves_fixed(m.from).contains(&m.to) {
                     is_legal = true;
                 }
             }
@@ -266,6 +268,114 @@ impl Board {
         self.castle_rights[color.to_index()] = CastleRights::from_index(index);
     }
 
+    /// Set the [`Color`] of the player who has to play (e.g. to set up a position).
+    pub fn set_side_to_move(&mut self, color: Color) {
+        self.side_to_move = color;
+    }
+
+    /// Set the [`CastleRights`] of the given [`Color`] (e.g. to set up a position).
+    ///
+    /// > **Note**: The rights are not verified, see [`Board::validate`].
+    pub fn set_castle_rights(&mut self, color: Color, castle_rights: CastleRights) {
+        self.castle_rights[color.to_index()] = castle_rights;
+    }
+
+    /// Set the [`Square`] where a pawn can be taken en passant (e.g. to set up a position).
+    ///
+    /// > **Note**: The square is not verified, see [`Board::validate`].
+    pub fn set_en_passant(&mut self, en_passant: Option<Square>) {
+        self.en_passant = en_passant;
+    }
+
+    /// Verify if the position can be played: one king per side, no pawn on the first and
+    /// last ranks, the side that doesn't play is not in check, and the castle rights and
+    /// the en passant square are possible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::{Board, Color, Piece, PositionError, Square};
+    ///
+    /// let mut board = Board::default();
+    /// assert_eq!(board.validate(), Ok(()));
+    ///
+    /// board[Square::E1] = None;
+    /// assert_eq!(board.validate(), Err(PositionError::KingCount(Color::White)));
+    /// ```
+    pub fn validate(&self) -> Result<(), PositionError> {
+        for color in ALL_COLORS {
+            if self.count(Piece::King, color) != 1 {
+                return Err(PositionError::KingCount(color));
+            }
+            if self.count(Piece::Pawn, color) > 8 {
+                return Err(PositionError::TooManyPawns(color));
+            }
+            let pieces = self.squares.iter().flatten();
+            if pieces.filter(|(_, owner)| *owner == color).count() > 16 {
+                return Err(PositionError::TooManyPieces(color));
+            }
+        }
+        for file in ALL_FILES {
+            for rank in [Rank::First, Rank::Eighth] {
+                if self.piece_on_is(Square::make_square(file, rank), Piece::Pawn) {
+                    return Err(PositionError::PawnOnBackRank);
+                }
+            }
+        }
+        let opponent = !self.side_to_move;
+        if self.is_targeted(self.king_of(opponent), self.side_to_move) {
+            return Err(PositionError::OpponentInCheck);
+        }
+        for color in ALL_COLORS {
+            let rights = self.castle_rights(color);
+            let rank = match color {
+                Color::White => Rank::First,
+                Color::Black => Rank::Eighth,
+            };
+            let king = self.on_is(Square::make_square(File::E, rank), (Piece::King, color));
+            let rook = |file| self.on_is(Square::make_square(file, rank), (Piece::Rook, color));
+            if (rights.has_kingside() && !(king && rook(File::H)))
+                || (rights.has_queenside() && !(king && rook(File::A)))
+            {
+                return Err(PositionError::InvalidCastleRights(color));
+            }
+        }
+        if let Some(square) = self.en_passant {
+            if !self.en_passant_targets_fixed().contains(&square) {
+                return Err(PositionError::InvalidEnPassant);
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the squares where a pawn of the side to move could take en passant if the
+    /// opponent had just pushed a pawn by two squares (the possible [`Board::en_passant`]).
+    #[cfg(feature = "alloc")]
+    pub fn en_passant_targets(&self) -> Vec<Square> {
+        self.en_passant_targets_fixed().to_vec()
+    }
+
+    /// Get the possible [`Board::en_passant`] squares without allocation
+    /// (see [`Board::en_passant_targets`]).
+    pub fn en_passant_targets_fixed(&self) -> FixedVec<Square, 8> {
+        let mut targets = FixedVec::new();
+        let (passed, from, to) = match self.side_to_move {
+            Color::White => (Rank::Sixth, Rank::Seventh, Rank::Fifth),
+            Color::Black => (Rank::Third, Rank::Second, Rank::Fourth),
+        };
+        let opponent = !self.side_to_move;
+        for file in ALL_FILES {
+            let square = Square::make_square(file, passed);
+            if self.is_empty(square)
+                && self.is_empty(Square::make_square(file, from))
+                && self.on_is(Square::make_square(file, to), (Piece::Pawn, opponent))
+            {
+                targets.push(square);
+            }
+        }
+        targets
+    }
+
     /// Get the [`Piece`] at a given [`Square`].
     pub fn piece_on(&self, square: Square) -> Option<Piece> {
         self.squares[square.to_index()].map(|(piece, _)| piece)
@@ -288,7 +398,7 @@ impl Board {
 
     /// Get the [`Color`] at a given [`Square`].
     pub fn on(&self, square: Square) -> Option<(Piece, Color)> {
-        self.squares[square.to_index()].map(|(piece, color)| (piece, color))
+        self.squares[square.to_index()]
     }
 
     /// Verify if the [`Square`] is occupied by the given [`Piece`] and [`Color`].
@@ -309,8 +419,14 @@ impl Board {
     }
 
     /// Get the piece pinned for the current side.
+    #[cfg(feature = "alloc")]
     pub fn pinned(&self) -> Vec<Square> {
-        let mut pinned = Vec::new();
+        self.pinned_fixed().to_vec()
+    }
+
+    /// Get the piece pinned for the current side, without allocation.
+    pub fn pinned_fixed(&self) -> FixedVec<Square, 16> {
+        let mut pinned = FixedVec::new();
         for square in ALL_SQUARES {
             if self.is_pinned(square) {
                 pinned.push(square);
@@ -329,6 +445,74 @@ impl Board {
         panic!("King square of {color:?} not found")
     }
 
+    /// Count the number of a given [`Piece`] and [`Color`] on the board.
+    pub fn count(&self, piece: Piece, color: Color) -> usize {
+        self.squares
+            .iter()
+            .filter(|&&content| content == Some((piece, color)))
+            .count()
+    }
+
+    /// Get the material (i.e. the sum of the [`values`][Piece::value]) of the given [`Color`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::{Board, Color};
+    ///
+    /// let board = Board::default();
+    /// assert_eq!(board.material(Color::White), 39);
+    /// ```
+    pub fn material(&self, color: Color) -> u32 {
+        self.squares
+            .iter()
+            .flatten()
+            .filter(|(_, piece_color)| *piece_color == color)
+            .map(|(piece, _)| piece.value())
+            .sum()
+    }
+
+    /// Get the material difference between the given [`Color`] and its opponent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::{Board, Color};
+    /// use std::str::FromStr;
+    ///
+    /// // White is up a knight
+    /// let board = Board::from_str("4k3/8/8/8/8/8/8/4KN2 w - - 0 1").unwrap();
+    /// assert_eq!(board.material_advantage(Color::White), 3);
+    /// assert_eq!(board.material_advantage(Color::Black), -3);
+    /// ```
+    pub fn material_advantage(&self, color: Color) -> i32 {
+        self.material(color) as i32 - self.material(!color) as i32
+    }
+
+    /// Get the pieces captured by the given [`Color`], in order of ascending value.
+    ///
+    /// Computed by comparing the opponent's pieces with the ones of the initial position,
+    /// so a promoted piece hide a captured one of the same kind.
+    #[cfg(feature = "alloc")]
+    pub fn captured(&self, color: Color) -> Vec<Piece> {
+        self.captured_fixed(color).to_vec()
+    }
+
+    /// Get the pieces captured by the given [`Color`], without allocation
+    /// (see [`Board::captured`]).
+    pub fn captured_fixed(&self, color: Color) -> FixedVec<Piece, 15> {
+        const INITIAL_COUNT: [usize; NUM_PIECES] = [8, 2, 2, 2, 1, 1];
+        let mut captured = FixedVec::new();
+        // The king can't be captured, it is only missing from an invalid position
+        for piece in ALL_PIECES.into_iter().filter(|&piece| piece != Piece::King) {
+            let missing = INITIAL_COUNT[piece.to_index()].saturating_sub(self.count(piece, !color));
+            for _ in 0..missing {
+                captured.push(piece);
+            }
+        }
+        captured
+    }
+
     /// Verify if the [`Square`] is empty (i.e. not occupied).
     pub fn is_empty(&self, square: Square) -> bool {
         self.squares[square.to_index()].is_none()
@@ -367,7 +551,7 @@ impl Board {
         let mut is_targeted = false;
         for from_square in ALL_SQUARES {
             if self.color_on_is(from_square, attacker) {
-                if self.get_valid_moves(from_square).contains(&target) {
+                if self.get_valid_moves_fixed(from_square).contains(&target) {
                     is_targeted = true;
                     break;
                 }
@@ -389,14 +573,14 @@ impl Board {
     ///
     /// > **Note**: The legality is not verify, if you want to: use [`has_legal_move`][Board::has_legal_move].
     pub fn has_valid_move(&self, square: Square) -> bool {
-        !self.get_valid_moves(square).is_empty()
+        !self.get_valid_moves_fixed(square).is_empty()
     }
 
     /// Verify if the [`Piece`] on the [`Square`] has one or more legal moves.
     ///
     /// If no [`Piece`] exist on the [`Square`], then return false.
     pub fn has_legal_move(&self, square: Square) -> bool {
-        !self.get_legal_moves(square).is_empty()
+        !self.get_legal_moves_fixed(square).is_empty()
     }
 
     /// Verify if the player has one or more legal moves in all the [`Board`].
@@ -413,13 +597,81 @@ impl Board {
         false
     }
 
+    /// Compute and return all the legal moves of the player who has to play.
+    ///
+    /// ```
+    /// use chess::Board;
+    ///
+    /// assert_eq!(Board::default().get_all_legal_moves().len(), 20);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn get_all_legal_moves(&self) -> Vec<ChessMove> {
+        self.get_all_legal_moves_fixed().to_vec()
+    }
+
+    /// Compute and return all the legal moves of the player who has to play, without allocation.
+    ///
+    /// ```
+    /// use chess::Board;
+    ///
+    /// assert_eq!(Board::default().get_all_legal_moves_fixed().len(), 20);
+    /// ```
+    pub fn get_all_legal_moves_fixed(&self) -> MoveList {
+        let mut moves = MoveList::new();
+        for from in ALL_SQUARES {
+            if self.color_on_is(from, self.side_to_move) {
+                for to in self.get_legal_moves_fixed(from) {
+                    moves.push(ChessMove::new(from, to));
+                }
+            }
+        }
+        moves
+    }
+
+    /// Count the leaf nodes of the tree of legal moves at the given depth.
+    ///
+    /// Used to verify the move generator against known values.
+    ///
+    /// > **Note**: A pawn is always promoted to a [`Queen`][Piece::Queen], so the count differs
+    /// > from the usual values when under-promotions are reachable.
+    ///
+    /// ```
+    /// use chess::Board;
+    ///
+    /// assert_eq!(Board::default().perft(2), 400);
+    /// ```
+    pub fn perft(&self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let moves = self.get_all_legal_moves_fixed();
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+        moves
+            .into_iter()
+            .map(|m| {
+                let mut board = *self;
+                board.update(m);
+                board.perft(depth - 1)
+            })
+            .sum()
+    }
+
     /// Compute and return all the valid moves for a [`Piece`] (if exist) at a given [`Square`].
     ///
     /// If no [`Piece`] exist on the [`Square`], then return an empty [`Vec`].
     ///
     /// > **Note**: The legality is not verify, if you want to: use [`get_legal_moves`][Board::get_legal_moves].
+    #[cfg(feature = "alloc")]
     pub fn get_valid_moves(&self, from: Square) -> Vec<Square> {
-        let mut valid_moves = Vec::new();
+        self.get_valid_moves_fixed(from).to_vec()
+    }
+
+    /// Compute and return all the valid moves for a [`Piece`] (if exist) at a given [`Square`],
+    /// without allocation (see [`Board::get_valid_moves`]).
+    pub fn get_valid_moves_fixed(&self, from: Square) -> SquareList {
+        let mut valid_moves = SquareList::new();
         if let Some((piece_from, side)) = self.on(from) {
             let mut dest_square;
             match piece_from {
@@ -467,7 +719,7 @@ impl Board {
                     }
                 }
                 Piece::Knight => {
-                    let _knight_moves = vec![
+                    let _knight_moves = [
                         from.up().up().left(),
                         from.up().up().right(),
                         from.right().right().up(),
@@ -477,7 +729,7 @@ impl Board {
                         from.left().left().down(),
                         from.left().left().up(),
                     ];
-                    let mut knight_moves = Vec::new();
+                    let mut knight_moves = FixedVec::<Square, 8>::new();
                     // filter
                     for dest_square in _knight_moves {
                         if from.distance(dest_square) == 2 {
@@ -595,8 +847,15 @@ impl Board {
     /// Compute and return all the legal moves for a [`Piece`] (if exist) at a given [`Square`].
     ///
     /// If no [`Piece`] exist on the [`Square`], then return an empty [`Vec`].
+    #[cfg(feature = "alloc")]
     pub fn get_legal_moves(&self, from: Square) -> Vec<Square> {
-        let mut valid_moves = Vec::new();
+        self.get_legal_moves_fixed(from).to_vec()
+    }
+
+    /// Compute and return all the legal moves for a [`Piece`] (if exist) at a given [`Square`],
+    /// without allocation (see [`Board::get_legal_moves`]).
+    pub fn get_legal_moves_fixed(&self, from: Square) -> SquareList {
+        let mut valid_moves = SquareList::new();
         if let Some((piece_from, side)) = self.on(from) {
             let mut dest_square;
             match piece_from {
@@ -653,7 +912,7 @@ impl Board {
                     }
                 }
                 Piece::Knight => {
-                    let knight_moves_rule = vec![
+                    let knight_moves_rule = [
                         from.up().up().left(),
                         from.up().up().right(),
                         from.right().right().up(),
@@ -836,8 +1095,15 @@ impl Board {
     ///     vec![Square::D2, Square::E3, Square::F4, Square::G5]
     /// )
     /// ```
+    #[cfg(feature = "alloc")]
     pub fn get_line(&self, from: Square, direction: Direction) -> Vec<Square> {
-        let mut line = Vec::with_capacity(7);
+        self.get_line_fixed(from, direction).to_vec()
+    }
+
+    /// Construct the line of [`Square`] from a [`Square`] (exclusive) to the first [`Piece`]
+    /// (inclusive) with a given direction, without allocation (see [`Board::get_line`]).
+    pub fn get_line_fixed(&self, from: Square, direction: Direction) -> FixedVec<Square, 7> {
+        let mut line = FixedVec::new();
         let mut current_square = from.follow_direction(direction);
         while self.is_empty(current_square) {
             line.push(current_square);
@@ -879,19 +1145,14 @@ impl FromStr for Board {
         let mut cur_file = File::A;
         let mut board = Board::new();
 
-        let tokens: Vec<&str> = value.split(' ').collect();
-        if tokens.len() < 6 {
-            return Err(Error::InvalidFen {
-                fen: value.to_string(),
-            });
-        }
-
-        let pieces = tokens[0];
-        let side = tokens[1];
-        let castles = tokens[2];
-        let ep = tokens[3];
-        let halfmoves = tokens[4];
-        let fullmoves = tokens[5];
+        let mut tokens = value.split(' ');
+        let mut token = || tokens.next().ok_or_else(|| Error::invalid_fen(value));
+        let pieces = token()?;
+        let side = token()?;
+        let castles = token()?;
+        let ep = token()?;
+        let halfmoves = token()?;
+        let fullmoves = token()?;
 
         // Piece Placement
         for x in pieces.chars() {
@@ -964,9 +1225,7 @@ impl FromStr for Board {
                     cur_file = cur_file.right();
                 }
                 _ => {
-                    return Err(Error::InvalidFen {
-                        fen: value.to_string(),
-                    });
+                    return Err(Error::invalid_fen(value));
                 }
             }
         }
@@ -975,11 +1234,7 @@ impl FromStr for Board {
         match side {
             "w" | "W" => board.side_to_move = Color::White,
             "b" | "B" => board.side_to_move = Color::Black,
-            _ => {
-                return Err(Error::InvalidFen {
-                    fen: value.to_string(),
-                })
-            }
+            _ => return Err(Error::invalid_fen(value)),
         }
 
         // Castling Rights
@@ -1029,7 +1284,7 @@ impl fmt::Display for Board {
                         write!(f, "{}", count)?;
                         count = 0;
                     }
-                    write!(f, "{}", piece.to_string(color))?;
+                    write!(f, "{}", piece.to_char(color))?;
                 } else {
                     count += 1;
                 }
@@ -1055,16 +1310,15 @@ impl fmt::Display for Board {
         }
 
         // Castling Rights
-        write!(
-            f,
-            "{}",
-            self.castle_rights(Color::White).to_string(Color::White)
-        )?;
-        write!(
-            f,
-            "{}",
-            self.castle_rights(Color::Black).to_string(Color::Black)
-        )?;
+        for color in ALL_COLORS {
+            let castle_rights = self.castle_rights(color);
+            if castle_rights.has_kingside() {
+                write!(f, "{}", Piece::King.to_char(color))?;
+            }
+            if castle_rights.has_queenside() {
+                write!(f, "{}", Piece::Queen.to_char(color))?;
+            }
+        }
         if self.castle_rights[0] == CastleRights::NoRights
             && self.castle_rights[1] == CastleRights::NoRights
         {
@@ -1211,6 +1465,57 @@ mod tests {
         }
     }
 
+    #[test]
+    fn material() {
+        let board = Board::default();
+        assert_eq!(board.material(Color::White), 39);
+        assert_eq!(board.material(Color::Black), 39);
+        assert_eq!(board.material_advantage(Color::White), 0);
+
+        // White: K, Q, R, 2P - Black: K, B, P
+        let fen = "4k3/5p2/8/3b4/8/8/PP6/3QK2R w - - 0 1";
+        let board = Board::from_str(fen).expect("valid fen");
+        assert_eq!(board.material(Color::White), 16);
+        assert_eq!(board.material(Color::Black), 4);
+        assert_eq!(board.material_advantage(Color::White), 12);
+        assert_eq!(board.material_advantage(Color::Black), -12);
+    }
+
+    #[test]
+    fn captured() {
+        let mut board = Board::default();
+        assert!(board.captured_fixed(Color::White).is_empty());
+        assert!(board.captured_fixed(Color::Black).is_empty());
+
+        board.update(ChessMove::new(Square::E2, Square::E4));
+        board.update(ChessMove::new(Square::D7, Square::D5));
+        board.update(ChessMove::new(Square::E4, Square::D5));
+        board.update(ChessMove::new(Square::D8, Square::D5));
+        board.update(ChessMove::new(Square::B1, Square::C3));
+        board.update(ChessMove::new(Square::D5, Square::D2));
+        board.update(ChessMove::new(Square::C1, Square::D2));
+
+        assert_eq!(
+            board.captured_fixed(Color::White),
+            [Piece::Pawn, Piece::Queen]
+        );
+        assert_eq!(
+            board.captured_fixed(Color::Black),
+            [Piece::Pawn, Piece::Pawn]
+        );
+        #[cfg(feature = "alloc")]
+        assert_eq!(
+            board.captured(Color::White),
+            vec![Piece::Pawn, Piece::Queen]
+        );
+        #[cfg(feature = "alloc")]
+        assert_eq!(board.captured(Color::Black), vec![Piece::Pawn, Piece::Pawn]);
+
+        let empty = Board::from_str("8/8/8/8/8/8/8/8 w - - 0 1").unwrap();
+        assert_eq!(empty.captured_fixed(Color::White).len(), 15);
+        assert!(!empty.captured_fixed(Color::Black).contains(&Piece::King));
+    }
+
     #[test]
     fn king_of() {
         let board = Board::default();
@@ -1305,6 +1610,20 @@ mod tests {
         todo!()
     }
 
+    #[test]
+    fn perft() {
+        let board = Board::default();
+        assert_eq!(board.perft(1), 20);
+        assert_eq!(board.perft(3), 8902);
+
+        // "Kiwipete": castles, en passant and pins
+        let board =
+            Board::from_str("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+        assert_eq!(board.perft(1), 48);
+        assert_eq!(board.perft(2), 2039);
+    }
+
     #[test]
     #[ignore]
     fn get_line() {
@@ -14,13 +14,13 @@ pub enum Align {
 }
 
 /// A struct of button for interact with the GUI.
-#[derive(Copy, Clone)]
+#[derive(Clone)]
 pub struct Button {
     /// The id is not unique, it's just a name to identify it.
     pub id: &'static str,
     enable: bool,
     rect: graphics::Rect,
-    image_path: Option<&'static str>,
+    image_path: Option<String>,
     color: graphics::Color,
     text: &'static str,
     align: Align,
@@ -67,9 +67,9 @@ impl Button {
     }
 
     /// Draw the image at the given path rather than a rectangle.
-    pub fn set_image(&mut self, path: Option<&'static str>) -> Self {
+    pub fn set_image(mut self, path: Option<String>) -> Self {
         self.image_path = path;
-        *self
+        self
     }
 
     /// Verify if a coordinate is in the button.
@@ -80,8 +80,8 @@ impl Button {
     /// Draw the button in the [`Context`].
     pub fn draw(&self, ctx: &mut Context, font_path: &str, font_scale: f32) -> GameResult {
         if self.enable {
-            if self.image_path.is_some() {
-                self.draw_image(ctx)?;
+            if let Some(path) = &self.image_path {
+                self.draw_image(ctx, path)?;
             } else {
                 self.draw_rect(ctx)?;
                 self.draw_text(ctx, font_path, font_scale)?;
@@ -119,8 +119,8 @@ impl Button {
     }
 
     /// Draw the button without text.
-    fn draw_image(&self, ctx: &mut Context) -> GameResult {
-        let image = graphics::Image::new(ctx, self.image_path.unwrap()).expect("Image load error");
+    fn draw_image(&self, ctx: &mut Context, path: &str) -> GameResult {
+        let image = graphics::Image::new(ctx, path).expect("Image load error");
         let image_scale = [
             self.rect.w / image.width() as f32,
             self.rect.h / image.height() as f32,
@@ -1,9 +1,14 @@
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+
+#[cfg(feature = "alloc")]
 use crate::Color;
 
 /// What castle rights does a particular player have?
 ///
 /// > rule: <https://en.wikipedia.org/wiki/Castling>
 #[derive(Debug, Clone, Copy, PartialOrd, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CastleRights {
     /// No right to Castle.
     NoRights,
@@ -56,6 +61,7 @@ impl CastleRights {
     /// assert_eq!(CastleRights::QueenSide.to_string(Color::Black), "q");
     /// assert_eq!(CastleRights::Both.to_string(Color::White), "KQ");
     /// ```
+    #[cfg(feature = "alloc")]
     pub fn to_string(&self, color: Color) -> String {
         let result = match *self {
             CastleRights::NoRights => "",
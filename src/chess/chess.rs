@@ -1,40 +1,15 @@
-use std::str::FromStr;
-
-use crate::{Board, ChessMove, Color, Square};
-
-/// The Result of the game.
-#[derive(Copy, Clone, Eq, PartialEq, Default, Debug)]
-pub enum GameState {
-    /// The game is still ongoing.
-    #[default]
-    Ongoing,
-    /// A player is checkmates.
-    Checkmates(Color),
-    /// Draw by Stalemate.
-    Stalemate,
-    /// Draw by request accepted (ie. Mutual Agreement).
-    DrawAccepted,
-    /// Draw declared by a player.
-    DrawDeclared,
-    /// The [`Color`] has resigns.
-    Resigns(Color),
-}
-
-impl GameState {
-    /// Verify if the game is ongoing.
-    pub fn is_ongoing(&self) -> bool {
-        matches!(self, GameState::Ongoing)
-    }
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::str::FromStr;
+use core::time::Duration;
 
-    /// Verify if the game is finish.
-    pub fn is_finish(&self) -> bool {
-        !matches!(self, GameState::Ongoing)
-    }
-}
+use crate::{
+    Annotations, Board, ChessMove, Clock, Color, GameState, Level, Square, TimeControl, NUM_COLORS,
+};
 
 /// A Standard Chess game.
-///
-/// TODO: Add a timer for each player.
 #[derive(Clone, Eq, PartialEq, Default, Debug)]
 pub struct Chess {
     pub(crate) board: Board,
@@ -42,6 +17,12 @@ pub struct Chess {
     pub(crate) offer_draw: bool,
     pub(crate) state: GameState,
     pub(crate) history: Vec<String>,
+    pub(crate) moves: Vec<ChessMove>,
+    pub(crate) clock: Option<Clock>,
+    /// The arrows and circles drawn on the positions, by ply (0 for the start position).
+    pub(crate) annotations: BTreeMap<usize, Annotations>,
+    /// The strength level of the built-in engine playing each color (if any).
+    pub(crate) levels: [Option<Level>; NUM_COLORS],
 }
 
 impl Chess {
@@ -52,7 +33,128 @@ impl Chess {
             square_focused: None,
             offer_draw: false,
             history: vec![],
+            moves: vec![],
             state: GameState::Ongoing,
+            clock: None,
+            annotations: BTreeMap::new(),
+            levels: [None; NUM_COLORS],
+        }
+    }
+
+    /// Create a new instance of Chess with a [`Clock`].
+    pub fn with_time_control(board: Board, time_control: TimeControl) -> Self {
+        Chess {
+            clock: Some(Clock::new(time_control)),
+            ..Chess::new(board)
+        }
+    }
+
+    /// Get the [`Board`] of the game.
+    pub fn board(&self) -> Board {
+        self.board
+    }
+
+    /// Get the [`Board`] at the beginning of the game.
+    pub fn start_board(&self) -> Board {
+        match self.history.first() {
+            Some(fen) => Board::from_str(fen).expect("valid fen from history"),
+            None => self.board,
+        }
+    }
+
+    /// Get the [`Board`] after a number of moves (0 for the start position).
+    ///
+    /// Return [`None`] if less moves were played.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::{Board, Chess, Square};
+    ///
+    /// let mut chess = Chess::default();
+    /// chess.play(Square::E2, Square::E4);
+    /// chess.play(Square::E7, Square::E5);
+    ///
+    /// assert_eq!(chess.board_at(0), Some(Board::default()));
+    /// assert_eq!(chess.board_at(2), Some(chess.board()));
+    /// assert_eq!(chess.board_at(3), None);
+    /// ```
+    pub fn board_at(&self, ply: usize) -> Option<Board> {
+        match self.history.get(ply) {
+            Some(fen) => Some(Board::from_str(fen).expect("valid fen from history")),
+            None if ply == self.moves.len() => Some(self.board),
+            None => None,
+        }
+    }
+
+    /// Get the arrows and circles drawn on the position after a number of moves (0 for the
+    /// start position).
+    pub fn annotations(&self, ply: usize) -> Option<&Annotations> {
+        self.annotations.get(&ply)
+    }
+
+    /// Set the arrows and circles drawn on the position after a number of moves, they are
+    /// removed when the moves are undone.
+    pub fn set_annotations(&mut self, ply: usize, annotations: Annotations) {
+        if annotations.is_empty() {
+            self.annotations.remove(&ply);
+        } else {
+            self.annotations.insert(ply, annotations);
+        }
+    }
+
+    /// Get the strength [`Level`] of the engine playing a color (if exist).
+    pub fn level(&self, color: Color) -> Option<Level> {
+        self.levels[color as usize]
+    }
+
+    /// Set the strength [`Level`] of the engine playing a color, it is saved in the tags of
+    /// the PGN (see [`Pgn::from_chess`][crate::Pgn::from_chess]).
+    ///
+    /// [`None`] for a human or another engine.
+    pub fn set_level(&mut self, color: Color, level: Option<Level>) {
+        self.levels[color as usize] = level;
+    }
+
+    /// Get the [`Clock`] of the game (if exist).
+    pub fn clock(&self) -> Option<Clock> {
+        self.clock
+    }
+
+    /// Set the [`TimeControl`] of the game, the clock restart from the initial time.
+    ///
+    /// [`None`] removes the clock.
+    pub fn set_time_control(&mut self, time_control: Option<TimeControl>) {
+        self.clock = time_control.map(Clock::new);
+    }
+
+    /// Let the time pass for the player who has to play.
+    ///
+    /// The clock starts after the first move, and the game ends when a flag falls.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use std::time::Duration;
+    /// use chess::{Board, Chess, Color, GameState, Square, TimeControl};
+    ///
+    /// let time_control = TimeControl::from_str("1+0").unwrap();
+    /// let mut chess = Chess::with_time_control(Board::default(), time_control);
+    /// chess.play(Square::E2, Square::E4);
+    /// chess.tick(Duration::from_secs(61));
+    ///
+    /// assert_eq!(chess.state(), GameState::Timeout(Color::Black));
+    /// ```
+    pub fn tick(&mut self, delta: Duration) {
+        if self.state.is_finish() || self.moves.is_empty() {
+            return;
+        }
+        if let Some(clock) = &mut self.clock {
+            let side = self.board.side_to_move();
+            if clock.consume(side, delta) {
+                self.state = GameState::Timeout(side);
+            }
         }
     }
 
@@ -63,6 +165,30 @@ impl Chess {
         self.history.clone()
     }
 
+    /// Get the moves played since the beginning of the game.
+    pub fn moves(&self) -> Vec<ChessMove> {
+        self.moves.clone()
+    }
+
+    /// Get the last move played (if exist).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::{Chess, ChessMove, Square};
+    /// let mut chess = Chess::default();
+    /// assert_eq!(chess.last_move(), None);
+    ///
+    /// chess.play(Square::E2, Square::E4);
+    /// assert_eq!(chess.last_move(), Some(ChessMove::new(Square::E2, Square::E4)));
+    ///
+    /// chess.undo();
+    /// assert_eq!(chess.last_move(), None);
+    /// ```
+    pub fn last_move(&self) -> Option<ChessMove> {
+        self.moves.last().copied()
+    }
+
     /// Go back one step in history.
     ///
     /// If the history is empty, reset the board to it's [`default`][Board::default] value.
@@ -81,16 +207,21 @@ impl Chess {
     pub fn undo(&mut self) {
         if let Some(fen) = self.history.pop() {
             self.board = Board::from_str(fen.as_str()).expect("valid fen from history");
+            self.moves.pop();
+            self.annotations.split_off(&(self.moves.len() + 1));
         }
     }
 
-    /// Reset the Game (board and history).
+    /// Reset the Game (board, history and clock), the levels of the players are kept.
     pub fn reset(&mut self) {
         self.board = Board::default();
         self.offer_draw = false;
         self.square_focused = None;
         self.history = vec![];
+        self.moves = vec![];
         self.state = GameState::Ongoing;
+        self.clock = self.clock.map(|clock| Clock::new(clock.time_control()));
+        self.annotations.clear();
     }
 
     /// Return the [`State`][GameState] of the Game.
@@ -103,7 +234,12 @@ impl Chess {
         let m = ChessMove::new(from, to);
         if self.board.is_legal(m) {
             self.history.push(self.board.to_string());
+            let side = self.board.side_to_move();
             self.board.update(m);
+            self.moves.push(m);
+            if let Some(clock) = &mut self.clock {
+                clock.add_increment(side);
+            }
             if self.offer_draw {
                 self.offer_draw = false;
             }
@@ -1,62 +1,1216 @@
 use std::cmp::max;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
 
 use ggez::event::{KeyCode, KeyMods, MouseButton};
 use ggez::{event, graphics, Context, GameError, GameResult};
-use log::{debug, info};
+use log::{debug, error, info, warn};
 use unicode_segmentation::UnicodeSegmentation;
 
 use crate::{
-    Align, Button, Chess, GameState, Square, Theme, ALL_SQUARES, BOARD_CELL_PX_SIZE, BOARD_PX_SIZE,
-    BOARD_SIZE, INDEX_THEME, NUM_THEMES, SIDE_SCREEN_PX_SIZE, THEMES,
+    Align, Analyser, Analysis, AnalysisReport, Animation, AnnotationColor, Arrow, Board,
+    BoardEditor, Button, CastleRights, Chess, ChessMove, Circle, Color, Engine, GameReview,
+    GameState, Layout, Message, MoveClass, Pgn, Piece, Player, Premoves, Puzzle, PuzzleMove,
+    PuzzleStats, PuzzleTrainer, SaveError, Session, Settings, SettingsEntry, SharedBot, Spectator,
+    Square, Theme, ThemeError, ThemeRegistry, UciEngine, UciError, ALL_COLORS, ALL_MOVE_CLASSES,
+    ALL_SETTINGS_ENTRIES, ALL_SQUARES, ANALYSIS_DEPTH, ANIMATION_DURATION, BOARD_SIZE, NUM_COLORS,
+    REVIEW_MAX_SCORE, REVIEW_MOVETIME,
 };
 
+/// The number of half-moves of the lines shown in the analysis mode.
+const ANALYSIS_LINE_LENGTH: usize = 8;
+
+/// The row of the resume prompt that resumes the last game.
+const RESUME_ROW: usize = 3;
+
+/// The row of the resume prompt that starts a new game.
+const NEW_GAME_ROW: usize = 4;
+
+/// The first row of the evaluation graph on the game report.
+const REPORT_GRAPH_ROW: usize = 5;
+
+/// The number of rows of the evaluation graph on the game report.
+const REPORT_GRAPH_ROWS: usize = 4;
+
+/// The row of the game report that exports the annotated game.
+const REPORT_EXPORT_ROW: usize = 10;
+
+/// A hint of the best move for the local player.
+#[derive(Debug)]
+enum Hint {
+    /// The engine searches the best move of the position in another thread.
+    Searching(Board, mpsc::Receiver<Option<ChessMove>>),
+    /// The piece to move is highlighted, and its destination once `revealed`.
+    Found {
+        board: Board,
+        m: ChessMove,
+        revealed: bool,
+    },
+}
+
+impl Hint {
+    /// Get the position of the hint.
+    fn board(&self) -> Board {
+        match self {
+            Hint::Searching(board, _) | Hint::Found { board, .. } => *board,
+        }
+    }
+}
+
+/// A message of the thread that reviews the game.
+#[derive(Debug)]
+enum ReviewUpdate {
+    /// The number of positions searched.
+    Progress(usize),
+    /// The review is finished.
+    Done(GameReview),
+    /// The engine failed.
+    Failed(String),
+}
+
+/// The review of the game shown on the game report.
+#[derive(Debug)]
+enum ReviewState {
+    /// The positions are searched in another thread: the number of positions searched and
+    /// the messages of the thread.
+    Running(usize, mpsc::Receiver<ReviewUpdate>),
+    /// The review is finished.
+    Done(GameReview),
+    /// The engine failed.
+    Failed(String),
+}
+
+/// A cell of the board editor on the side screen.
+#[derive(Copy, Clone, Debug)]
+enum EditorCell {
+    /// Put this piece on the board.
+    Piece(Piece, Color),
+    /// Empty the squares of the board.
+    Eraser,
+    /// Give or remove a castle right.
+    Castle(Color, CastleRights),
+}
+
+/// The cells of the board editor with their row and column: the pieces of each color,
+/// then the castle rights (as in FEN) and the eraser.
+const EDITOR_CELLS: [(usize, usize, EditorCell); 17] = [
+    (0, 0, EditorCell::Piece(Piece::King, Color::White)),
+    (0, 1, EditorCell::Piece(Piece::Queen, Color::White)),
+    (0, 2, EditorCell::Piece(Piece::Rook, Color::White)),
+    (0, 3, EditorCell::Piece(Piece::Bishop, Color::White)),
+    (0, 4, EditorCell::Piece(Piece::Knight, Color::White)),
+    (0, 5, EditorCell::Piece(Piece::Pawn, Color::White)),
+    (1, 0, EditorCell::Piece(Piece::King, Color::Black)),
+    (1, 1, EditorCell::Piece(Piece::Queen, Color::Black)),
+    (1, 2, EditorCell::Piece(Piece::Rook, Color::Black)),
+    (1, 3, EditorCell::Piece(Piece::Bishop, Color::Black)),
+    (1, 4, EditorCell::Piece(Piece::Knight, Color::Black)),
+    (1, 5, EditorCell::Piece(Piece::Pawn, Color::Black)),
+    (
+        2,
+        0,
+        EditorCell::Castle(Color::White, CastleRights::KingSide),
+    ),
+    (
+        2,
+        1,
+        EditorCell::Castle(Color::White, CastleRights::QueenSide),
+    ),
+    (
+        2,
+        2,
+        EditorCell::Castle(Color::Black, CastleRights::KingSide),
+    ),
+    (
+        2,
+        3,
+        EditorCell::Castle(Color::Black, CastleRights::QueenSide),
+    ),
+    (2, 5, EditorCell::Eraser),
+];
+
 /// GUI for the [`Chess`] game.
 #[derive(Debug)]
 pub struct ChessGui {
     pub(crate) chess: Chess,
-    theme: Theme,
+    themes: ThemeRegistry,
     buttons: Vec<Button>,
+    square_hovered: Option<Square>,
+    animation: Option<Animation>,
+    animation_duration: Duration,
+    layout: Layout,
+    settings: Settings,
+    settings_path: Option<PathBuf>,
+    settings_open: bool,
+    /// Who plays each side.
+    players: [Player; NUM_COLORS],
+    bots: [Option<SharedBot>; NUM_COLORS],
+    thinking: Option<mpsc::Receiver<Option<ChessMove>>>,
+    autosave_path: Option<PathBuf>,
+    autosaved: (usize, GameState),
+    saved_game_path: Option<PathBuf>,
+    resume: Option<Chess>,
+    network: Option<Session>,
+    spectator: Option<Spectator>,
+    /// The moves in SAN, shown to the spectators.
+    move_list: Vec<String>,
+    /// The move being typed (in SAN or UCI).
+    move_input: String,
+    /// The square selected with the arrow keys (shown once an arrow is pressed).
+    cursor: Option<Square>,
+    /// The position being set up, the game is paused while the editor is open.
+    editor: Option<BoardEditor>,
+    /// The last error of the editor (e.g. an invalid FEN pasted).
+    editor_error: Option<String>,
+    /// The moves queued by the local player during the turn of the opponent.
+    premoves: Premoves,
+    /// The square of the premove being selected.
+    premove_focused: Option<Square>,
+    /// The square where the right button was pressed to draw an arrow (or a circle).
+    annotation_start: Option<(Square, AnnotationColor)>,
+    /// The number of moves of the position shown while browsing the history (the current
+    /// position if [`None`]).
+    view: Option<usize>,
+    /// The engine of the analysis mode, it follows the position shown.
+    analyser: Option<Analyser>,
+    /// Whether the game report is shown over the board.
+    report_open: bool,
+    /// The review of the game report, with the number of moves reviewed.
+    review: Option<(usize, ReviewState)>,
+    /// The file where the game report exports the annotated game.
+    review_path: Option<PathBuf>,
+    /// The hint of the local player, forgotten once the position changes.
+    hint: Option<Hint>,
+    /// The number of hints given in the game.
+    hints_used: usize,
+    /// The puzzles being solved, the game is paused meanwhile.
+    puzzles: Option<PuzzleTrainer>,
+    /// The game paused during the puzzles.
+    paused_game: Option<Chess>,
+    /// The file where the statistics of the puzzles are saved.
+    puzzle_stats_path: Option<PathBuf>,
 }
 
 impl ChessGui {
     /// Create a new instance of ChessGui.
-    pub fn new(chess: Chess, theme: Theme, buttons: Vec<Button>) -> Self {
+    pub fn new(chess: Chess, themes: ThemeRegistry, buttons: Vec<Button>) -> Self {
         ChessGui {
             chess,
-            theme,
+            themes,
             buttons,
+            square_hovered: None,
+            animation: None,
+            animation_duration: ANIMATION_DURATION,
+            layout: Layout::default(),
+            settings: Settings::default(),
+            settings_path: None,
+            settings_open: false,
+            players: [Player::Human, Player::Human],
+            bots: [None, None],
+            thinking: None,
+            autosave_path: None,
+            autosaved: (0, GameState::Ongoing),
+            saved_game_path: Chess::saved_game_path(),
+            resume: None,
+            network: None,
+            spectator: None,
+            move_list: vec![],
+            move_input: String::new(),
+            cursor: None,
+            editor: None,
+            editor_error: None,
+            premoves: Premoves::default(),
+            premove_focused: None,
+            annotation_start: None,
+            view: None,
+            analyser: None,
+            report_open: false,
+            review: None,
+            review_path: Chess::review_path(),
+            hint: None,
+            hints_used: 0,
+            puzzles: None,
+            paused_game: None,
+            puzzle_stats_path: PuzzleStats::default_path(),
+        }
+    }
+
+    /// Replace the game (e.g. to start from a position).
+    pub fn set_chess(&mut self, chess: Chess) {
+        self.chess = chess;
+        for color in ALL_COLORS {
+            self.chess
+                .set_level(color, self.players[color.to_index()].level());
+        }
+        self.view = None;
+        self.animation = None;
+        self.thinking = None;
+        self.hint = None;
+        self.hints_used = 0;
+        self.clear_premoves();
+        self.buttons.clear();
+        self.init_buttons();
+    }
+
+    /// Save the game in a file after every move (and when the window is closed, to keep
+    /// the clock).
+    pub fn enable_autosave(&mut self, path: PathBuf) {
+        self.autosave_path = Some(path);
+        self.autosaved = (self.chess.moves.len(), self.chess.state);
+    }
+
+    /// Get the path of the autosave file (if the autosave is enabled).
+    pub fn autosave_path(&self) -> Option<&Path> {
+        self.autosave_path.as_deref()
+    }
+
+    /// Save the game in the autosave file (if the autosave is enabled).
+    ///
+    /// A spectator doesn't save, it could follow the autosave file of another player.
+    fn autosave(&mut self) {
+        self.autosaved = (self.chess.moves.len(), self.chess.state);
+        // The puzzles are not a game
+        if self.is_read_only() || self.puzzles.is_some() {
+            return;
+        }
+        if let Some(path) = &self.autosave_path {
+            if let Err(error) = self.chess.save(path) {
+                warn!("{error}");
+            }
+        }
+    }
+
+    /// Propose to resume the game saved in a file (e.g. the autosave of the last run).
+    ///
+    /// Only an unfinished game is proposed, the user chooses to resume it or to keep the
+    /// current game. Return true if the game is proposed.
+    pub fn propose_resume<P: AsRef<Path>>(&mut self, path: P) -> bool {
+        match Chess::load(path) {
+            Ok(chess) if chess.state.is_ongoing() && !chess.moves.is_empty() => {
+                self.resume = Some(chess);
+                true
+            }
+            Ok(_) => false,
+            Err(SaveError::Io { source, .. }) if source.kind() == std::io::ErrorKind::NotFound => {
+                false
+            }
+            Err(error) => {
+                warn!("{error}");
+                false
+            }
+        }
+    }
+
+    /// Answer the resume prompt: resume the proposed game or keep the current one.
+    pub fn answer_resume(&mut self, resume: bool) {
+        if let Some(chess) = self.resume.take() {
+            if resume {
+                info!("Resume the last game");
+                self.set_chess(chess);
+            }
+        }
+    }
+
+    /// Save the game in the file of the Save and Load buttons.
+    pub fn save_game(&self) {
+        match &self.saved_game_path {
+            Some(path) => match self.chess.save(path) {
+                Ok(()) => info!("Game saved in {}", path.display()),
+                Err(error) => warn!("{error}"),
+            },
+            None => warn!("No data directory to save the game"),
+        }
+    }
+
+    /// Load the game of the Save and Load buttons, it replaces the current game.
+    pub fn load_game(&mut self) {
+        if self.is_read_only() {
+            return;
+        }
+        if self.is_network_guest() {
+            warn!("Only the host can load a network game");
+            return;
+        }
+        match &self.saved_game_path {
+            Some(path) => match Chess::load(path) {
+                Ok(chess) => {
+                    info!("Game loaded from {}", path.display());
+                    self.set_chess(chess);
+                    self.sync_network();
+                }
+                Err(error) => warn!("{error}"),
+            },
+            None => warn!("No data directory to load the game"),
+        }
+    }
+
+    /// Set the file used by the Save and Load buttons
+    /// (see [`Chess::saved_game_path`] for the default one).
+    pub fn set_saved_game_path(&mut self, path: PathBuf) {
+        self.saved_game_path = Some(path);
+    }
+
+    /// Play against a player on the network, the other side can't be played locally.
+    ///
+    /// The host can undo, reset or load the game (the guest receives the whole game),
+    /// the guest can't.
+    pub fn set_network(&mut self, session: Session) {
+        if let Some(color) = session.color() {
+            self.layout.set_flipped(color == Color::Black);
+        }
+        // The engine can't help a player of a network game
+        self.analyser = None;
+        self.network = Some(session);
+    }
+
+    /// Get the network session (if the game is played on the network).
+    pub fn network(&self) -> Option<&Session> {
+        self.network.as_ref()
+    }
+
+    /// Verify if the game is played on the network and the local player is the guest.
+    fn is_network_guest(&self) -> bool {
+        matches!(&self.network, Some(session) if !session.is_host())
+    }
+
+    /// Verify if the local player can't play now because of the network: the other player
+    /// is not connected or it is his turn.
+    fn is_remote_turn(&self) -> bool {
+        matches!(&self.network, Some(session) if !session.is_local_turn(&self.chess))
+    }
+
+    /// Send a message to the other player (if the game is played on the network).
+    fn send(&mut self, message: Message) {
+        if let Some(session) = &mut self.network {
+            session.send(message);
+        }
+    }
+
+    /// Send the whole game to the guest (if the local player hosts a network game).
+    fn sync_network(&mut self) {
+        if let Some(session) = &mut self.network {
+            session.sync(&self.chess);
         }
     }
 
+    /// Only show a game played elsewhere: the board, the moves and the clocks are updated
+    /// by the [`Spectator`] and the game can't be played.
+    pub fn set_spectator(&mut self, spectator: Spectator) {
+        self.spectator = Some(spectator);
+        self.buttons.clear();
+        self.init_buttons();
+    }
+
+    /// Verify if the game is only shown (see [`ChessGui::set_spectator`]).
+    pub fn is_read_only(&self) -> bool {
+        self.spectator.is_some()
+    }
+
+    /// Show the last version of the game received by the [`Spectator`] (if any).
+    fn update_spectator(&mut self) {
+        let Some(chess) = self.spectator.as_mut().and_then(Spectator::update) else {
+            return;
+        };
+        let before = self.chess.board;
+        self.chess = chess;
+        self.animate_from(before);
+        let mut move_list = vec![];
+        for (index, pair) in Pgn::from_chess(&self.chess).moves().chunks(2).enumerate() {
+            move_list.push(format!("{}. {}", index + 1, pair.join(" ")));
+        }
+        self.move_list = move_list;
+    }
+
+    /// Set who plays a side, an UCI engine is started immediately.
+    ///
+    /// The [`Level`][crate::Level] of the built-in engine is recorded in the game.
+    pub fn set_player(&mut self, color: Color, player: &Player) -> Result<(), UciError> {
+        self.bots[color.to_index()] = player.bot()?.map(|bot| Arc::new(Mutex::new(bot)));
+        self.players[color.to_index()] = player.clone();
+        self.chess.set_level(color, player.level());
+        self.thinking = None;
+        Ok(())
+    }
+
+    /// Verify if the player who has to play is a [`Bot`][crate::Bot], or the opponent of a
+    /// puzzle.
+    fn is_bot_turn(&self) -> bool {
+        let side = self.chess.board.side_to_move();
+        match &self.puzzles {
+            Some(trainer) => side != trainer.puzzle().color(),
+            None => self.bots[side.to_index()].is_some(),
+        }
+    }
+
+    /// Ask the bot to play if it is its turn, and play its move once found.
+    ///
+    /// The bot thinks in another thread to keep the GUI responsive. It doesn't wait for the
+    /// animation of the previous move, its move finishes the animation at once.
+    fn update_bots(&mut self) {
+        let side = self.chess.board.side_to_move();
+        if let Some(receiver) = &self.thinking {
+            match receiver.try_recv() {
+                Ok(Some(m)) => {
+                    self.thinking = None;
+                    // The move replaces the animation still running
+                    self.animation = None;
+                    if self.chess.state.is_ongoing() {
+                        self.play(m.from, m.to);
+                    }
+                }
+                Ok(None) | Err(mpsc::TryRecvError::Disconnected) => {
+                    // Don't ask again, the side is given back to the user
+                    error!("The bot playing {side:?} can't find a move");
+                    self.thinking = None;
+                    self.bots[side.to_index()] = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => {}
+            }
+        } else if self.chess.state.is_ongoing() {
+            if let Some(bot) = &self.bots[side.to_index()] {
+                let bot = Arc::clone(bot);
+                let board = self.chess.board;
+                let (sender, receiver) = mpsc::channel();
+                thread::spawn(move || {
+                    let mut bot = bot.lock().expect("bot poisoned");
+                    // The GUI may have stopped waiting for this move
+                    let _ = sender.send(bot.choose_move(&board));
+                });
+                self.thinking = Some(receiver);
+            }
+        }
+    }
+
+    /// Get the color of the local player if a premove can be queued now: during the turn of
+    /// the bot or of the remote player.
+    fn premove_color(&self) -> Option<Color> {
+        if self.is_read_only()
+            || self.editor.is_some()
+            || self.puzzles.is_some()
+            || !self.chess.state.is_ongoing()
+        {
+            return None;
+        }
+        let side = self.chess.board.side_to_move();
+        let color = match &self.network {
+            Some(session) => session.color()?,
+            None if self.is_bot_turn() && self.bots[(!side).to_index()].is_none() => !side,
+            None => return None,
+        };
+        (color != side).then_some(color)
+    }
+
+    /// Play the first premove once the turn of the local player comes back, all the premoves
+    /// are cancelled if it is not legal.
+    fn play_premove(&mut self) {
+        if self.premove_color().is_some() || !self.chess.state.is_ongoing() {
+            return;
+        }
+        if self.is_read_only() || self.is_bot_turn() || self.is_remote_turn() {
+            return;
+        }
+        // The piece selected for an unfinished premove stays selected
+        if let Some(square) = self.premove_focused.take() {
+            let side = self.chess.board.side_to_move();
+            if self.premoves.is_empty() && self.chess.board.color_on_is(square, side) {
+                self.chess.square_focused = Some(square);
+            }
+        }
+        if self.premoves.is_empty() {
+            return;
+        }
+        match self.premoves.pop(&self.chess.board) {
+            Some(m) => {
+                info!("Play the premove: {m}");
+                self.play(m.from, m.to);
+            }
+            None => {
+                warn!("Illegal premove, the premoves are cancelled");
+                self.premove_focused = None;
+            }
+        }
+    }
+
+    /// Cancel all the premoves of the local player.
+    pub fn clear_premoves(&mut self) {
+        self.premoves.clear();
+        self.premove_focused = None;
+    }
+
     /// Reset The chess game and buttons but not the theme.
+    ///
+    /// The new game uses the time control of the [`Settings`]. In the puzzles, the puzzle is
+    /// tried again.
     pub fn reset(&mut self) {
+        if self.is_read_only() {
+            return;
+        }
+        if self.puzzles.is_some() {
+            self.retry_puzzle();
+            return;
+        }
+        if self.is_network_guest() {
+            warn!("Only the host can reset a network game");
+            return;
+        }
         self.chess.reset();
+        self.chess.set_time_control(self.settings.time_control());
+        self.animation = None;
+        self.thinking = None;
+        self.hint = None;
+        self.hints_used = 0;
+        self.clear_premoves();
         self.buttons.clear();
         self.init_buttons();
+        self.sync_network();
+    }
+
+    /// Get the theme in use.
+    pub fn theme(&self) -> &Theme {
+        self.themes.current()
+    }
+
+    /// Get all the themes available.
+    pub fn themes(&self) -> &ThemeRegistry {
+        &self.themes
     }
 
-    /// Set the theme for the GUI.
+    /// Set the theme for the GUI, it replaces the known theme with the same name.
     ///
     /// # Examples
     ///
     /// ```
-    /// use chess::{ChessGui, THEME_SANDCASTLE};
+    /// use chess::{ChessGui, Theme};
     ///
     /// let mut game = ChessGui::default();
-    /// game.set_theme(THEME_SANDCASTLE);
+    /// game.set_theme(Theme {
+    ///     name: "Big font".to_string(),
+    ///     font_scale: 30.0,
+    ///     ..Default::default()
+    /// });
+    /// assert_eq!(game.theme().name, "Big font");
     /// ```
     pub fn set_theme(&mut self, theme: Theme) {
-        self.theme = theme;
+        let name = theme.name.clone();
+        self.themes.add(theme);
+        self.select_theme(&name).expect("theme just added");
+    }
+
+    /// Use the known theme with the given name.
+    pub fn select_theme(&mut self, name: &str) -> Result<(), ThemeError> {
+        self.themes.select(name)?;
+        Ok(())
+    }
+
+    /// Set the theme to the next one for the GUI, the choice is saved in the [`Settings`].
+    pub fn next_theme(&mut self) {
+        self.change_setting(SettingsEntry::Theme);
+    }
+
+    /// Get the settings in use.
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Apply the settings to the GUI.
+    ///
+    /// The time control is used for the next game, or immediately if no move has been played.
+    /// An unknown theme is logged and the current theme is kept. A new engine level is given
+    /// to the players with a [`Level`][crate::Level].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::{ChessGui, Settings};
+    ///
+    /// let mut game = ChessGui::default();
+    /// game.apply_settings(Settings {
+    ///     time_control: "5+3".to_string(),
+    ///     ..Default::default()
+    /// });
+    /// assert!(game.settings().time_control().is_some());
+    /// ```
+    pub fn apply_settings(&mut self, settings: Settings) {
+        if let Err(error) = self.themes.select(&settings.theme) {
+            warn!("{error}");
+        }
+        if self.chess.moves.is_empty() {
+            self.chess.set_time_control(settings.time_control());
+        }
+        let restart_analysis = self.analyser.is_some()
+            && (settings.analysis_engine != self.settings.analysis_engine
+                || settings.analysis_lines != self.settings.analysis_lines);
+        let level_changed = settings.engine_level != self.settings.engine_level;
+        self.settings = settings;
+        self.update_flipped();
+        if level_changed {
+            let level = Player::Level(self.settings.engine_level());
+            for color in ALL_COLORS {
+                if self.players[color.to_index()].level().is_some() {
+                    if let Err(error) = self.set_player(color, &level) {
+                        warn!("{error}");
+                    }
+                }
+            }
+        }
+        if restart_analysis {
+            self.analyser = None;
+            self.toggle_analysis();
+        }
+        self.buttons.clear();
+        self.init_buttons();
+    }
+
+    /// Load the settings from a file and apply them, they are saved in this file on every change.
+    ///
+    /// An invalid settings file is logged and the default settings are used.
+    pub fn load_settings(&mut self, path: PathBuf) {
+        let settings = Settings::load(&path).unwrap_or_else(|error| {
+            warn!("{error}");
+            Settings::default()
+        });
+        self.settings_path = Some(path);
+        self.apply_settings(settings);
+    }
+
+    /// Get the path of the settings file (if the settings were loaded from a file).
+    pub fn settings_path(&self) -> Option<&Path> {
+        self.settings_path.as_deref()
+    }
+
+    /// Save the settings in the file they were loaded from (if exist).
+    fn save_settings(&self) {
+        if let Some(path) = &self.settings_path {
+            if let Err(error) = self.settings.save(path) {
+                warn!("{error}");
+            }
+        }
+    }
+
+    /// Open or close the settings screen.
+    pub fn toggle_settings_screen(&mut self) {
+        self.settings_open = !self.settings_open;
+    }
+
+    /// Show the board from the other side, the choice is saved in the [`Settings`].
+    pub fn flip_board(&mut self) {
+        self.change_setting(SettingsEntry::BoardFlipped);
+    }
+
+    /// Change an entry of the settings, apply and save them.
+    fn change_setting(&mut self, entry: SettingsEntry) {
+        let mut settings = self.settings.clone();
+        match entry {
+            SettingsEntry::Theme => {
+                settings.theme = self.themes.select_next().name.clone();
+            }
+            _ => settings.change(entry),
+        }
+        self.apply_settings(settings);
+        self.save_settings();
+    }
+
+    /// Compute the layout of the GUI for the new size of the window.
+    ///
+    /// The buttons are recreated at their new position.
+    pub fn resize(&mut self, width: f32, height: f32) {
+        self.layout = Layout::new(width, height);
+        self.layout.set_flipped(self.settings.board_flipped);
+        self.buttons.clear();
+        self.init_buttons();
+    }
+
+    /// Set the duration of the pieces animations.
+    ///
+    /// A zero [`Duration`] disables the animations.
+    pub fn set_animation_duration(&mut self, duration: Duration) {
+        self.animation_duration = duration;
+    }
+
+    /// Play a move and animate it.
+    ///
+    /// In a network game, the move and the clock are sent to the other player. In the puzzles,
+    /// the move is checked and played only if it is the solution.
+    pub fn play(&mut self, from: Square, to: Square) {
+        if self.puzzles.is_some() {
+            self.play_puzzle(ChessMove::new(from, to));
+            return;
+        }
+        let before = self.chess.board;
+        self.chess.play(from, to);
+        self.animate_from(before);
+        if before != self.chess.board {
+            self.send(Message::Move(ChessMove::new(from, to)));
+            if let Some(clock) = self.chess.clock() {
+                self.send(Message::Clock {
+                    white: clock.remaining(Color::White),
+                    black: clock.remaining(Color::Black),
+                });
+            }
+        }
+    }
+
+    /// The local player offers a draw.
+    pub fn offer_draw(&mut self) {
+        self.chess.offer_draw();
+        self.send(Message::OfferDraw);
+    }
+
+    /// The local player accepts the draw offer.
+    pub fn accept_draw(&mut self) {
+        self.chess.accept_draw();
+        self.send(Message::AcceptDraw);
+    }
+
+    /// The local player declares a draw by 3-fold repetition or 50-move rule.
+    pub fn declare_draw(&mut self) {
+        self.chess.declare_draw();
+        self.send(Message::DeclareDraw);
+    }
+
+    /// The local player resigns: the player of the network game, or the side to move.
+    pub fn resign(&mut self) {
+        let color = match &self.network {
+            Some(session) => session.color(),
+            None => None,
+        };
+        let color = color.unwrap_or_else(|| self.chess.board.side_to_move());
+        self.chess.resign(color);
+        self.send(Message::Resign);
+    }
+
+    /// Undo the last move and animate it.
+    ///
+    /// Against a bot, the moves are undone until it is the turn of the user.
+    pub fn undo(&mut self) {
+        if self.is_read_only() || self.puzzles.is_some() {
+            return;
+        }
+        if self.is_network_guest() {
+            warn!("Only the host can undo a move of a network game");
+            return;
+        }
+        let before = self.chess.board;
+        self.thinking = None;
+        self.clear_premoves();
+        self.chess.undo();
+        while self.is_bot_turn() && !self.chess.moves.is_empty() {
+            self.chess.undo();
+        }
+        self.animate_from(before);
+        self.sync_network();
+    }
+
+    /// Start the animation from the given [`Board`] to the current one.
+    ///
+    /// Nothing is animated if the board didn't change or if the animations are disabled.
+    fn animate_from(&mut self, before: Board) {
+        // The position shown doesn't change while browsing the history
+        if self.animation_duration.is_zero() || before == self.chess.board || self.view.is_some() {
+            return;
+        }
+        self.animation = Some(Animation::between(
+            &before,
+            &self.chess.board,
+            self.animation_duration,
+        ));
+    }
+
+    /// Get the number of moves of the position shown on the board.
+    fn displayed_ply(&self) -> usize {
+        self.view.unwrap_or(self.chess.moves.len())
+    }
+
+    /// Get the position shown on the board: the current one, or a position of the history.
+    fn displayed_board(&self) -> Board {
+        self.view
+            .and_then(|ply| self.chess.board_at(ply))
+            .unwrap_or(self.chess.board)
+    }
+
+    /// Show the position after a number of moves (0 for the start position) and animate it,
+    /// the game goes on meanwhile.
+    ///
+    /// The current position is shown again with the number of moves played (or more).
+    pub fn show_ply(&mut self, ply: usize) {
+        let before = self.displayed_board();
+        self.view = (ply < self.chess.moves.len()).then_some(ply);
+        self.chess.square_focused = None;
+        let after = self.displayed_board();
+        self.animation = (!self.animation_duration.is_zero() && before != after)
+            .then(|| Animation::between(&before, &after, self.animation_duration));
+    }
+
+    /// Start or stop the analysis mode: the engine of the [`Settings`] analyses the position
+    /// shown, even while browsing the history.
+    ///
+    /// The analysis is refused during a network game.
+    pub fn toggle_analysis(&mut self) {
+        if self.analyser.take().is_some() {
+            return;
+        }
+        if self.network.is_some() {
+            warn!("The analysis is not available during a network game");
+            return;
+        }
+        if self.puzzles.is_some() {
+            warn!("The analysis is not available during the puzzles");
+            return;
+        }
+        let player = self.settings.analysis_engine();
+        match Analyser::spawn(&player, self.settings.analysis_lines) {
+            Ok(analyser) => {
+                info!("Analysis with {}", analyser.name());
+                self.analyser = Some(analyser);
+            }
+            Err(error) => error!("{error}"),
+        }
+    }
+
+    /// Get the last analysis of the position shown (if the analysis mode is on).
+    pub fn analysis(&self) -> Option<&AnalysisReport> {
+        self.analyser.as_ref().and_then(Analyser::report)
+    }
+
+    /// Get the number of hints left in the game (see [`Settings::hints_per_game`]).
+    pub fn hints_left(&self) -> usize {
+        self.settings.hints_per_game.saturating_sub(self.hints_used)
+    }
+
+    /// Give a hint to the local player: the first request highlights the piece of the best
+    /// move found by the engine of the [`Settings`] (or by the analysis), the second one its
+    /// destination.
+    ///
+    /// Only the first request of a position uses a hint. The hints are refused during the
+    /// turn of a bot, in a network game and once the hints of the game are used.
+    pub fn hint(&mut self) {
+        if let Some(Hint::Found { revealed, .. }) = &mut self.hint {
+            *revealed = true;
+            return;
+        }
+        if self.hint.is_some()
+            || self.is_read_only()
+            || self.editor.is_some()
+            || self.puzzles.is_some()
+            || self.is_bot_turn()
+            || !self.chess.state.is_ongoing()
+        {
+            return;
+        }
+        if self.network.is_some() {
+            warn!("The hints are not available during a network game");
+            return;
+        }
+        if self.hints_left() == 0 {
+            warn!("No hint left in this game");
+            return;
+        }
+        self.hints_used += 1;
+        info!("Hint given, {} left", self.hints_left());
+        let board = self.chess.board;
+        let analysed = self
+            .analysis()
+            .filter(|report| report.board == board)
+            .and_then(|report| report.lines.first());
+        if let Some(line) = analysed {
+            self.hint = Some(Hint::Found {
+                board,
+                m: line.best_move,
+                revealed: false,
+            });
+            return;
+        }
+        let (sender, receiver) = mpsc::channel();
+        let player = self.settings.analysis_engine();
+        thread::spawn(move || {
+            let _ = sender.send(search_hint(&board, &player));
+        });
+        self.hint = Some(Hint::Searching(board, receiver));
+    }
+
+    /// Receive the move of the hint, without waiting. The hint is forgotten once the
+    /// position changes.
+    fn update_hint(&mut self) {
+        if let Some(Hint::Searching(board, receiver)) = &self.hint {
+            let board = *board;
+            match receiver.try_recv() {
+                Ok(Some(m)) => {
+                    self.hint = Some(Hint::Found {
+                        board,
+                        m,
+                        revealed: false,
+                    })
+                }
+                Ok(None) | Err(mpsc::TryRecvError::Disconnected) => self.hint = None,
+                Err(mpsc::TryRecvError::Empty) => {}
+            }
+        }
+        if self
+            .hint
+            .as_ref()
+            .is_some_and(|hint| hint.board() != self.chess.board)
+        {
+            self.hint = None;
+        }
+    }
+
+    /// Open or close the game report: the moves are reviewed by the engine of the
+    /// [`Settings`] in another thread (see [`GameReview`]), the review is kept until the
+    /// next move.
+    ///
+    /// The report is refused before the first move and during a network game in progress.
+    pub fn toggle_report(&mut self) {
+        if self.report_open {
+            self.report_open = false;
+            return;
+        }
+        if self.network.is_some() && self.chess.state.is_ongoing() {
+            warn!("The game report is not available during a network game");
+            return;
+        }
+        if self.chess.moves.is_empty() {
+            warn!("No move to review");
+            return;
+        }
+        self.report_open = true;
+        let reviewed = self.review.as_ref().map(|(ply, _)| *ply);
+        if reviewed != Some(self.chess.moves.len()) {
+            let (sender, receiver) = mpsc::channel();
+            let chess = self.chess.clone();
+            let player = self.settings.analysis_engine();
+            thread::spawn(move || {
+                let update = match review_game(&chess, &player, &sender) {
+                    Ok(review) => ReviewUpdate::Done(review),
+                    Err(error) => ReviewUpdate::Failed(error.to_string()),
+                };
+                let _ = sender.send(update);
+            });
+            self.review = Some((self.chess.moves.len(), ReviewState::Running(0, receiver)));
+        }
+    }
+
+    /// Receive the progress of the review of the game, without waiting.
+    fn update_review(&mut self) {
+        let Some((_, ReviewState::Running(searched, receiver))) = &mut self.review else {
+            return;
+        };
+        let mut finished = None;
+        for update in receiver.try_iter() {
+            match update {
+                ReviewUpdate::Progress(count) => *searched = count,
+                ReviewUpdate::Done(review) => finished = Some(ReviewState::Done(review)),
+                ReviewUpdate::Failed(error) => {
+                    error!("{error}");
+                    finished = Some(ReviewState::Failed(error));
+                }
+            }
+        }
+        if let (Some(state), Some((_, review))) = (finished, &mut self.review) {
+            *review = state;
+        }
     }
 
-    /// Set the theme to the next one for the GUI.
+    /// Get the review of the game report (once finished).
+    pub fn review(&self) -> Option<&GameReview> {
+        match &self.review {
+            Some((_, ReviewState::Done(review))) => Some(review),
+            _ => None,
+        }
+    }
+
+    /// Save the game annotated with its review in the file of the game report
+    /// (see [`Chess::save_review`]).
+    pub fn export_review(&self) {
+        let Some(review) = self.review() else {
+            warn!("The game is not reviewed yet");
+            return;
+        };
+        if review.moves().len() != self.chess.moves.len() {
+            warn!("Moves were played since the review, open the report again");
+            return;
+        }
+        match &self.review_path {
+            Some(path) => match self.chess.save_review(review, path) {
+                Ok(()) => info!("Annotated game saved in {}", path.display()),
+                Err(error) => warn!("{error}"),
+            },
+            None => warn!("No data directory to export the game"),
+        }
+    }
+
+    /// Set the file where the game report exports the annotated game
+    /// (see [`Chess::review_path`] for the default one).
+    pub fn set_review_path(&mut self, path: PathBuf) {
+        self.review_path = Some(path);
+    }
+
+    /// Start to solve puzzles, the game is paused until the puzzles are closed.
     ///
-    /// # Safety
+    /// The opponent replies automatically. The statistics of the user are loaded from the
+    /// statistics file (see [`PuzzleStats::default_path`]) and saved after every puzzle.
+    /// The puzzles are refused during a network game.
     ///
-    /// This function use/set a static variable.
-    pub unsafe fn next_theme(&mut self) {
-        INDEX_THEME = (INDEX_THEME + 1) % NUM_THEMES;
-        self.theme = THEMES[INDEX_THEME % 6];
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use chess::{ChessGui, Puzzle, Square};
+    ///
+    /// let puzzle = Puzzle::from_str("mate1,7k/p5pp/8/8/8/8/6PP/3R2K1 b - - 0 1,a7a6 d1d8,600");
+    /// let mut game = ChessGui::default();
+    /// game.set_puzzle_stats_path(std::env::temp_dir().join("rg-chess-doc-puzzle-stats.txt"));
+    /// game.open_puzzles(vec![puzzle.unwrap()]);
+    /// assert!(!game.puzzles().unwrap().attempt().is_solved());
+    ///
+    /// game.play(Square::D1, Square::D8);
+    /// assert!(game.puzzles().unwrap().attempt().is_solved());
+    /// game.close_puzzles();
+    /// assert!(game.puzzles().is_none());
+    /// # std::fs::remove_file(std::env::temp_dir().join("rg-chess-doc-puzzle-stats.txt")).unwrap();
+    /// ```
+    pub fn open_puzzles(&mut self, puzzles: Vec<Puzzle>) {
+        if self.is_read_only() || self.network.is_some() {
+            warn!("The puzzles are not available during a network game");
+            return;
+        }
+        let stats = match &self.puzzle_stats_path {
+            Some(path) => PuzzleStats::load(path).unwrap_or_else(|error| {
+                warn!("{error}");
+                PuzzleStats::default()
+            }),
+            None => PuzzleStats::default(),
+        };
+        let Some(trainer) = PuzzleTrainer::new(puzzles, stats) else {
+            warn!("No puzzle to solve");
+            return;
+        };
+        info!("{} puzzles loaded", trainer.len());
+        self.editor = None;
+        // The analysis would show the solution
+        self.analyser = None;
+        if self.puzzles.is_none() {
+            self.paused_game = Some(self.chess.clone());
+        }
+        self.puzzles = Some(trainer);
+        self.start_puzzle();
+    }
+
+    /// Close the puzzles and resume the paused game.
+    pub fn close_puzzles(&mut self) {
+        if self.puzzles.take().is_none() {
+            return;
+        }
+        let chess = self.paused_game.take().unwrap_or_default();
+        self.update_flipped();
+        self.set_chess(chess);
+    }
+
+    /// Get the puzzles being solved (if the puzzles are open).
+    pub fn puzzles(&self) -> Option<&PuzzleTrainer> {
+        self.puzzles.as_ref()
+    }
+
+    /// Set the file where the statistics of the puzzles are saved
+    /// (see [`PuzzleStats::default_path`] for the default one).
+    pub fn set_puzzle_stats_path(&mut self, path: PathBuf) {
+        self.puzzle_stats_path = Some(path);
+    }
+
+    /// Show the position of the current puzzle, the moves of the attempt are played on the
+    /// board one by one (see [`ChessGui::update_puzzle`]).
+    fn start_puzzle(&mut self) {
+        let Some(trainer) = &self.puzzles else {
+            return;
+        };
+        let puzzle = trainer.puzzle();
+        info!("Puzzle {} (rating {})", puzzle.id, puzzle.rating);
+        let chess = Chess::new(puzzle.board);
+        self.update_flipped();
+        self.set_chess(chess);
+    }
+
+    /// Try the current puzzle again.
+    pub fn retry_puzzle(&mut self) {
+        if let Some(trainer) = &mut self.puzzles {
+            trainer.retry();
+            self.start_puzzle();
+        }
+    }
+
+    /// Go to the next puzzle.
+    pub fn next_puzzle(&mut self) {
+        if let Some(trainer) = &mut self.puzzles {
+            trainer.next();
+            self.start_puzzle();
+        }
+    }
+
+    /// Show the solution of the current puzzle, it counts as a failure.
+    pub fn show_solution(&mut self) {
+        if let Some(trainer) = &mut self.puzzles {
+            let stats = trainer.stats();
+            trainer.show_solution();
+            if trainer.stats() != stats {
+                self.save_puzzle_stats();
+            }
+        }
+    }
+
+    /// Check a move of the user in the current puzzle.
+    fn play_puzzle(&mut self, m: ChessMove) {
+        let Some(trainer) = &mut self.puzzles else {
+            return;
+        };
+        let stats = trainer.stats();
+        match trainer.play(m) {
+            PuzzleMove::Illegal => {}
+            PuzzleMove::Wrong => info!("Wrong move: {m}"),
+            PuzzleMove::Correct => info!("Correct move: {m}"),
+            PuzzleMove::Solved => info!("Puzzle solved"),
+        }
+        let changed = trainer.stats() != stats;
+        self.chess.square_focused = None;
+        if changed {
+            self.save_puzzle_stats();
+        }
+    }
+
+    /// Play on the board the moves of the puzzle attempt not shown yet (the user's move, the
+    /// reply of the opponent or the solution), one after the animation of the other.
+    fn update_puzzle(&mut self) {
+        let Some(trainer) = &self.puzzles else {
+            return;
+        };
+        if self.animation.is_some() {
+            return;
+        }
+        if let Some(&m) = trainer.attempt().played().get(self.chess.moves.len()) {
+            let before = self.chess.board;
+            self.chess.play(m.from, m.to);
+            self.animate_from(before);
+        }
+    }
+
+    /// Save the statistics of the puzzles in their file (if exist).
+    fn save_puzzle_stats(&self) {
+        if let (Some(trainer), Some(path)) = (&self.puzzles, &self.puzzle_stats_path) {
+            if let Err(error) = trainer.stats().save(path) {
+                warn!("{error}");
+            }
+        }
+    }
+
+    /// Show the board from the side of the settings, or from the side of the user in the
+    /// puzzles (flipped if the settings flip the board).
+    fn update_flipped(&mut self) {
+        let black_puzzle = self
+            .puzzles
+            .as_ref()
+            .is_some_and(|trainer| trainer.puzzle().color() == Color::Black);
+        self.layout
+            .set_flipped(self.settings.board_flipped != black_puzzle);
     }
 
     /// Add a button in the GUI.
@@ -64,142 +1218,494 @@ impl ChessGui {
         self.buttons.push(button);
     }
 
-    /// Set all the buttons in the GUI.
-    fn init_buttons(&mut self) {
-        self.buttons.push(
-            Button::new(
-                "theme",
-                true,
-                graphics::Rect::new(
-                    BOARD_PX_SIZE.0 + SIDE_SCREEN_PX_SIZE.0 - 70.0,
-                    20.0,
-                    50.0,
-                    50.0,
-                ),
-                graphics::Color::new(1.0, 1.0, 1.0, 1.0),
-                "Theme",
-                Align::Center,
-                Some(|chess_gui| unsafe {
-                    chess_gui.next_theme();
-                }),
-            )
-            .set_image(self.theme.theme_icon_path),
-        );
+    /// Set all the buttons in the GUI.
+    fn init_buttons(&mut self) {
+        self.buttons.push(
+            Button::new(
+                "theme",
+                true,
+                self.layout.theme_button(),
+                graphics::Color::new(1.0, 1.0, 1.0, 1.0),
+                "Theme",
+                Align::Center,
+                Some(|chess_gui| {
+                    chess_gui.next_theme();
+                }),
+            )
+            .set_image(self.theme().theme_icon_path.clone()),
+        );
+        if let Some(editor) = self.editor {
+            self.init_editor_buttons(editor);
+            return;
+        }
+        if self.puzzles.is_some() {
+            self.init_puzzle_buttons();
+            return;
+        }
+        self.buttons.push(Button::new(
+            "undo",
+            true,
+            self.layout.button(0, 0),
+            graphics::Color::new(0.65, 0.44, 0.78, 1.0),
+            "Undo",
+            Align::Center,
+            Some(|chess_gui| {
+                chess_gui.undo();
+            }),
+        ));
+        self.buttons.push(Button::new(
+            "declare-draw",
+            false,
+            self.layout.button(0, 1),
+            graphics::Color::new(0.89, 0.8, 0.35, 1.0),
+            "Declare Draw",
+            Align::Center,
+            Some(|chess_gui| {
+                chess_gui.declare_draw();
+            }),
+        ));
+        self.buttons.push(Button::new(
+            "offer-draw",
+            true,
+            self.layout.button(1, 0),
+            graphics::Color::new(1.0, 0.64, 0.38, 1.0),
+            "Offer Draw",
+            Align::Center,
+            Some(|chess_gui| {
+                chess_gui.offer_draw();
+            }),
+        ));
+        self.buttons.push(Button::new(
+            "accept-draw",
+            false,
+            self.layout.button(1, 1),
+            graphics::Color::new(0.56, 0.78, 0.4, 1.0),
+            "Accept Draw",
+            Align::Center,
+            Some(|chess_gui| {
+                chess_gui.accept_draw();
+            }),
+        ));
+        self.buttons.push(Button::new(
+            "reset",
+            true,
+            self.layout.button(2, 0),
+            graphics::Color::new(0.65, 0.44, 0.78, 1.0),
+            "Reset",
+            Align::Center,
+            Some(|chess_gui| {
+                chess_gui.reset();
+            }),
+        ));
+        self.buttons.push(Button::new(
+            "resign",
+            true,
+            self.layout.button(2, 1),
+            graphics::Color::new(0.98, 0.3, 0.3, 1.0),
+            "Resign",
+            Align::Center,
+            Some(|chess_gui| {
+                chess_gui.resign();
+            }),
+        ));
+        self.buttons.push(Button::new(
+            "settings",
+            true,
+            self.layout.button(3, 0),
+            graphics::Color::new(0.45, 0.6, 0.8, 1.0),
+            "Settings",
+            Align::Center,
+            Some(|chess_gui| {
+                chess_gui.toggle_settings_screen();
+            }),
+        ));
+        self.buttons.push(Button::new(
+            "flip",
+            true,
+            self.layout.button(3, 1),
+            graphics::Color::new(0.45, 0.6, 0.8, 1.0),
+            "Flip Board",
+            Align::Center,
+            Some(|chess_gui| {
+                chess_gui.flip_board();
+            }),
+        ));
+        self.buttons.push(Button::new(
+            "save",
+            true,
+            self.layout.button(4, 0),
+            graphics::Color::new(0.4, 0.7, 0.7, 1.0),
+            "Save Game",
+            Align::Center,
+            Some(|chess_gui| {
+                chess_gui.save_game();
+            }),
+        ));
+        self.buttons.push(Button::new(
+            "load",
+            true,
+            self.layout.button(4, 1),
+            graphics::Color::new(0.4, 0.7, 0.7, 1.0),
+            "Load Game",
+            Align::Center,
+            Some(|chess_gui| {
+                chess_gui.load_game();
+            }),
+        ));
+        self.buttons.push(Button::new(
+            "hint",
+            true,
+            self.layout.hint_button(),
+            graphics::Color::new(0.1, 0.7, 0.7, 1.0),
+            "Hint",
+            Align::Center,
+            Some(|chess_gui| {
+                chess_gui.hint();
+            }),
+        ));
+        // The spectators can't play, the move list replaces the buttons of the game
+        if self.is_read_only() {
+            self.buttons
+                .retain(|button| matches!(button.id, "theme" | "settings" | "flip" | "save"));
+        }
+    }
+
+    /// Set the buttons of the board editor, instead of the buttons of the game.
+    fn init_editor_buttons(&mut self, editor: BoardEditor) {
+        let side_to_move = match editor.board().side_to_move() {
+            Color::White => "White to move",
+            Color::Black => "Black to move",
+        };
+        let en_passant = match editor.board().en_passant() {
+            Some(_) => "En passant: on",
+            None => "En passant: off",
+        };
         self.buttons.push(Button::new(
-            "undo",
+            "editor-side",
             true,
-            graphics::Rect::new(
-                BOARD_PX_SIZE.0 + 20.0,
-                SIDE_SCREEN_PX_SIZE.1 - 210.0,
-                150.0,
-                50.0,
-            ),
+            self.layout.button(0, 0),
+            graphics::Color::new(0.45, 0.6, 0.8, 1.0),
+            side_to_move,
+            Align::Center,
+            Some(|chess_gui| {
+                chess_gui.edit(BoardEditor::toggle_side_to_move);
+            }),
+        ));
+        self.buttons.push(Button::new(
+            "editor-en-passant",
+            !editor.board().en_passant_targets().is_empty(),
+            self.layout.button(0, 1),
+            graphics::Color::new(0.45, 0.6, 0.8, 1.0),
+            en_passant,
+            Align::Center,
+            Some(|chess_gui| {
+                chess_gui.edit(BoardEditor::next_en_passant);
+            }),
+        ));
+        self.buttons.push(Button::new(
+            "editor-clear",
+            true,
+            self.layout.button(1, 0),
+            graphics::Color::new(0.98, 0.3, 0.3, 1.0),
+            "Clear",
+            Align::Center,
+            Some(|chess_gui| {
+                chess_gui.edit(BoardEditor::clear);
+            }),
+        ));
+        self.buttons.push(Button::new(
+            "editor-start",
+            true,
+            self.layout.button(1, 1),
             graphics::Color::new(0.65, 0.44, 0.78, 1.0),
-            "Undo",
+            "Start Position",
             Align::Center,
             Some(|chess_gui| {
-                chess_gui.chess.undo();
+                chess_gui.edit(BoardEditor::start_position);
             }),
         ));
         self.buttons.push(Button::new(
-            "declare-draw",
-            false,
-            graphics::Rect::new(
-                BOARD_PX_SIZE.0 + 190.0,
-                SIDE_SCREEN_PX_SIZE.1 - 210.0,
-                150.0,
-                50.0,
-            ),
-            graphics::Color::new(0.89, 0.8, 0.35, 1.0),
-            "Declare Draw",
+            "editor-copy",
+            true,
+            self.layout.button(2, 0),
+            graphics::Color::new(0.4, 0.7, 0.7, 1.0),
+            "Copy FEN",
             Align::Center,
             Some(|chess_gui| {
-                chess_gui.chess.declare_draw();
+                chess_gui.copy_fen();
             }),
         ));
         self.buttons.push(Button::new(
-            "offer-draw",
+            "editor-paste",
             true,
-            graphics::Rect::new(
-                BOARD_PX_SIZE.0 + 20.0,
-                SIDE_SCREEN_PX_SIZE.1 - 140.0,
-                150.0,
-                50.0,
-            ),
-            graphics::Color::new(1.0, 0.64, 0.38, 1.0),
-            "Offer Draw",
+            self.layout.button(2, 1),
+            graphics::Color::new(0.4, 0.7, 0.7, 1.0),
+            "Paste FEN",
             Align::Center,
             Some(|chess_gui| {
-                chess_gui.chess.offer_draw();
+                chess_gui.paste_fen();
             }),
         ));
         self.buttons.push(Button::new(
-            "accept-draw",
-            false,
-            graphics::Rect::new(
-                BOARD_PX_SIZE.0 + 190.0,
-                SIDE_SCREEN_PX_SIZE.1 - 140.0,
-                150.0,
-                50.0,
-            ),
+            "settings",
+            true,
+            self.layout.button(3, 0),
+            graphics::Color::new(0.45, 0.6, 0.8, 1.0),
+            "Settings",
+            Align::Center,
+            Some(|chess_gui| {
+                chess_gui.toggle_settings_screen();
+            }),
+        ));
+        self.buttons.push(Button::new(
+            "flip",
+            true,
+            self.layout.button(3, 1),
+            graphics::Color::new(0.45, 0.6, 0.8, 1.0),
+            "Flip Board",
+            Align::Center,
+            Some(|chess_gui| {
+                chess_gui.flip_board();
+            }),
+        ));
+        self.buttons.push(Button::new(
+            "editor-play",
+            editor.validate().is_ok(),
+            self.layout.button(4, 0),
             graphics::Color::new(0.56, 0.78, 0.4, 1.0),
-            "Accept Draw",
+            "Play From Here",
             Align::Center,
             Some(|chess_gui| {
-                chess_gui.chess.accept_draw();
+                chess_gui.play_from_editor();
             }),
         ));
         self.buttons.push(Button::new(
-            "reset",
+            "editor-cancel",
             true,
-            graphics::Rect::new(
-                BOARD_PX_SIZE.0 + 20.0,
-                SIDE_SCREEN_PX_SIZE.1 - 70.0,
-                150.0,
-                50.0,
-            ),
+            self.layout.button(4, 1),
+            graphics::Color::new(1.0, 0.64, 0.38, 1.0),
+            "Cancel",
+            Align::Center,
+            Some(|chess_gui| {
+                chess_gui.close_editor();
+            }),
+        ));
+    }
+
+    /// Set the buttons of the puzzles, instead of the buttons of the game.
+    fn init_puzzle_buttons(&mut self) {
+        self.buttons.push(Button::new(
+            "puzzle-retry",
+            true,
+            self.layout.button(0, 0),
             graphics::Color::new(0.65, 0.44, 0.78, 1.0),
-            "Reset",
+            "Retry",
             Align::Center,
             Some(|chess_gui| {
-                chess_gui.reset();
+                chess_gui.retry_puzzle();
             }),
         ));
         self.buttons.push(Button::new(
-            "resign",
+            "puzzle-solution",
             true,
-            graphics::Rect::new(
-                BOARD_PX_SIZE.0 + 190.0,
-                SIDE_SCREEN_PX_SIZE.1 - 70.0,
-                150.0,
-                50.0,
-            ),
+            self.layout.button(0, 1),
+            graphics::Color::new(1.0, 0.64, 0.38, 1.0),
+            "Solution",
+            Align::Center,
+            Some(|chess_gui| {
+                chess_gui.show_solution();
+            }),
+        ));
+        self.buttons.push(Button::new(
+            "puzzle-next",
+            true,
+            self.layout.button(1, 0),
+            graphics::Color::new(0.56, 0.78, 0.4, 1.0),
+            "Next Puzzle",
+            Align::Center,
+            Some(|chess_gui| {
+                chess_gui.next_puzzle();
+            }),
+        ));
+        self.buttons.push(Button::new(
+            "puzzle-close",
+            true,
+            self.layout.button(1, 1),
             graphics::Color::new(0.98, 0.3, 0.3, 1.0),
-            "Resign",
+            "Back To Game",
+            Align::Center,
+            Some(|chess_gui| {
+                chess_gui.close_puzzles();
+            }),
+        ));
+        self.buttons.push(Button::new(
+            "settings",
+            true,
+            self.layout.button(3, 0),
+            graphics::Color::new(0.45, 0.6, 0.8, 1.0),
+            "Settings",
             Align::Center,
             Some(|chess_gui| {
-                chess_gui.chess.resign(chess_gui.chess.board.side_to_move());
+                chess_gui.toggle_settings_screen();
             }),
         ));
+        self.buttons.push(Button::new(
+            "flip",
+            true,
+            self.layout.button(3, 1),
+            graphics::Color::new(0.45, 0.6, 0.8, 1.0),
+            "Flip Board",
+            Align::Center,
+            Some(|chess_gui| {
+                chess_gui.flip_board();
+            }),
+        ));
+    }
+
+    /// Open the board editor on the current position, the game is paused until it is closed.
+    pub fn open_editor(&mut self) {
+        if self.is_read_only() || self.puzzles.is_some() {
+            return;
+        }
+        if self.is_network_guest() {
+            warn!("Only the host can set up the position of a network game");
+            return;
+        }
+        self.editor = Some(BoardEditor::new(self.chess.board));
+        self.editor_error = None;
+        self.animation = None;
+        self.clear_premoves();
+        self.chess.square_focused = None;
+        self.move_input.clear();
+        self.buttons.clear();
+        self.init_buttons();
+    }
+
+    /// Close the board editor without changing the game.
+    pub fn close_editor(&mut self) {
+        self.editor = None;
+        self.buttons.clear();
+        self.init_buttons();
+    }
+
+    /// Start a new game from the position of the board editor (if it is valid).
+    pub fn play_from_editor(&mut self) {
+        let Some(editor) = &self.editor else {
+            return;
+        };
+        match editor.to_chess() {
+            Ok(mut chess) => {
+                info!("Play from the position: {}", editor.fen());
+                chess.set_time_control(self.settings.time_control());
+                self.editor = None;
+                self.set_chess(chess);
+                self.sync_network();
+            }
+            Err(error) => warn!("{error}"),
+        }
+    }
+
+    /// Change the position of the board editor (if it is open).
+    fn edit(&mut self, change: impl FnOnce(&mut BoardEditor)) {
+        if let Some(editor) = &mut self.editor {
+            change(editor);
+            self.editor_error = None;
+            // The labels of the buttons show the position
+            self.buttons.clear();
+            self.init_buttons();
+        }
+    }
+
+    /// Copy the FEN of the board editor to the clipboard.
+    pub fn copy_fen(&mut self) {
+        let Some(editor) = &self.editor else {
+            return;
+        };
+        let result =
+            arboard::Clipboard::new().and_then(|mut clipboard| clipboard.set_text(editor.fen()));
+        match result {
+            Ok(()) => info!("FEN copied: {}", editor.fen()),
+            Err(error) => self.editor_error = Some(format!("Can't copy the FEN: {error}")),
+        }
+    }
+
+    /// Set up the position of the FEN in the clipboard in the board editor.
+    pub fn paste_fen(&mut self) {
+        if self.editor.is_none() {
+            return;
+        }
+        let text = arboard::Clipboard::new().and_then(|mut clipboard| clipboard.get_text());
+        let result = match text {
+            Ok(text) => {
+                let mut editor = self.editor.expect("the editor is open");
+                editor
+                    .set_fen(&text)
+                    .map(|()| editor)
+                    .map_err(|error| error.to_string())
+            }
+            Err(error) => Err(format!("Can't paste the FEN: {error}")),
+        };
+        match result {
+            Ok(editor) => self.edit(|current| *current = editor),
+            Err(error) => self.editor_error = Some(error),
+        }
     }
 
     /// Base function to call when a user click on the screen.
     pub fn click(&mut self, x: f32, y: f32) {
-        if x < BOARD_PX_SIZE.0 && self.chess.state.is_ongoing() {
-            self.click_on_board(x, y);
-        } else {
-            self.click_on_side(x, y);
+        match self.layout.square_at(x, y) {
+            Some(_) if self.resume.is_some() => self.click_on_resume(x, y),
+            Some(_) if self.settings_open => self.click_on_settings(x, y),
+            Some(_) if self.report_open => {
+                if self.layout.overlay_row(REPORT_EXPORT_ROW).contains([x, y]) {
+                    self.export_review();
+                }
+            }
+            Some(square) if self.editor.is_some() => self.edit(|editor| editor.put(square)),
+            // Back to the game from the history
+            Some(_) if self.view.is_some() => self.show_ply(self.chess.moves.len()),
+            Some(square) if self.chess.state.is_ongoing() => self.click_on_board(square),
+            _ => self.click_on_side(x, y),
         }
     }
 
-    /// React when the user click on the board screen.
-    ///
-    /// It is the callers responsibility to ensure the coordinate is in the board.
-    fn click_on_board(&mut self, x: f32, y: f32) {
-        let current_square = Square::from_screen(x, y);
-        debug!("Click at: ({x},{y}) -> on the square: {current_square}");
+    /// React when the user click on the resume prompt.
+    fn click_on_resume(&mut self, x: f32, y: f32) {
+        if self.layout.overlay_row(RESUME_ROW).contains([x, y]) {
+            self.answer_resume(true);
+        } else if self.layout.overlay_row(NEW_GAME_ROW).contains([x, y]) {
+            self.answer_resume(false);
+        }
+    }
+
+    /// React when the user click on the settings screen.
+    fn click_on_settings(&mut self, x: f32, y: f32) {
+        // The first row is the title
+        let entry = ALL_SETTINGS_ENTRIES
+            .into_iter()
+            .enumerate()
+            .find(|&(index, _)| self.layout.overlay_row(index + 1).contains([x, y]));
+        if let Some((_, entry)) = entry {
+            info!("Change the setting: {}", entry.label());
+            self.change_setting(entry);
+        }
+    }
+
+    /// React when the user click on a [`Square`] of the board.
+    fn click_on_board(&mut self, current_square: Square) {
+        debug!("Click on the square: {current_square}");
+        if self.view.is_some() {
+            return;
+        }
+        if let Some(color) = self.premove_color() {
+            self.click_premove(current_square, color);
+            return;
+        }
+        if self.is_read_only() || self.is_bot_turn() || self.is_remote_turn() {
+            return;
+        }
         match self.chess.square_focused {
-            Some(square_selected) => self.chess.play(square_selected, current_square),
+            Some(square_selected) => self.play(square_selected, current_square),
             None => {
                 if self
                     .chess
@@ -212,11 +1718,103 @@ impl ChessGui {
         }
     }
 
+    /// Select the piece of a premove then its destination, during the turn of the opponent.
+    fn click_premove(&mut self, square: Square, color: Color) {
+        let position = self.premoves.position(&self.chess.board, color);
+        if let Some(from) = self.premove_focused.take() {
+            let m = ChessMove::new(from, square);
+            if self.premoves.push(&self.chess.board, color, m) {
+                info!("Premove queued: {m}");
+                return;
+            }
+        }
+        if position.color_on_is(square, color) {
+            self.premove_focused = Some(square);
+        }
+    }
+
+    /// Draw an arrow between two squares (a circle if it is the same square) on the position
+    /// shown, or remove it if it is already drawn.
+    pub fn annotate(&mut self, from: Square, to: Square, color: AnnotationColor) {
+        let ply = self.displayed_ply();
+        let mut annotations = self.chess.annotations(ply).cloned().unwrap_or_default();
+        if from == to {
+            annotations.toggle_circle(from, color);
+        } else {
+            annotations.toggle_arrow(from, to, color);
+        }
+        self.chess.set_annotations(ply, annotations);
+        self.autosave();
+    }
+
+    /// Get the legal moves matching the move being typed, with their SAN.
+    ///
+    /// A move matches if its SAN or its UCI notation starts with the input, the captures
+    /// (`x`), the checks (`+`, `#`) and the `=` of the promotions are optional.
+    fn matching_moves(&self) -> Vec<(ChessMove, String)> {
+        let input = simplify_san(&self.move_input);
+        if input.is_empty() || self.chess.state.is_finish() {
+            return vec![];
+        }
+        let board = self.chess.board;
+        board
+            .get_all_legal_moves()
+            .into_iter()
+            .map(|m| (m, m.to_san(&board)))
+            .filter(|(m, san)| {
+                simplify_san(san).starts_with(&input) || m.to_string().starts_with(&input)
+            })
+            .collect()
+    }
+
+    /// Play the move typed by the user: the one written exactly, or the only matching one.
+    fn submit_move_input(&mut self) {
+        let matching = self.matching_moves();
+        let input = std::mem::take(&mut self.move_input);
+        if self.is_read_only() || self.is_bot_turn() || self.is_remote_turn() {
+            return;
+        }
+        let exact = matching
+            .iter()
+            .find(|(m, san)| simplify_san(san) == simplify_san(&input) || m.to_string() == input);
+        match (exact, matching.as_slice()) {
+            (Some(&(m, _)), _) | (None, &[(m, _)]) => {
+                info!("Play the typed move: {input}");
+                self.cursor = self.cursor.map(|_| m.to);
+                self.play(m.from, m.to);
+            }
+            (None, []) => warn!("Illegal move: {input}"),
+            (None, _) => warn!("Ambiguous move: {input}"),
+        }
+    }
+
+    /// Move the keyboard cursor in the direction of an arrow, as seen on the screen.
+    fn move_cursor(&mut self, keycode: KeyCode) {
+        let start = match self.chess.board.side_to_move() {
+            Color::White => Square::E2,
+            Color::Black => Square::E7,
+        };
+        let Some(square) = self.cursor.or(self.chess.square_focused) else {
+            self.cursor = Some(start);
+            return;
+        };
+        let flipped = self.layout.is_flipped();
+        self.cursor = Some(match (keycode, flipped) {
+            (KeyCode::Up, false) | (KeyCode::Down, true) => square.up(),
+            (KeyCode::Down, false) | (KeyCode::Up, true) => square.down(),
+            (KeyCode::Right, false) | (KeyCode::Left, true) => square.right(),
+            _ => square.left(),
+        });
+    }
+
     /// React when the user click on the side screen.
     ///
     /// It is the callers responsibility to ensure the coordinate is in the side.
     fn click_on_side(&mut self, x: f32, y: f32) {
         info!("Click at: ({x},{y}) -> on the side screen");
+        if self.editor.is_some() {
+            self.click_on_editor(x, y);
+        }
         let buttons = self.buttons.clone();
         for button in buttons.iter() {
             if button.contains(x, y) {
@@ -225,17 +1823,60 @@ impl ChessGui {
         }
     }
 
+    /// React when the user click on the cells of the board editor.
+    fn click_on_editor(&mut self, x: f32, y: f32) {
+        for (row, column, cell) in EDITOR_CELLS {
+            if !self.layout.editor_cell(row, column).contains([x, y]) {
+                continue;
+            }
+            self.edit(|editor| match cell {
+                EditorCell::Piece(piece, color) => editor.select(Some((piece, color))),
+                EditorCell::Eraser => editor.select(None),
+                EditorCell::Castle(color, rights) => editor.toggle_castle_rights(color, rights),
+            });
+        }
+    }
+
     /// Draw all of the board side.
     fn draw_board(&self, ctx: &mut Context) -> GameResult {
         self.draw_empty_board(ctx)?;
-        self.draw_legal_moves(ctx)?;
+        if let Some(editor) = &self.editor {
+            self.draw_editor_board(ctx, editor)?;
+            self.draw_cursor(ctx)?;
+            if self.settings_open {
+                self.draw_settings(ctx)?;
+            }
+            return Ok(());
+        }
+        self.draw_last_move(ctx)?;
+        // The moves can't be played on a position of the history
+        if self.view.is_none() {
+            self.draw_selected_square(ctx)?;
+            self.draw_premoves(ctx)?;
+            self.draw_hint(ctx)?;
+            self.draw_legal_moves(ctx)?;
+            self.draw_hovered_move(ctx)?;
+        }
+        self.draw_check(ctx)?;
         self.draw_pinned_piece(ctx)?;
         self.draw_content_board(ctx)?;
+        self.draw_best_move(ctx)?;
+        self.draw_annotations(ctx)?;
+        self.draw_cursor(ctx)?;
+        if let Some(chess) = &self.resume {
+            self.draw_resume_prompt(ctx, chess)?;
+        } else if self.settings_open {
+            self.draw_settings(ctx)?;
+        } else if self.report_open {
+            self.draw_report(ctx)?;
+        }
         Ok(())
     }
 
     /// Draw the empty chess board (without pieces).
     fn draw_empty_board(&self, ctx: &mut Context) -> GameResult {
+        let cell_size = self.layout.cell_size();
+        let board = self.layout.board();
         for y in 0..BOARD_SIZE.1 {
             for x in 0..BOARD_SIZE.0 {
                 let color_index = if (x % 2 == 1 && y % 2 == 1) || (x % 2 == 0 && y % 2 == 0) {
@@ -247,12 +1888,12 @@ impl ChessGui {
                     .rectangle(
                         graphics::DrawMode::fill(),
                         graphics::Rect::new(
-                            x as f32 * BOARD_CELL_PX_SIZE.0,
-                            y as f32 * BOARD_CELL_PX_SIZE.1,
-                            BOARD_CELL_PX_SIZE.0,
-                            BOARD_CELL_PX_SIZE.1,
+                            board.x + x as f32 * cell_size.0,
+                            board.y + y as f32 * cell_size.1,
+                            cell_size.0,
+                            cell_size.1,
                         ),
-                        self.theme.board_color[color_index],
+                        self.theme().board_color[color_index],
                     )?
                     .build(ctx)?;
                 graphics::draw(ctx, &mesh, graphics::DrawParam::default())?;
@@ -261,21 +1902,252 @@ impl ChessGui {
         Ok(())
     }
 
-    /// Draw pieces on the board.
-    fn draw_content_board(&self, ctx: &mut Context) -> GameResult {
-        let mut path;
-        let mut image;
-        for square in ALL_SQUARES {
-            if let Some((piece, color)) = self.chess.board.on(square) {
-                path = self.theme.piece_path[color.to_index()][piece.to_index()];
-                image = graphics::Image::new(ctx, path).expect("Image load error");
-                let (x, y) = square.to_screen();
-                let dest_point = [x, y];
-                let image_scale = [0.5, 0.5];
-                let dp = graphics::DrawParam::new()
-                    .dest(dest_point)
-                    .scale(image_scale);
-                graphics::draw(ctx, &image, dp)?;
+    /// Draw pieces on the board.
+    ///
+    /// If an animation is running, the moving pieces are drawn at their interpolated position.
+    fn draw_content_board(&self, ctx: &mut Context) -> GameResult {
+        if let Some(animation) = &self.animation {
+            for &(piece, color, square) in animation.vanishing() {
+                self.draw_piece(ctx, piece, color, self.layout.square_to_screen(square))?;
+            }
+        }
+        // The pieces of the premoves are shown on their destination
+        let board = match self.premove_color() {
+            Some(color) if self.view.is_none() => self.premoves.position(&self.chess.board, color),
+            _ => self.displayed_board(),
+        };
+        for square in ALL_SQUARES {
+            if let Some((piece, color)) = board.on(square) {
+                if matches!(&self.animation, Some(animation) if animation.is_destination(square)) {
+                    continue;
+                }
+                self.draw_piece(ctx, piece, color, self.layout.square_to_screen(square))?;
+            }
+        }
+        if let Some(animation) = &self.animation {
+            for moving in animation.moving() {
+                let (x, y) = animation.interpolate(
+                    self.layout.square_to_screen(moving.from),
+                    self.layout.square_to_screen(moving.to),
+                );
+                self.draw_piece(ctx, moving.piece, moving.color, (x, y))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Draw the position of the board editor, with its en passant square.
+    fn draw_editor_board(&self, ctx: &mut Context, editor: &BoardEditor) -> GameResult {
+        let board = editor.board();
+        if let (Some(square), Some(color)) = (board.en_passant(), self.theme().selected_color) {
+            self.draw_highlight(ctx, square, color)?;
+        }
+        for square in ALL_SQUARES {
+            if let Some((piece, color)) = board.on(square) {
+                self.draw_piece(ctx, piece, color, self.layout.square_to_screen(square))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Draw a [`Piece`] at the given screen coordinate.
+    fn draw_piece(
+        &self,
+        ctx: &mut Context,
+        piece: Piece,
+        color: Color,
+        (x, y): (f32, f32),
+    ) -> GameResult {
+        let path = &self.theme().piece_path[color.to_index()][piece.to_index()];
+        let image = graphics::Image::new(ctx, path).expect("Image load error");
+        let dest_point = [x, y];
+        let cell_size = self.layout.cell_size();
+        let image_scale = [
+            cell_size.0 / image.width() as f32,
+            cell_size.1 / image.height() as f32,
+        ];
+        let dp = graphics::DrawParam::new()
+            .dest(dest_point)
+            .scale(image_scale);
+        graphics::draw(ctx, &image, dp)
+    }
+
+    /// Fill a [`Square`] of the board with the given color.
+    fn draw_highlight(
+        &self,
+        ctx: &mut Context,
+        square: Square,
+        color: graphics::Color,
+    ) -> GameResult {
+        let (x, y) = self.layout.square_to_screen(square);
+        let cell_size = self.layout.cell_size();
+        let mesh = graphics::MeshBuilder::new()
+            .rectangle(
+                graphics::DrawMode::fill(),
+                graphics::Rect::new(x, y, cell_size.0, cell_size.1),
+                color,
+            )?
+            .build(ctx)?;
+        graphics::draw(ctx, &mesh, graphics::DrawParam::default())
+    }
+
+    /// Draw the departure and arrival [`Square`] of the last move.
+    fn draw_last_move(&self, ctx: &mut Context) -> GameResult {
+        if let (true, Some(color)) = (
+            self.settings.highlights.last_move,
+            self.theme().last_move_color,
+        ) {
+            let ply = self.displayed_ply();
+            if let Some(m) = ply.checked_sub(1).map(|index| self.chess.moves[index]) {
+                self.draw_highlight(ctx, m.from, color)?;
+                self.draw_highlight(ctx, m.to, color)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Draw the [`Square`] selected by the player.
+    fn draw_selected_square(&self, ctx: &mut Context) -> GameResult {
+        if let (true, Some(color)) = (
+            self.settings.highlights.selected,
+            self.theme().selected_color,
+        ) {
+            if let Some(square) = self.chess.square_focused {
+                self.draw_highlight(ctx, square, color)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Draw the arrows and circles of the current position, and the one being drawn.
+    fn draw_annotations(&self, ctx: &mut Context) -> GameResult {
+        let mut arrows = vec![];
+        let mut circles = vec![];
+        if let Some(annotations) = self.chess.annotations(self.displayed_ply()) {
+            arrows.extend_from_slice(annotations.arrows());
+            circles.extend_from_slice(annotations.circles());
+        }
+        if let (Some((from, color)), Some(to)) = (self.annotation_start, self.square_hovered) {
+            match from == to {
+                true => circles.push(Circle {
+                    square: from,
+                    color,
+                }),
+                false => arrows.push(Arrow { from, to, color }),
+            }
+        }
+        if arrows.is_empty() && circles.is_empty() {
+            return Ok(());
+        }
+
+        let (cell_size, _) = self.layout.cell_size();
+        let center = |square| {
+            let (x, y) = self.layout.square_to_screen(square);
+            [x + cell_size / 2.0, y + cell_size / 2.0]
+        };
+        let mut mesh = graphics::MeshBuilder::new();
+        for circle in circles {
+            mesh.circle(
+                graphics::DrawMode::stroke(cell_size * 0.08),
+                center(circle.square),
+                cell_size * 0.45,
+                0.5,
+                annotation_color(circle.color),
+            )?;
+        }
+        for arrow in arrows {
+            self.add_arrow(
+                &mut mesh,
+                arrow.from,
+                arrow.to,
+                annotation_color(arrow.color),
+            )?;
+        }
+        let mesh = mesh.build(ctx)?;
+        graphics::draw(ctx, &mesh, graphics::DrawParam::default())
+    }
+
+    /// Add an arrow between the centers of two squares to a mesh.
+    fn add_arrow(
+        &self,
+        mesh: &mut graphics::MeshBuilder,
+        from: Square,
+        to: Square,
+        color: graphics::Color,
+    ) -> GameResult {
+        let (cell_size, _) = self.layout.cell_size();
+        let center = |square| {
+            let (x, y) = self.layout.square_to_screen(square);
+            [x + cell_size / 2.0, y + cell_size / 2.0]
+        };
+        let [x0, y0] = center(from);
+        let [x1, y1] = center(to);
+        let length = ((x1 - x0).powi(2) + (y1 - y0).powi(2)).sqrt();
+        let (dx, dy) = ((x1 - x0) / length, (y1 - y0) / length);
+        // The head is a triangle, the line stops at its base
+        let head = cell_size * 0.4;
+        let half_width = head * 0.6;
+        let base = [x1 - dx * head, y1 - dy * head];
+        mesh.line(&[[x0, y0], base], cell_size * 0.15, color)?;
+        mesh.polygon(
+            graphics::DrawMode::fill(),
+            &[
+                [x1, y1],
+                [base[0] - dy * half_width, base[1] + dx * half_width],
+                [base[0] + dy * half_width, base[1] - dx * half_width],
+            ],
+            color,
+        )?;
+        Ok(())
+    }
+
+    /// Draw the best move found by the analysis as an arrow.
+    fn draw_best_move(&self, ctx: &mut Context) -> GameResult {
+        if let Some(line) = self.analysis().and_then(|report| report.lines.first()) {
+            let mut mesh = graphics::MeshBuilder::new();
+            let color = graphics::Color::new(0.3, 0.6, 0.9, 0.6);
+            self.add_arrow(&mut mesh, line.best_move.from, line.best_move.to, color)?;
+            let mesh = mesh.build(ctx)?;
+            graphics::draw(ctx, &mesh, graphics::DrawParam::default())?;
+        }
+        Ok(())
+    }
+
+    /// Draw the square of the piece of the hint, and its destination once revealed.
+    fn draw_hint(&self, ctx: &mut Context) -> GameResult {
+        if let (Some(Hint::Found { m, revealed, .. }), Some(color)) =
+            (&self.hint, self.theme().hint_color)
+        {
+            self.draw_highlight(ctx, m.from, color)?;
+            if *revealed {
+                self.draw_highlight(ctx, m.to, color)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Draw the squares of the premoves queued by the local player, and the piece selected
+    /// for the next one.
+    fn draw_premoves(&self, ctx: &mut Context) -> GameResult {
+        if let (Some(_), Some(color)) = (self.premove_color(), self.theme().premove_color) {
+            for m in self.premoves.moves() {
+                self.draw_highlight(ctx, m.from, color)?;
+                self.draw_highlight(ctx, m.to, color)?;
+            }
+            if let Some(square) = self.premove_focused {
+                self.draw_highlight(ctx, square, color)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Draw the king of the current side if he is in check.
+    fn draw_check(&self, ctx: &mut Context) -> GameResult {
+        if let (true, Some(color)) = (self.settings.highlights.check, self.theme().check_color) {
+            let board = self.displayed_board();
+            if (self.chess.state.is_ongoing() || self.view.is_some()) && board.is_check() {
+                let side = board.side_to_move();
+                self.draw_highlight(ctx, board.king_of(side), color)?;
             }
         }
         Ok(())
@@ -283,18 +2155,47 @@ impl ChessGui {
 
     /// Draw all the possible destination of the selected piece.
     fn draw_legal_moves(&self, ctx: &mut Context) -> GameResult {
-        if self.theme.valid_moves_color.is_some() {
+        if let (true, Some(color)) = (
+            self.settings.highlights.legal_moves,
+            self.theme().valid_moves_color,
+        ) {
             if let Some(square) = self.chess.square_focused {
                 for dest in self.chess.board.get_legal_moves(square) {
-                    let (x, y) = dest.to_screen();
-                    let mesh = graphics::MeshBuilder::new()
-                        .rectangle(
-                            graphics::DrawMode::fill(),
-                            graphics::Rect::new(x, y, BOARD_CELL_PX_SIZE.0, BOARD_CELL_PX_SIZE.1),
-                            self.theme.valid_moves_color.unwrap(),
-                        )?
-                        .build(ctx)?;
-                    graphics::draw(ctx, &mesh, graphics::DrawParam::default())?;
+                    self.draw_highlight(ctx, dest, color)?;
+                }
+            }
+            // The destinations of the moves matching the typed move
+            for (m, _) in self.matching_moves() {
+                self.draw_highlight(ctx, m.to, color)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Draw a frame around the [`Square`] of the keyboard cursor.
+    fn draw_cursor(&self, ctx: &mut Context) -> GameResult {
+        if let Some(square) = self.cursor {
+            let (x, y) = self.layout.square_to_screen(square);
+            let cell_size = self.layout.cell_size();
+            let mesh = graphics::MeshBuilder::new()
+                .rectangle(
+                    graphics::DrawMode::stroke(4.0 * self.layout.scale()),
+                    graphics::Rect::new(x, y, cell_size.0, cell_size.1),
+                    graphics::Color::new(0.2, 0.5, 1.0, 1.0),
+                )?
+                .build(ctx)?;
+            graphics::draw(ctx, &mesh, graphics::DrawParam::default())?;
+        }
+        Ok(())
+    }
+
+    /// Draw the legal destination of the selected piece under the mouse.
+    fn draw_hovered_move(&self, ctx: &mut Context) -> GameResult {
+        if let (true, Some(color)) = (self.settings.highlights.hovered, self.theme().hovered_color)
+        {
+            if let (Some(from), Some(to)) = (self.chess.square_focused, self.square_hovered) {
+                if self.chess.board.is_legal(ChessMove::new(from, to)) {
+                    self.draw_highlight(ctx, to, color)?;
                 }
             }
         }
@@ -303,57 +2204,469 @@ impl ChessGui {
 
     /// Draw a cross on [`Square`] that are pinned (i.e. can't move).
     fn draw_pinned_piece(&self, ctx: &mut Context) -> GameResult {
-        if self.theme.piece_pinned_path.is_some() {
-            let mut path;
+        if !self.settings.highlights.pinned {
+            return Ok(());
+        }
+        if let Some(path) = &self.theme().piece_pinned_path {
             let mut image;
-            for square in self.chess.board.pinned() {
-                path = self.theme.piece_pinned_path.unwrap();
+            for square in self.displayed_board().pinned() {
                 image = graphics::Image::new(ctx, path).expect("Image load error");
-                let (x, y) = square.to_screen();
+                let (x, y) = self.layout.square_to_screen(square);
                 let dest_point = [x, y];
                 // We set the scale at 1.0 because we want the same size
                 // for the image and a Board_cell
                 const SCALE: f32 = 1.0;
+                let cell_size = self.layout.cell_size();
                 let image_scale = [
-                    SCALE * (BOARD_CELL_PX_SIZE.0 / image.width() as f32),
-                    SCALE * (BOARD_CELL_PX_SIZE.1 / image.height() as f32),
+                    SCALE * (cell_size.0 / image.width() as f32),
+                    SCALE * (cell_size.1 / image.height() as f32),
                 ];
                 let dp = graphics::DrawParam::new()
                     .dest(dest_point)
                     .scale(image_scale);
                 graphics::draw(ctx, &image, dp)?;
             }
-        } else if self.theme.piece_pinned_color.is_some() {
-            for piece in self.chess.board.pinned() {
-                let (x, y) = piece.to_screen();
-                let mesh = graphics::MeshBuilder::new()
-                    .rectangle(
-                        graphics::DrawMode::fill(),
-                        graphics::Rect::new(x, y, BOARD_CELL_PX_SIZE.0, BOARD_CELL_PX_SIZE.1),
-                        self.theme.piece_pinned_color.unwrap(),
-                    )?
-                    .build(ctx)?;
-                graphics::draw(ctx, &mesh, graphics::DrawParam::default())?;
+        } else if let Some(color) = self.theme().piece_pinned_color {
+            for square in self.displayed_board().pinned() {
+                self.draw_highlight(ctx, square, color)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Draw a dark screen over the board with a title on the first row.
+    fn draw_overlay(&self, ctx: &mut Context, title: &str) -> GameResult {
+        let background = graphics::MeshBuilder::new()
+            .rectangle(
+                graphics::DrawMode::fill(),
+                self.layout.board(),
+                graphics::Color::new(0.1, 0.1, 0.1, 0.9),
+            )?
+            .build(ctx)?;
+        graphics::draw(ctx, &background, graphics::DrawParam::default())?;
+        self.draw_overlay_text(ctx, 0, title, 2.0)
+    }
+
+    /// Draw a text centered in a row of the screen over the board.
+    fn draw_overlay_text(
+        &self,
+        ctx: &mut Context,
+        row: usize,
+        text: &str,
+        scale: f32,
+    ) -> GameResult {
+        let font = graphics::Font::new(ctx, &self.theme().font_path)?;
+        let bounds = self.layout.overlay_row(row);
+        let text = graphics::Text::new((text, font, self.font_scale() * scale));
+        let dest_point = [
+            bounds.x + (bounds.w - text.width(ctx)) / 2.0,
+            bounds.y + (bounds.h - text.height(ctx)) / 2.0,
+        ];
+        graphics::draw(ctx, &text, (dest_point,))
+    }
+
+    /// Draw the prompt to resume the game of the last run over the board.
+    fn draw_resume_prompt(&self, ctx: &mut Context, chess: &Chess) -> GameResult {
+        self.draw_overlay(ctx, "Resume the last game?")?;
+        let summary = format!(
+            "{} moves played, {:?} to play",
+            chess.moves.len(),
+            chess.board.side_to_move()
+        );
+        self.draw_overlay_text(ctx, 1, &summary, 1.5)?;
+        self.draw_overlay_button(ctx, RESUME_ROW, "Resume")?;
+        self.draw_overlay_button(ctx, NEW_GAME_ROW, "New game")
+    }
+
+    /// Draw a framed text in a row of the screen over the board.
+    fn draw_overlay_button(&self, ctx: &mut Context, row: usize, label: &str) -> GameResult {
+        let frame = graphics::MeshBuilder::new()
+            .rectangle(
+                graphics::DrawMode::stroke(3.0),
+                self.layout.overlay_row(row),
+                graphics::Color::new(0.7, 0.7, 0.7, 1.0),
+            )?
+            .build(ctx)?;
+        graphics::draw(ctx, &frame, graphics::DrawParam::default())?;
+        self.draw_overlay_text(ctx, row, label, 1.5)
+    }
+
+    /// Draw the game report over the board: the accuracy and the classes of the moves of
+    /// each player, then the evaluation graph.
+    fn draw_report(&self, ctx: &mut Context) -> GameResult {
+        self.draw_overlay(ctx, "Game report")?;
+        let review = match &self.review {
+            Some((_, ReviewState::Done(review))) => review,
+            Some((ply, ReviewState::Running(searched, _))) => {
+                let progress = format!("Analysing the positions: {searched}/{}", ply + 1);
+                return self.draw_overlay_text(ctx, 1, &progress, 1.5);
+            }
+            Some((_, ReviewState::Failed(error))) => {
+                return self.draw_overlay_text(ctx, 1, error, 1.0);
             }
+            None => return Ok(()),
+        };
+        for (index, color) in ALL_COLORS.into_iter().enumerate() {
+            let summary = format!(
+                "{color:?}: {:.1}% accuracy, {:.0} centipawns lost per move",
+                review.accuracy(color),
+                review.average_loss(color)
+            );
+            self.draw_overlay_text(ctx, 1 + 2 * index, &summary, 1.2)?;
+            let counts: Vec<String> = ALL_MOVE_CLASSES
+                .into_iter()
+                .map(|class| format!("{class}: {}", review.count(color, class)))
+                .collect();
+            self.draw_overlay_text(ctx, 2 + 2 * index, &counts.join("   "), 1.0)?;
+        }
+        self.draw_eval_graph(ctx, review)?;
+        self.draw_overlay_button(ctx, REPORT_EXPORT_ROW, "Export annotated PGN")
+    }
+
+    /// Draw the evaluation graph of the game report: the score of white after every move
+    /// (the white area grows with its advantage), with a mark on the bad moves and a line
+    /// on the position shown.
+    fn draw_eval_graph(&self, ctx: &mut Context, review: &GameReview) -> GameResult {
+        let top = self.layout.overlay_row(REPORT_GRAPH_ROW);
+        let bottom = self
+            .layout
+            .overlay_row(REPORT_GRAPH_ROW + REPORT_GRAPH_ROWS - 1);
+        let bounds = graphics::Rect::new(top.x, top.y, top.w, bottom.bottom() - top.y);
+        let middle = bounds.y + bounds.h / 2.0;
+        let scores = review.scores();
+        let last = scores.len() - 1;
+        let step = bounds.w / last.max(1) as f32;
+        let point = |ply: usize| {
+            let score = scores[ply] as f32 / REVIEW_MAX_SCORE as f32;
+            [
+                bounds.x + ply as f32 * step,
+                middle - score * bounds.h / 2.0,
+            ]
+        };
+        let mut white_area = vec![[bounds.x, bounds.bottom()]];
+        white_area.extend((0..scores.len()).map(point));
+        white_area.push([point(last)[0], bounds.bottom()]);
+
+        let mut mesh = graphics::MeshBuilder::new();
+        mesh.rectangle(
+            graphics::DrawMode::fill(),
+            bounds,
+            graphics::Color::new(0.25, 0.25, 0.25, 1.0),
+        )?
+        .polygon(
+            graphics::DrawMode::fill(),
+            &white_area,
+            graphics::Color::new(0.95, 0.95, 0.95, 1.0),
+        )?
+        .line(
+            &[[bounds.x, middle], [bounds.right(), middle]],
+            1.0,
+            graphics::Color::new(0.5, 0.5, 0.5, 1.0),
+        )?;
+        let shown = point(self.displayed_ply().min(last))[0];
+        mesh.line(
+            &[[shown, bounds.y], [shown, bounds.bottom()]],
+            2.0,
+            annotation_color(AnnotationColor::Blue),
+        )?;
+        for (index, move_review) in review.moves().iter().enumerate() {
+            if let Some(color) = move_class_color(move_review.class) {
+                let radius = 4.0 * self.layout.scale();
+                mesh.circle(
+                    graphics::DrawMode::fill(),
+                    point(index + 1),
+                    radius,
+                    0.1,
+                    color,
+                )?;
+            }
+        }
+        let mesh = mesh.build(ctx)?;
+        graphics::draw(ctx, &mesh, graphics::DrawParam::default())
+    }
+
+    /// Draw the settings screen over the board.
+    fn draw_settings(&self, ctx: &mut Context) -> GameResult {
+        self.draw_overlay(ctx, "Settings")?;
+
+        let font = graphics::Font::new(ctx, &self.theme().font_path)?;
+        for (index, entry) in ALL_SETTINGS_ENTRIES.into_iter().enumerate() {
+            let bounds = self.layout.overlay_row(index + 1);
+            let label = graphics::Text::new((entry.label(), font, self.font_scale() * 1.5));
+            let value =
+                graphics::Text::new((self.settings.value(entry), font, self.font_scale() * 1.5));
+            let y = bounds.y + (bounds.h - label.height(ctx)) / 2.0;
+            graphics::draw(ctx, &label, ([bounds.x, y],))?;
+            graphics::draw(ctx, &value, ([bounds.right() - value.width(ctx), y],))?;
         }
         Ok(())
     }
 
+    /// The font scale of the theme according to the size of the window.
+    fn font_scale(&self) -> f32 {
+        self.theme().font_scale * self.layout.scale()
+    }
+
     /// Draw all the side screen.
     fn draw_side(&self, ctx: &mut Context) -> GameResult {
         for button in self.buttons.iter() {
-            button.draw(ctx, self.theme.font_path, self.theme.font_scale)?;
+            button.draw(ctx, &self.theme().font_path, self.font_scale())?;
+        }
+        if let Some(editor) = &self.editor {
+            return self.draw_editor(ctx, editor);
         }
         self.draw_timers(ctx)?;
-        self.draw_winner(ctx)?;
+        self.draw_material(ctx)?;
+        if let Some(trainer) = &self.puzzles {
+            self.draw_puzzle(ctx, trainer)?;
+        } else if self.analyser.is_some() {
+            self.draw_eval_bar(ctx)?;
+            self.draw_analysis(ctx)?;
+        } else {
+            self.draw_winner(ctx)?;
+        }
+        if self.is_read_only() {
+            self.draw_move_list(ctx)?;
+        } else {
+            self.draw_move_input(ctx)?;
+        }
+        Ok(())
+    }
+
+    /// Draw the cells of the board editor (the piece palette and the castle rights), then the
+    /// FEN of the position and why it can't be played.
+    fn draw_editor(&self, ctx: &mut Context, editor: &BoardEditor) -> GameResult {
+        let font = graphics::Font::new(ctx, &self.theme().font_path)?;
+        let board = editor.board();
+        for (row, column, cell) in EDITOR_CELLS {
+            let bounds = self.layout.editor_cell(row, column);
+            let active = match cell {
+                EditorCell::Piece(piece, color) => editor.selected() == Some((piece, color)),
+                EditorCell::Eraser => editor.selected().is_none(),
+                EditorCell::Castle(color, rights) => {
+                    board.castle_rights(color).to_index() & rights.to_index() != 0
+                }
+            };
+            let frame_color = match active {
+                true => graphics::Color::new(0.3, 0.5, 0.9, 1.0),
+                false => graphics::Color::new(0.7, 0.7, 0.7, 1.0),
+            };
+            let frame = graphics::MeshBuilder::new()
+                .rectangle(graphics::DrawMode::stroke(3.0), bounds, frame_color)?
+                .build(ctx)?;
+            graphics::draw(ctx, &frame, graphics::DrawParam::default())?;
+            let label = match cell {
+                EditorCell::Piece(piece, color) => {
+                    let path = &self.theme().piece_path[color.to_index()][piece.to_index()];
+                    let image = graphics::Image::new(ctx, path).expect("Image load error");
+                    let size = bounds.w.min(bounds.h);
+                    let dp = graphics::DrawParam::new()
+                        .dest([
+                            bounds.x + (bounds.w - size) / 2.0,
+                            bounds.y + (bounds.h - size) / 2.0,
+                        ])
+                        .scale([size / image.width() as f32, size / image.height() as f32]);
+                    graphics::draw(ctx, &image, dp)?;
+                    continue;
+                }
+                EditorCell::Eraser => "Del",
+                EditorCell::Castle(Color::White, CastleRights::KingSide) => "K",
+                EditorCell::Castle(Color::White, _) => "Q",
+                EditorCell::Castle(Color::Black, CastleRights::KingSide) => "k",
+                EditorCell::Castle(Color::Black, _) => "q",
+            };
+            let text = graphics::Text::new((label, font, self.font_scale() * 1.5));
+            let dest_point = [
+                bounds.x + (bounds.w - text.width(ctx)) / 2.0,
+                bounds.y + (bounds.h - text.height(ctx)) / 2.0,
+            ];
+            graphics::draw(ctx, &text, (dest_point,))?;
+        }
+
+        let bounds = self.layout.editor_status();
+        let (status, color) = match (&self.editor_error, editor.validate()) {
+            (Some(error), _) => (error.clone(), graphics::Color::new(0.98, 0.3, 0.3, 1.0)),
+            (None, Err(error)) => (error.to_string(), graphics::Color::new(0.98, 0.3, 0.3, 1.0)),
+            (None, Ok(())) => (
+                "Valid position".to_string(),
+                graphics::Color::new(0.56, 0.78, 0.4, 1.0),
+            ),
+        };
+        let mut text =
+            graphics::Text::new((format!("{}\n\n", editor.fen()), font, self.font_scale()));
+        text.add(
+            graphics::TextFragment::new(status)
+                .font(font)
+                .scale(self.font_scale())
+                .color(color),
+        );
+        text.set_bounds([bounds.w, bounds.h], graphics::Align::Left);
+        graphics::draw(ctx, &text, ([bounds.x, bounds.y],))
+    }
+
+    /// Draw the evaluation bar: the share of white grows with its advantage.
+    fn draw_eval_bar(&self, ctx: &mut Context) -> GameResult {
+        let Some(report) = self.analysis() else {
+            return Ok(());
+        };
+        let Some(line) = report.lines.first() else {
+            return Ok(());
+        };
+        let white_share = match white_score(line, report.board.side_to_move()) {
+            (_, Some(moves)) => (moves > 0) as u8 as f32,
+            (score, None) => 1.0 / (1.0 + (-score as f32 / 400.0).exp()),
+        };
+        let bounds = self.layout.eval_bar();
+        // White is on the side of the white pieces, or on the left under the board
+        let white = match (self.layout.is_portrait(), self.layout.is_flipped()) {
+            (true, false) => {
+                graphics::Rect::new(bounds.x, bounds.y, bounds.w * white_share, bounds.h)
+            }
+            (true, true) => graphics::Rect::new(
+                bounds.right() - bounds.w * white_share,
+                bounds.y,
+                bounds.w * white_share,
+                bounds.h,
+            ),
+            (false, false) => graphics::Rect::new(
+                bounds.x,
+                bounds.bottom() - bounds.h * white_share,
+                bounds.w,
+                bounds.h * white_share,
+            ),
+            (false, true) => {
+                graphics::Rect::new(bounds.x, bounds.y, bounds.w, bounds.h * white_share)
+            }
+        };
+        let mesh = graphics::MeshBuilder::new()
+            .rectangle(
+                graphics::DrawMode::fill(),
+                bounds,
+                graphics::Color::new(0.25, 0.25, 0.25, 1.0),
+            )?
+            .rectangle(
+                graphics::DrawMode::fill(),
+                white,
+                graphics::Color::new(0.95, 0.95, 0.95, 1.0),
+            )?
+            .build(ctx)?;
+        graphics::draw(ctx, &mesh, graphics::DrawParam::default())
+    }
+
+    /// Draw the engine, the statistics of the search and the best lines of the analysis, in
+    /// place of the state of the game.
+    fn draw_analysis(&self, ctx: &mut Context) -> GameResult {
+        let Some(analyser) = &self.analyser else {
+            return Ok(());
+        };
+        let bounds = self.layout.status();
+        let frame = graphics::MeshBuilder::new()
+            .rectangle(
+                graphics::DrawMode::stroke(3.0),
+                bounds,
+                graphics::Color::new(0.7, 0.7, 0.7, 1.0),
+            )?
+            .build(ctx)?;
+        graphics::draw(ctx, &frame, graphics::DrawParam::default())?;
+
+        let mut lines = vec![analyser.name().to_string()];
+        match analyser.report() {
+            None => lines.push("Analysing...".to_string()),
+            Some(report) if report.lines.is_empty() => lines.push("No legal move".to_string()),
+            Some(report) => {
+                lines.push(format!(
+                    "Depth {}  {} nodes  {}/s",
+                    report.depth(),
+                    format_count(report.nodes),
+                    format_count(report.nps())
+                ));
+                for line in &report.lines {
+                    let mut shown = line.clone();
+                    shown.pv.truncate(ANALYSIS_LINE_LENGTH);
+                    lines.push(format!(
+                        "{}  {}",
+                        line.score_text(report.board.side_to_move()),
+                        shown.pv_san(&report.board)
+                    ));
+                }
+            }
+        }
+        let font = graphics::Font::new(ctx, &self.theme().font_path)?;
+        let padding = 10.0 * self.layout.scale();
+        let mut text = graphics::Text::new((lines.join("\n"), font, self.font_scale()));
+        text.set_bounds(
+            [bounds.w - 2.0 * padding, bounds.h - 2.0 * padding],
+            graphics::Align::Left,
+        );
+        graphics::draw(ctx, &text, ([bounds.x + padding, bounds.y + padding],))
+    }
+
+    /// Draw the box of the typed move, with the matching moves under it.
+    fn draw_move_input(&self, ctx: &mut Context) -> GameResult {
+        let bounds = self.layout.move_input();
+        // The hint button is at the right of the input box
+        let width = self.layout.hint_button().x - bounds.x - 10.0 * self.layout.scale();
+        let input_box = graphics::Rect::new(bounds.x, bounds.y, width, bounds.h / 2.0);
+        let frame = graphics::MeshBuilder::new()
+            .rectangle(
+                graphics::DrawMode::stroke(3.0),
+                input_box,
+                graphics::Color::new(0.7, 0.7, 0.7, 1.0),
+            )?
+            .build(ctx)?;
+        graphics::draw(ctx, &frame, graphics::DrawParam::default())?;
+
+        let font = graphics::Font::new(ctx, &self.theme().font_path)?;
+        let (text, color) = match self.move_input.is_empty() {
+            true => (
+                "Type a move".to_string(),
+                graphics::Color::new(0.6, 0.6, 0.6, 1.0),
+            ),
+            false => (format!("> {}", self.move_input), graphics::Color::WHITE),
+        };
+        let text = graphics::Text::new((text, font, self.font_scale() * 1.2));
+        let margin = 10.0 * self.layout.scale();
+        let dest_point = [
+            input_box.x + margin,
+            input_box.y + (input_box.h - text.height(ctx)) / 2.0,
+        ];
+        graphics::draw(ctx, &text, (dest_point, color))?;
+
+        let matching: Vec<String> = self
+            .matching_moves()
+            .into_iter()
+            .map(|(_, san)| san)
+            .collect();
+        if !matching.is_empty() {
+            let mut text = graphics::Text::new((matching.join("  "), font, self.font_scale()));
+            text.set_bounds([bounds.w, bounds.h / 2.0 - margin], graphics::Align::Left);
+            let dest_point = [bounds.x, input_box.bottom() + margin];
+            let param = graphics::DrawParam::new().dest(dest_point);
+            graphics::draw(ctx, &text, param)?;
+        }
+        Ok(())
+    }
+
+    /// Draw the last moves on the side screen (for the spectators).
+    fn draw_move_list(&self, ctx: &mut Context) -> GameResult {
+        let bounds = self.layout.move_list();
+        let font = graphics::Font::new(ctx, &self.theme().font_path)?;
+        let line_height = self.font_scale() * 1.4;
+        let num_lines = (bounds.h / line_height) as usize;
+        let start = self.move_list.len().saturating_sub(num_lines);
+        for (row, line) in self.move_list[start..].iter().enumerate() {
+            let text = graphics::Text::new((line.as_str(), font, self.font_scale()));
+            let dest_point = [bounds.x, bounds.y + row as f32 * line_height];
+            graphics::draw(ctx, &text, (dest_point,))?;
+        }
         Ok(())
     }
 
     /// Draw timers on the side screen.
     fn draw_timers(&self, ctx: &mut Context) -> GameResult {
         // Draw the rect background
-        let bounds_white = graphics::Rect::new(BOARD_PX_SIZE.0 + 20.0, 20.0, 115.0, 50.0);
-        let bounds_black = graphics::Rect::new(BOARD_PX_SIZE.0 + 155.0, 20.0, 115.0, 50.0);
+        let bounds_white = self.layout.timer(Color::White);
+        let bounds_black = self.layout.timer(Color::Black);
         let background_mesh_white = graphics::MeshBuilder::new()
             .rectangle(
                 graphics::DrawMode::fill(),
@@ -372,17 +2685,24 @@ impl ChessGui {
         graphics::draw(ctx, &background_mesh_black, graphics::DrawParam::default())?;
 
         // Draw the text
-        let text_white = format!("{}:{}", "--", "--");
-        let font = graphics::Font::new(ctx, self.theme.font_path)?;
-        let text_white = graphics::Text::new((text_white, font, self.theme.font_scale * 2.0));
+        let remaining = |color| match self.chess.clock() {
+            Some(clock) => {
+                let seconds = clock.remaining(color).as_secs_f32().ceil() as u64;
+                format!("{:02}:{:02}", seconds / 60, seconds % 60)
+            }
+            None => format!("{}:{}", "--", "--"),
+        };
+        let text_white = remaining(Color::White);
+        let font = graphics::Font::new(ctx, &self.theme().font_path)?;
+        let text_white = graphics::Text::new((text_white, font, self.font_scale() * 2.0));
         let dest_point = [
             bounds_white.x + (bounds_white.w - text_white.width(ctx)) / 2.0,
             bounds_white.y + (bounds_white.h - text_white.height(ctx)) / 2.0,
         ];
         graphics::draw(ctx, &text_white, (dest_point,))?;
-        let text_black = format!("{}:{}", "--", "--");
-        let font = graphics::Font::new(ctx, self.theme.font_path)?;
-        let text_black = graphics::Text::new((text_black, font, self.theme.font_scale * 2.0));
+        let text_black = remaining(Color::Black);
+        let font = graphics::Font::new(ctx, &self.theme().font_path)?;
+        let text_black = graphics::Text::new((text_black, font, self.font_scale() * 2.0));
         let dest_point = [
             bounds_black.x + (bounds_black.w - text_black.width(ctx)) / 2.0,
             bounds_black.y + (bounds_black.h - text_black.height(ctx)) / 2.0,
@@ -392,15 +2712,45 @@ impl ChessGui {
         Ok(())
     }
 
+    /// Draw the captured pieces and the material advantage of each player on the side screen,
+    /// in the position shown on the board.
+    fn draw_material(&self, ctx: &mut Context) -> GameResult {
+        let font = graphics::Font::new(ctx, &self.theme().font_path)?;
+        let board = self.displayed_board();
+        for color in ALL_COLORS {
+            let bounds = self.layout.material(color);
+            let (mut x, y) = (bounds.x, bounds.y);
+            let piece_px_size = bounds.h;
+            for piece in board.captured(color) {
+                // Pieces captured by a player are the pieces of his opponent
+                let path = &self.theme().piece_path[(!color).to_index()][piece.to_index()];
+                let image = graphics::Image::new(ctx, path).expect("Image load error");
+                let image_scale = [
+                    piece_px_size / image.width() as f32,
+                    piece_px_size / image.height() as f32,
+                ];
+                let dp = graphics::DrawParam::new().dest([x, y]).scale(image_scale);
+                graphics::draw(ctx, &image, dp)?;
+                // Pieces overlap to save space
+                x += piece_px_size * 0.6;
+            }
+            let advantage = board.material_advantage(color);
+            if advantage > 0 {
+                let text = graphics::Text::new((format!("+{advantage}"), font, self.font_scale()));
+                let dest_point = [
+                    x + piece_px_size * 0.6,
+                    y + (piece_px_size - text.height(ctx)) / 2.0,
+                ];
+                graphics::draw(ctx, &text, (dest_point,))?;
+            }
+        }
+        Ok(())
+    }
+
     /// Draw the winner on the side screen.
     fn draw_winner(&self, ctx: &mut Context) -> GameResult {
         // Draw the rect background
-        let bounds = graphics::Rect::new(
-            BOARD_PX_SIZE.0 + 20.0,
-            90.0,
-            320.0,
-            SIDE_SCREEN_PX_SIZE.1 - 250.0 - 70.0,
-        );
+        let bounds = self.layout.status();
         let background_mesh = graphics::MeshBuilder::new()
             .rectangle(
                 graphics::DrawMode::stroke(3.0),
@@ -412,6 +2762,20 @@ impl ChessGui {
 
         // Draw the text (9 caractères max avec une font_scale de 20.0)
         let text = match self.chess.state {
+            GameState::Ongoing
+                if self
+                    .network()
+                    .is_some_and(|session| !session.is_connected()) =>
+            {
+                "Waiting for\nthe opponent".to_string()
+            }
+            _ if self
+                .spectator
+                .as_ref()
+                .is_some_and(|spectator| !spectator.is_connected()) =>
+            {
+                "Waiting for\nthe game".to_string()
+            }
             GameState::Ongoing => {
                 let line1 = "Ongoing:".to_string();
                 let line2 = format!("{:?} turn", self.chess.board.side_to_move());
@@ -427,9 +2791,10 @@ impl ChessGui {
             GameState::DrawAccepted => "Draw: Accepted".to_string(),
             GameState::DrawDeclared => "Draw: Declared".to_string(),
             GameState::Resigns(color) => format!("{:?} resigns\n\n {:?} win !", color, !color),
+            GameState::Timeout(color) => format!("{:?} timeout\n\n {:?} win !", color, !color),
         };
-        let font = graphics::Font::new(ctx, self.theme.font_path)?;
-        let text = graphics::Text::new((text, font, self.theme.font_scale * 2.0));
+        let font = graphics::Font::new(ctx, &self.theme().font_path)?;
+        let text = graphics::Text::new((text, font, self.font_scale() * 2.0));
         let dest_point = [
             bounds.x + (bounds.w - text.width(ctx)) / 2.0,
             bounds.y + (bounds.h - text.height(ctx)) / 2.0,
@@ -437,13 +2802,262 @@ impl ChessGui {
         graphics::draw(ctx, &text, (dest_point,))?;
         Ok(())
     }
+
+    /// Draw the current puzzle in place of the state of the game: its rating, the result of
+    /// the last move and the statistics of the user.
+    fn draw_puzzle(&self, ctx: &mut Context, trainer: &PuzzleTrainer) -> GameResult {
+        let bounds = self.layout.status();
+        let frame = graphics::MeshBuilder::new()
+            .rectangle(
+                graphics::DrawMode::stroke(3.0),
+                bounds,
+                graphics::Color::new(0.7, 0.7, 0.7, 1.0),
+            )?
+            .build(ctx)?;
+        graphics::draw(ctx, &frame, graphics::DrawParam::default())?;
+
+        let attempt = trainer.attempt();
+        let green = graphics::Color::new(0.56, 0.78, 0.4, 1.0);
+        let red = graphics::Color::new(0.98, 0.3, 0.3, 1.0);
+        let (status, color) = if attempt.is_solution_shown() {
+            ("Solution shown".to_string(), red)
+        } else if attempt.is_solved() {
+            ("Solved!".to_string(), green)
+        } else {
+            match attempt.last_move() {
+                Some(PuzzleMove::Wrong) => ("Wrong move, try again".to_string(), red),
+                Some(PuzzleMove::Correct) => ("Best move! Keep going".to_string(), green),
+                _ => {
+                    let color = trainer.puzzle().color();
+                    (
+                        format!("Find the best move for {color}"),
+                        graphics::Color::WHITE,
+                    )
+                }
+            }
+        };
+        let stats = trainer.stats();
+        let font = graphics::Font::new(ctx, &self.theme().font_path)?;
+        let scale = self.font_scale();
+        let mut text = graphics::Text::new((
+            format!(
+                "Puzzle {}/{} ({})\n\n",
+                trainer.index() + 1,
+                trainer.len(),
+                trainer.puzzle().rating
+            ),
+            font,
+            scale,
+        ));
+        text.add(
+            graphics::TextFragment::new(status)
+                .font(font)
+                .scale(scale)
+                .color(color),
+        );
+        text.add(
+            graphics::TextFragment::new(format!(
+                "\n\nRating: {}\nSolved: {}/{} ({:.0}%)",
+                stats.rating,
+                stats.solved,
+                stats.attempts(),
+                stats.success_rate()
+            ))
+            .font(font)
+            .scale(scale),
+        );
+        let padding = 10.0 * self.layout.scale();
+        text.set_bounds(
+            [bounds.w - 2.0 * padding, bounds.h],
+            graphics::Align::Center,
+        );
+        let dest_point = [
+            bounds.x + padding,
+            bounds.y + (bounds.h - text.height(ctx)) / 2.0,
+        ];
+        graphics::draw(ctx, &text, (dest_point,))
+    }
+}
+
+/// Get the color of the arrows and circles drawn with the right button and the modifier
+/// keys: green by default, red with Shift, blue with Alt and yellow with Ctrl.
+fn annotation_color_of(keymod: KeyMods) -> AnnotationColor {
+    if keymod.contains(KeyMods::CTRL) {
+        AnnotationColor::Yellow
+    } else if keymod.contains(KeyMods::SHIFT) {
+        AnnotationColor::Red
+    } else if keymod.contains(KeyMods::ALT) {
+        AnnotationColor::Blue
+    } else {
+        AnnotationColor::Green
+    }
+}
+
+/// Get the color drawn on the board for an [`AnnotationColor`].
+fn annotation_color(color: AnnotationColor) -> graphics::Color {
+    match color {
+        AnnotationColor::Green => graphics::Color::new(0.08, 0.47, 0.11, 0.8),
+        AnnotationColor::Red => graphics::Color::new(0.53, 0.13, 0.13, 0.8),
+        AnnotationColor::Yellow => graphics::Color::new(0.9, 0.56, 0.0, 0.8),
+        AnnotationColor::Blue => graphics::Color::new(0.0, 0.19, 0.53, 0.8),
+    }
+}
+
+/// Get the color of the mark of a move on the evaluation graph (only for the bad moves).
+fn move_class_color(class: MoveClass) -> Option<graphics::Color> {
+    match class {
+        MoveClass::Best | MoveClass::Good => None,
+        MoveClass::Inaccuracy => Some(annotation_color(AnnotationColor::Yellow)),
+        MoveClass::Mistake => Some(graphics::Color::new(0.9, 0.35, 0.0, 1.0)),
+        MoveClass::Blunder => Some(graphics::Color::new(0.85, 0.1, 0.1, 1.0)),
+    }
+}
+
+/// Review the moves of a game with the engine of a player (the built-in engine searches at
+/// [`ANALYSIS_DEPTH`] for a human), the number of positions searched is sent on the way.
+fn review_game(
+    chess: &Chess,
+    player: &Player,
+    progress: &mpsc::Sender<ReviewUpdate>,
+) -> Result<GameReview, UciError> {
+    let mut searched = 0;
+    let mut report = || {
+        searched += 1;
+        let _ = progress.send(ReviewUpdate::Progress(searched));
+    };
+    match player {
+        Player::Uci(path) => {
+            let mut engine = UciEngine::spawn(path)?;
+            engine.set_movetime(REVIEW_MOVETIME);
+            let mut result = Ok(());
+            let review = GameReview::new(chess, |board| {
+                report();
+                // The engine has no move to send when the game is over
+                if result.is_err() || board.get_all_legal_moves().is_empty() {
+                    return None;
+                }
+                match engine.analyse(board) {
+                    Ok(analysis) => Some(analysis),
+                    Err(error) => {
+                        result = Err(error);
+                        None
+                    }
+                }
+            });
+            result.map(|()| review)
+        }
+        Player::Human | Player::Engine(_) | Player::Level(_) => {
+            let engine = Engine::new(player.depth().unwrap_or(ANALYSIS_DEPTH));
+            Ok(GameReview::new(chess, |board| {
+                report();
+                engine.analyse(board)
+            }))
+        }
+    }
+}
+
+/// Search the best move of a position with the engine of a player (the built-in engine
+/// searches at [`ANALYSIS_DEPTH`] for a human).
+fn search_hint(board: &Board, player: &Player) -> Option<ChessMove> {
+    match player {
+        Player::Uci(path) => UciEngine::spawn(path)
+            .and_then(|mut engine| engine.analyse(board))
+            .map(|analysis| analysis.best_move)
+            .map_err(|error| error!("{error}"))
+            .ok(),
+        Player::Human | Player::Engine(_) | Player::Level(_) => {
+            Engine::new(player.depth().unwrap_or(ANALYSIS_DEPTH)).best_move(board)
+        }
+    }
+}
+
+/// Get the score of a line from the point of view of white (in centipawns), with the number
+/// of moves before the checkmate (negative for a mate of black).
+fn white_score(line: &Analysis, side_to_move: Color) -> (i32, Option<i32>) {
+    match side_to_move {
+        Color::White => (line.score, line.mate_in()),
+        Color::Black => (-line.score, line.mate_in().map(|moves| -moves)),
+    }
+}
+
+/// Write a big number with a suffix (e.g. `12.3k` or `4.5M`).
+fn format_count(count: u64) -> String {
+    match count {
+        0..1_000 => count.to_string(),
+        1_000..1_000_000 => format!("{:.1}k", count as f64 / 1e3),
+        _ => format!("{:.1}M", count as f64 / 1e6),
+    }
+}
+
+/// Remove the optional characters of a move in SAN: the captures, the checks and the `=` of
+/// the promotions (e.g. `exd8=Q+` becomes `ed8Q`).
+fn simplify_san(text: &str) -> String {
+    text.replace(['x', '+', '#', '='], "")
 }
 
 impl event::EventHandler<GameError> for ChessGui {
     /// Update will happen on every frame before it is drawn.
-    fn update(&mut self, _ctx: &mut Context) -> GameResult {
+    fn update(&mut self, ctx: &mut Context) -> GameResult {
+        // The size is not always notified (e.g. at startup with a scale factor)
+        let (width, height) = graphics::drawable_size(ctx);
+        if self.layout.size() != (width, height) {
+            self.resize_event(ctx, width, height);
+        }
+        // The clocks wait for the other player of a network game, and the end of the editor
+        if self.editor.is_none() && self.network.as_ref().is_none_or(Session::is_connected) {
+            self.chess.tick(ggez::timer::delta(ctx));
+        }
+        if let Some(session) = &mut self.network {
+            let before = self.chess.board;
+            session.update(&mut self.chess);
+            self.animate_from(before);
+        }
+        self.update_spectator();
+        // The bots wait for the answer of the resume prompt, and the end of the editor
+        if self.resume.is_none() && self.editor.is_none() && self.puzzles.is_none() {
+            self.update_bots();
+        }
+        self.update_puzzle();
+        self.play_premove();
+        // The position shown was undone
+        if self.view.is_some_and(|ply| ply >= self.chess.moves.len()) {
+            self.view = None;
+        }
+        if self.editor.is_none() {
+            let board = self.displayed_board();
+            if let Some(analyser) = &mut self.analyser {
+                analyser.set_position(board);
+                analyser.update();
+            }
+        }
+        self.update_review();
+        self.update_hint();
+        if (self.chess.moves.len(), self.chess.state) != self.autosaved {
+            self.autosave();
+        }
+        if let Some(animation) = &mut self.animation {
+            animation.update(ggez::timer::delta(ctx));
+            if animation.is_finished() {
+                self.animation = None;
+            }
+        }
+        let network_guest = self.is_network_guest();
+        // A found hint can still be revealed when no hint is left
+        let unrevealed = matches!(
+            self.hint,
+            Some(Hint::Found {
+                revealed: false,
+                ..
+            })
+        );
+        let hint_available = self.network.is_none() && (self.hints_left() > 0 || unrevealed);
+        let puzzle_over = self
+            .puzzles
+            .as_ref()
+            .is_some_and(|trainer| trainer.attempt().is_over());
         for button in self.buttons.iter_mut() {
             match button.id {
+                "undo" | "reset" | "load" if network_guest => button.disable(),
                 "declare-draw" => {
                     if self.chess.can_declare_draw() {
                         button.enable();
@@ -458,13 +3072,27 @@ impl event::EventHandler<GameError> for ChessGui {
                         button.disable();
                     }
                 }
+                "hint" => {
+                    if hint_available {
+                        button.enable();
+                    } else {
+                        button.disable();
+                    }
+                }
+                "puzzle-solution" => {
+                    if puzzle_over {
+                        button.disable();
+                    } else {
+                        button.enable();
+                    }
+                }
                 _ => {}
             }
         }
-        if self.chess.state.is_finish() {
+        if self.chess.state.is_finish() && self.editor.is_none() && self.puzzles.is_none() {
             for button in self.buttons.iter_mut() {
                 match button.id {
-                    "reset" | "theme" => {}
+                    "reset" | "theme" | "settings" | "flip" | "save" | "load" => {}
                     _ => button.disable(),
                 }
             }
@@ -475,7 +3103,7 @@ impl event::EventHandler<GameError> for ChessGui {
     /// Render the game's current state.
     fn draw(&mut self, ctx: &mut Context) -> GameResult {
         // First we clear the screen and set the background color
-        graphics::clear(ctx, self.theme.background_color);
+        graphics::clear(ctx, self.theme().background_color);
 
         // Draw the board and the side screen (that contains all button/info)
         self.draw_board(ctx)?;
@@ -489,16 +3117,48 @@ impl event::EventHandler<GameError> for ChessGui {
         Ok(())
     }
 
-    /// Called every time a mouse button gets pressed
-    fn mouse_button_down_event(&mut self, _ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
-        if button == MouseButton::Left {
-            self.click(x, y);
+    /// Called when the window is closed, the game is saved to keep the clock.
+    fn quit_event(&mut self, _ctx: &mut Context) -> bool {
+        self.autosave();
+        false
+    }
+
+    /// Called every time a mouse button gets pressed.
+    ///
+    /// The right button cancels the premoves, or starts to draw an arrow (or a circle) until
+    /// it is released.
+    fn mouse_button_down_event(&mut self, ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        let overlay = self.resume.is_some()
+            || self.settings_open
+            || self.report_open
+            || self.editor.is_some();
+        match button {
+            MouseButton::Left => self.click(x, y),
+            MouseButton::Right if !self.premoves.is_empty() => self.clear_premoves(),
+            MouseButton::Right if !overlay => {
+                let color = annotation_color_of(ggez::input::keyboard::active_mods(ctx));
+                self.annotation_start = self.layout.square_at(x, y).map(|square| (square, color));
+            }
+            _ => {}
+        }
+    }
+
+    /// Called every time a mouse button gets released, the arrow (or the circle) drawn with
+    /// the right button is added.
+    fn mouse_button_up_event(&mut self, _ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        if button == MouseButton::Right {
+            let start = self.annotation_start.take();
+            if let (Some((from, color)), Some(to)) = (start, self.layout.square_at(x, y)) {
+                self.annotate(from, to, color);
+            }
         }
     }
 
-    /// Change the [`ggez::input::mouse::CursorIcon`] when the mouse is on a button.
+    /// Change the [`ggez::input::mouse::CursorIcon`] when the mouse is on a button
+    /// and keep track of the hovered [`Square`].
     fn mouse_motion_event(&mut self, ctx: &mut Context, x: f32, y: f32, _dx: f32, _dy: f32) {
-        if x > BOARD_PX_SIZE.0 {
+        self.square_hovered = self.layout.square_at(x, y);
+        if self.layout.side().contains([x, y]) {
             let mut on_button = false;
             for button in self.buttons.iter() {
                 if button.contains(x, y) {
@@ -514,15 +3174,39 @@ impl event::EventHandler<GameError> for ChessGui {
         }
     }
 
+    /// Called when the window is resized, layout everything according to the new size.
+    fn resize_event(&mut self, ctx: &mut Context, width: f32, height: f32) {
+        graphics::set_screen_coordinates(ctx, graphics::Rect::new(0.0, 0.0, width, height))
+            .expect("Failed to set the screen coordinates");
+        self.resize(width, height);
+    }
+
     /// Called every time a key gets pressed.
     ///
     /// # Keys
     ///
-    /// |  Keys  |          Actions           |
-    /// |--------|----------------------------|
-    /// | Escape | Quit the game              |
-    /// | R      | Reset the game and buttons |
-    /// | CTRL+Z | Undo                       |
+    /// |  Keys     |                  Actions                   |
+    /// |-----------|--------------------------------------------|
+    /// | Enter     | Resume the last game (on the prompt),      |
+    /// |           | play the typed move or select the square   |
+    /// |           | of the cursor                              |
+    /// | Arrows    | Move the cursor on the board               |
+    /// | CTRL+←/→  | Show the previous/next position            |
+    /// | Home/End  | Show the start/current position            |
+    /// | Backspace | Erase the last character of the typed move |
+    /// | Escape    | Start a new game (on the prompt),          |
+    /// |           | close the settings or the game report,     |
+    /// |           | clear the typed move and the cursor,       |
+    /// |           | close the puzzles, or quit                 |
+    /// | CTRL+A    | Start or stop the analysis                 |
+    /// | CTRL+E    | Open the board editor                      |
+    /// | CTRL+G    | Open or close the game report              |
+    /// | CTRL+H    | Show a hint, then its destination          |
+    /// | CTRL+R    | Reset the game and buttons                 |
+    /// | CTRL+Z    | Undo                                       |
+    ///
+    /// The other characters are typed in the move box. In the board editor, Enter puts the
+    /// selected piece on the square of the cursor and Escape closes the editor.
     fn key_down_event(
         &mut self,
         ctx: &mut Context,
@@ -530,21 +3214,80 @@ impl event::EventHandler<GameError> for ChessGui {
         keymod: KeyMods,
         _repeat: bool,
     ) {
+        let overlay = self.resume.is_some() || self.settings_open || self.report_open;
         match keycode {
+            KeyCode::Return if self.resume.is_some() => self.answer_resume(true),
+            KeyCode::Escape if self.resume.is_some() => self.answer_resume(false),
+            KeyCode::Escape if self.settings_open => self.toggle_settings_screen(),
+            KeyCode::Escape if self.report_open => self.toggle_report(),
+            KeyCode::G if keymod == KeyMods::CTRL && self.report_open => self.toggle_report(),
+            KeyCode::Escape if self.editor.is_some() => self.close_editor(),
+            _ if overlay => {}
+            KeyCode::Left if keymod == KeyMods::CTRL && self.editor.is_none() => {
+                self.show_ply(self.displayed_ply().saturating_sub(1))
+            }
+            KeyCode::Right if keymod == KeyMods::CTRL && self.editor.is_none() => {
+                self.show_ply(self.displayed_ply() + 1)
+            }
+            KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right => {
+                self.move_cursor(keycode)
+            }
+            KeyCode::Return | KeyCode::NumpadEnter | KeyCode::Space if self.editor.is_some() => {
+                if let Some(square) = self.cursor {
+                    self.edit(|editor| editor.put(square));
+                }
+            }
+            _ if self.editor.is_some() => {}
+            KeyCode::Escape if !self.move_input.is_empty() || self.cursor.is_some() => {
+                self.move_input.clear();
+                self.cursor = None;
+                self.chess.square_focused = None;
+            }
+            KeyCode::Escape if self.puzzles.is_some() => self.close_puzzles(),
             KeyCode::Escape => event::quit(ctx),
-            KeyCode::R => self.reset(),
-            KeyCode::Z if keymod == KeyMods::CTRL => self.chess.undo(),
+            KeyCode::Home => self.show_ply(0),
+            KeyCode::End => self.show_ply(self.chess.moves.len()),
+            KeyCode::A if keymod == KeyMods::CTRL => self.toggle_analysis(),
+            KeyCode::E if keymod == KeyMods::CTRL => self.open_editor(),
+            KeyCode::G if keymod == KeyMods::CTRL => self.toggle_report(),
+            KeyCode::H if keymod == KeyMods::CTRL => self.hint(),
+            KeyCode::R if keymod == KeyMods::CTRL => self.reset(),
+            KeyCode::Z if keymod == KeyMods::CTRL => self.undo(),
+            KeyCode::Back => {
+                self.move_input.pop();
+            }
+            KeyCode::Return | KeyCode::NumpadEnter if !self.move_input.is_empty() => {
+                self.submit_move_input()
+            }
+            KeyCode::Return | KeyCode::NumpadEnter | KeyCode::Space => {
+                if let Some(square) = self.cursor {
+                    if self.chess.state.is_ongoing() {
+                        self.click_on_board(square);
+                    }
+                }
+            }
             _ => {}
         };
     }
+
+    /// Called when a character is typed, the characters of a move are added to the move box.
+    fn text_input_event(&mut self, _ctx: &mut Context, character: char) {
+        let overlay = self.resume.is_some()
+            || self.settings_open
+            || self.report_open
+            || self.editor.is_some();
+        if !overlay && (character.is_ascii_alphanumeric() || "+#=-".contains(character)) {
+            self.move_input.push(character);
+        }
+    }
 }
 
 impl Default for ChessGui {
     fn default() -> Self {
         let mut chess_gui = ChessGui::new(
             Default::default(),
-            Default::default(),
-            Vec::with_capacity(7),
+            ThemeRegistry::discover(),
+            Vec::with_capacity(12),
         );
         chess_gui.init_buttons();
         chess_gui
@@ -1,6 +1,13 @@
-use std::fmt;
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
 
-use crate::Square;
+#[cfg(feature = "alloc")]
+use crate::{Board, Color, File, GameState, Piece, Rank};
+use crate::{Error, Square};
 
 /// Represent a ChessMove.
 #[derive(Clone, Copy, Eq, PartialEq, Debug)]
@@ -18,10 +25,13 @@ impl ChessMove {
         ChessMove { from, to }
     }
 
-    /*
     /// Convert a SAN (Standard Algebraic Notation) move into a [`ChessMove`].
     ///
-    /// TODO
+    /// The move must be legal on the given [`Board`]. The suffixes (`+`, `#`, `!`, `?`)
+    /// are ignored.
+    ///
+    /// > **Note**: A pawn is always promoted to a [`Queen`][Piece::Queen], so the promotion
+    /// > can be omitted and an under-promotion is invalid.
     ///
     /// ```
     /// use chess::{Board, ChessMove, Square};
@@ -31,290 +41,187 @@ impl ChessMove {
     ///     ChessMove::from_san(&board, "e4").expect("e4 is valid in the initial position"),
     ///     ChessMove::new(Square::E2, Square::E4)
     /// );
+    /// assert_eq!(
+    ///     ChessMove::from_san(&board, "Nf3").expect("Nf3 is valid in the initial position"),
+    ///     ChessMove::new(Square::G1, Square::F3)
+    /// );
+    /// assert!(ChessMove::from_san(&board, "e5").is_err());
     /// ```
+    #[cfg(feature = "alloc")]
     pub fn from_san(board: &Board, move_text: &str) -> Result<ChessMove, Error> {
+        let error = || Error::InvalidSanMove {
+            san: move_text.to_string(),
+        };
+        let text = move_text
+            .trim()
+            .trim_end_matches(" e.p.")
+            .trim_end_matches(['+', '#', '!', '?']);
+
         // Castles first...
-        if move_text == "O-O" || move_text == "O-O-O" {
+        let castle = match text {
+            "O-O" | "0-0" => Some(File::G),
+            "O-O-O" | "0-0-0" => Some(File::C),
+            _ => None,
+        };
+        if let Some(dest_file) = castle {
             let rank = match board.side_to_move() {
                 Color::White => Rank::First,
                 Color::Black => Rank::Eighth,
             };
-            let source_file = File::E;
-            let dest_file = if move_text == "O-O" { File::G } else { File::C };
-
             let m = ChessMove::new(
-                Square::make_square(source_file, rank),
+                Square::make_square(File::E, rank),
                 Square::make_square(dest_file, rank),
             );
-            return if board.is_legal(m) {
-                Ok(m)
-            } else {
-                Err(Error::InvalidSanMove)
+            return match board.on(m.from) {
+                Some((Piece::King, _)) if board.is_legal(m) => Ok(m),
+                _ => Err(error()),
             };
         }
 
         // forms of SAN moves
-        // a4 (Pawn moves to a4)
-        // exd4 (Pawn on e file takes on d4)
-        // xd4 (Illegal, source file must be specified)
-        // 1xd4 (Illegal, source file (not rank) must be specified)
+        // e4 (Pawn moves to e4)
+        // exd5 (Pawn on e file takes on d5)
+        // e8=Q or e8Q (Pawn moves to e8 and is promoted)
         // Nc3 (Knight (or any piece) on *some square* to c3
-        // Nb1c3 (Knight (or any piece) on b1 to c3
-        // Nbc3 (Knight on b file to c3)
-        // N1c3 (Knight on first rank to c3)
-        // Nb1xc3 (Knight on b1 takes on c3)
-        // Nbxc3 (Knight on b file takes on c3)
-        // N1xc3 (Knight on first rank takes on c3)
-        // Nc3+ (Knight moves to c3 with check)
-        // Nc3# (Knight moves to c3 with checkmate)
-
-        let error = Error::InvalidSanMove;
-        let mut cur_index: usize = 0;
-        let moving_piece = match move_text
-            .get(cur_index..(cur_index + 1))
-            .ok_or(error.clone())?
-        {
-            "N" => {
-                cur_index += 1;
-                Piece::Knight
-            }
-            "B" => {
-                cur_index += 1;
-                Piece::Bishop
-            }
-            "Q" => {
-                cur_index += 1;
-                Piece::Queen
-            }
-            "R" => {
-                cur_index += 1;
-                Piece::Rook
-            }
-            "K" => {
-                cur_index += 1;
-                Piece::King
-            }
-            _ => Piece::Pawn,
-        };
-
-        let mut source_file = match move_text
-            .get(cur_index..(cur_index + 1))
-            .ok_or(error.clone())?
-        {
-            "a" => {
-                cur_index += 1;
-                Some(File::A)
-            }
-            "b" => {
-                cur_index += 1;
-                Some(File::B)
-            }
-            "c" => {
-                cur_index += 1;
-                Some(File::C)
-            }
-            "d" => {
-                cur_index += 1;
-                Some(File::D)
-            }
-            "e" => {
-                cur_index += 1;
-                Some(File::E)
-            }
-            "f" => {
-                cur_index += 1;
-                Some(File::F)
-            }
-            "g" => {
-                cur_index += 1;
-                Some(File::G)
-            }
-            "h" => {
-                cur_index += 1;
-                Some(File::H)
-            }
-            _ => None,
-        };
-
-        let mut source_rank = match move_text
-            .get(cur_index..(cur_index + 1))
-            .ok_or(error.clone())?
-        {
-            "1" => {
-                cur_index += 1;
-                Some(Rank::First)
-            }
-            "2" => {
-                cur_index += 1;
-                Some(Rank::Second)
-            }
-            "3" => {
-                cur_index += 1;
-                Some(Rank::Third)
-            }
-            "4" => {
-                cur_index += 1;
-                Some(Rank::Fourth)
-            }
-            "5" => {
-                cur_index += 1;
-                Some(Rank::Fifth)
-            }
-            "6" => {
-                cur_index += 1;
-                Some(Rank::Sixth)
-            }
-            "7" => {
-                cur_index += 1;
-                Some(Rank::Seventh)
-            }
-            "8" => {
-                cur_index += 1;
-                Some(Rank::Eighth)
-            }
-            _ => None,
+        // Nbc3, N1c3, Nb1c3 (Knight on b file, first rank or b1 to c3)
+        // Nxc3, Nbxc3 (Knight takes on c3)
+        let text = match text.strip_suffix('Q') {
+            Some(text) if text.len() >= 2 => text.trim_end_matches('='),
+            _ => text,
         };
-
-        let takes = if let Some(s) = move_text.get(cur_index..(cur_index + 1)) {
-            match s {
-                "x" => {
-                    cur_index += 1;
-                    true
-                }
-                _ => false,
-            }
-        } else {
-            false
+        if text.ends_with(['N', 'B', 'R']) {
+            // Under-promotion is not supported
+            return Err(error());
+        }
+        let moving_piece = match text.chars().next() {
+            Some('N') => Piece::Knight,
+            Some('B') => Piece::Bishop,
+            Some('R') => Piece::Rook,
+            Some('Q') => Piece::Queen,
+            Some('K') => Piece::King,
+            _ => Piece::Pawn,
         };
-
-        let dest = if let Some(s) = move_text.get(cur_index..(cur_index + 2)) {
-            if let Ok(q) = Square::from_str(s) {
-                cur_index += 2;
-                q
-            } else {
-                let sq = Square::make_square(
-                    source_file.ok_or(error.clone())?,
-                    source_rank.ok_or(error.clone())?,
-                );
-                source_rank = None;
-                source_file = None;
-                sq
-            }
+        let text = if moving_piece == Piece::Pawn {
+            text
         } else {
-            let sq = Square::make_square(
-                source_file.ok_or(error.clone())?,
-                source_rank.ok_or(error.clone())?,
-            );
-            source_rank = None;
-            source_file = None;
-            sq
+            &text[1..]
         };
+        if text.len() < 2 || !text.is_char_boundary(text.len() - 2) {
+            return Err(error());
+        }
+        let (disambiguation, dest) = text.split_at(text.len() - 2);
+        let dest = Square::from_str(dest).map_err(|_| error())?;
+        let takes = disambiguation.ends_with('x');
+        let disambiguation = disambiguation.trim_end_matches('x');
 
-        let promotion = if let Some(s) = move_text.get(cur_index..(cur_index + 1)) {
-            match s {
-                "N" => {
-                    cur_index += 1;
-                    Some(Piece::Knight)
+        let mut source_file = None;
+        let mut source_rank = None;
+        for c in disambiguation.chars() {
+            match c {
+                'a'..='h' if source_file.is_none() => {
+                    source_file = Some(File::new(c as usize - 'a' as usize))
                 }
-                "B" => {
-                    cur_index += 1;
-                    Some(Piece::Bishop)
+                '1'..='8' if source_rank.is_none() => {
+                    source_rank = Some(Rank::new(c as usize - '1' as usize))
                 }
-                "R" => {
-                    cur_index += 1;
-                    Some(Piece::Rook)
-                }
-                "Q" => {
-                    cur_index += 1;
-                    Some(Piece::Queen)
-                }
-                _ => None,
+                _ => return Err(error()),
             }
-        } else {
-            None
-        };
-
-        if let Some(s) = move_text.get(cur_index..(cur_index + 1)) {
-            let _maybe_check_or_mate = match s {
-                "+" => {
-                    cur_index += 1;
-                    Some(false)
-                }
-                "#" => {
-                    cur_index += 1;
-                    Some(true)
-                }
-                _ => None,
-            };
+        }
+        // A pawn capture must specify the file
+        if moving_piece == Piece::Pawn && takes != source_file.is_some() {
+            return Err(error());
         }
 
-        let ep = if let Some(s) = move_text.get(cur_index..) {
-            s == " e.p."
-        } else {
-            false
-        };
-
-        //if ep {
-        //    cur_index += 5;
-        //}
-
-        // Ok, now we have all the data from the SAN move, in the following structures
-        // moveing_piece, source_rank, source_file, taks, dest, promotion, maybe_check_or_mate, and
-        // ep
-
-        todo!()
-        /*
-        let mut found_move: Option<ChessMove> = None;
-        for m in &mut MoveGen::new_legal(board) {
+        let mut found_move = None;
+        for m in board.get_all_legal_moves() {
             // check that the move has the properties specified
-            if board.piece_on(m.get_source()) != Some(moving_piece) {
+            if m.to != dest || !board.piece_on_is(m.from, moving_piece) {
                 continue;
             }
-
-            if let Some(rank) = source_rank {
-                if m.get_source().get_rank() != rank {
-                    continue;
-                }
-            }
-
-            if let Some(file) = source_file {
-                if m.get_source().get_file() != file {
-                    continue;
-                }
-            }
-
-            if m.get_dest() != dest {
+            if source_file.is_some_and(|file| m.from.file() != file)
+                || source_rank.is_some_and(|rank| m.from.rank() != rank)
+            {
                 continue;
             }
-
-            if m.get_promotion() != promotion {
-                continue;
-            }
-
             if found_move.is_some() {
-                return Err(error);
+                // Ambiguous move
+                return Err(error());
             }
+            found_move = Some(m);
+        }
+        found_move.ok_or_else(error)
+    }
 
-            // takes is complicated, because of e.p.
-            if !takes {
-                if board.piece_on(m.get_dest()).is_some() {
-                    continue;
+    /// Convert the [`ChessMove`] into a SAN (Standard Algebraic Notation) string.
+    ///
+    /// Assume that the [`ChessMove`] is legal on the given [`Board`].
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use chess::{Board, ChessMove, Square};
+    ///
+    /// let board = Board::from_str("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1").unwrap();
+    /// assert_eq!(ChessMove::new(Square::E1, Square::G1).to_san(&board), "O-O");
+    /// assert_eq!(ChessMove::new(Square::A1, Square::D1).to_san(&board), "Rd1");
+    /// assert_eq!(ChessMove::new(Square::A1, Square::A8).to_san(&board), "Ra8+");
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn to_san(&self, board: &Board) -> String {
+        let (piece, side) = board.on(self.from).expect("a piece to move");
+        let mut san = String::new();
+        if piece == Piece::King && self.distance() == 2 && self.from.rank() == self.to.rank() {
+            san.push_str(if self.to.file() == File::G {
+                "O-O"
+            } else {
+                "O-O-O"
+            });
+        } else {
+            let takes = board.is_occupied(self.to)
+                || (piece == Piece::Pawn && self.from.file() != self.to.file());
+            if piece == Piece::Pawn {
+                if takes {
+                    san.push_str(&self.from.to_string()[..1]);
                 }
-            }
-
-            if !ep && takes {
-                if board.piece_on(m.get_dest()).is_none() {
-                    continue;
+            } else {
+                san.push_str(&piece.to_string(Color::White));
+                // Disambiguate with the other pieces of the same kind going to the same square
+                let others: Vec<Square> = board
+                    .get_all_legal_moves()
+                    .into_iter()
+                    .filter(|m| {
+                        m.to == self.to && m.from != self.from && board.piece_on_is(m.from, piece)
+                    })
+                    .map(|m| m.from)
+                    .collect();
+                if !others.is_empty() {
+                    let from = self.from.to_string();
+                    if others.iter().all(|other| other.file() != self.from.file()) {
+                        san.push_str(&from[..1]);
+                    } else if others.iter().all(|other| other.rank() != self.from.rank()) {
+                        san.push_str(&from[1..]);
+                    } else {
+                        san.push_str(&from);
+                    }
                 }
             }
-
-            found_move = Some(m);
+            if takes {
+                san.push('x');
+            }
+            san.push_str(&self.to.to_string());
+            if piece == Piece::Pawn && self.to.rank_for(side) == Rank::Eighth {
+                san.push_str("=Q");
+            }
         }
 
-        found_move.ok_or(error.clone())
-
-        */
-    } */
+        let mut after = *board;
+        after.update(*self);
+        match after.state() {
+            GameState::Checkmates(_) => san.push('#'),
+            _ if after.is_check() => san.push('+'),
+            _ => {}
+        }
+        san
+    }
 
     /// The distance between the two [`Square`] of the move.
     ///
@@ -335,3 +242,106 @@ impl fmt::Display for ChessMove {
         write!(f, "{}{}", self.from, self.to)
     }
 }
+
+impl FromStr for ChessMove {
+    type Err = Error;
+
+    /// From the UCI (Universal Chess Interface) notation (e.g. `e2e4` or `e7e8q`).
+    ///
+    /// > **Note**: A pawn is always promoted to a [`Queen`][Piece::Queen], so only the `q`
+    /// > promotion is valid.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || Error::invalid_uci_move(s);
+        let (from, to, promotion) = match s.len() {
+            4 | 5 if s.is_ascii() => (&s[0..2], &s[2..4], &s[4..]),
+            _ => return Err(error()),
+        };
+        if !promotion.is_empty() && promotion != "q" {
+            return Err(error());
+        }
+        Ok(ChessMove::new(
+            Square::from_str(from).map_err(|_| error())?,
+            Square::from_str(to).map_err(|_| error())?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    fn san(fen: &str, san: &str) -> Result<ChessMove, Error> {
+        ChessMove::from_san(&Board::from_str(fen).unwrap(), san)
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn from_san_disambiguation() {
+        let fen = "4k3/8/8/8/8/8/4K3/R6R w - - 0 1";
+        assert_eq!(san(fen, "Rad1"), Ok(ChessMove::new(Square::A1, Square::D1)));
+        assert_eq!(san(fen, "Rhd1"), Ok(ChessMove::new(Square::H1, Square::D1)));
+        assert!(san(fen, "Rd1").is_err());
+        assert!(san(fen, "O-O").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn from_san_pawn() {
+        let fen = "4k3/1P6/8/3pP3/8/8/8/4K3 w - d6 0 1";
+        assert_eq!(
+            san(fen, "exd6 e.p."),
+            Ok(ChessMove::new(Square::E5, Square::D6))
+        );
+        assert_eq!(san(fen, "e6"), Ok(ChessMove::new(Square::E5, Square::E6)));
+        assert_eq!(
+            san(fen, "b8=Q+"),
+            Ok(ChessMove::new(Square::B7, Square::B8))
+        );
+        assert_eq!(san(fen, "b8"), Ok(ChessMove::new(Square::B7, Square::B8)));
+        assert!(san(fen, "b8=N").is_err());
+        assert!(san(fen, "xd6").is_err());
+        assert!(san(fen, "").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn to_san_round_trip() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "4k3/1P6/8/3pP3/8/8/8/4K3 w - d6 0 1",
+            "1N3k2/8/8/8/8/8/8/1N2K3 w - - 0 1",
+        ] {
+            let board = Board::from_str(fen).unwrap();
+            for m in board.get_all_legal_moves() {
+                assert_eq!(ChessMove::from_san(&board, &m.to_san(&board)), Ok(m));
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn to_san_mate() {
+        let board = Board::from_str("6k1/5ppp/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+        assert_eq!(
+            ChessMove::new(Square::A1, Square::A8).to_san(&board),
+            "Ra8#"
+        );
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(
+            ChessMove::from_str("e2e4"),
+            Ok(ChessMove::new(Square::E2, Square::E4))
+        );
+        assert_eq!(
+            ChessMove::from_str("a7a8q"),
+            Ok(ChessMove::new(Square::A7, Square::A8))
+        );
+        for uci in ["e2", "e2e9", "a7a8n", "e2e4qq", "é2e4"] {
+            assert_eq!(ChessMove::from_str(uci), Err(Error::invalid_uci_move(uci)));
+        }
+    }
+}
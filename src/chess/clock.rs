@@ -0,0 +1,178 @@
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+
+use crate::{Color, Error, NUM_COLORS};
+
+/// The time control of a game: an initial time and an increment per move.
+///
+/// It is written `<minutes>+<seconds>` (e.g. `5+3` for 5 minutes and 3 seconds per move).
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+/// use std::time::Duration;
+/// use chess::TimeControl;
+///
+/// let time_control = TimeControl::from_str("5+3").unwrap();
+/// assert_eq!(time_control.initial, Duration::from_secs(300));
+/// assert_eq!(time_control.increment, Duration::from_secs(3));
+/// assert_eq!(time_control.to_string(), "5+3");
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimeControl {
+    /// The time of each player at the beginning of the game.
+    pub initial: Duration,
+    /// The time added to a player after each of his moves.
+    pub increment: Duration,
+}
+
+impl TimeControl {
+    /// Create a new [`TimeControl`].
+    pub fn new(initial: Duration, increment: Duration) -> Self {
+        TimeControl { initial, increment }
+    }
+}
+
+impl FromStr for TimeControl {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || Error::invalid_time_control(s);
+        let (minutes, seconds) = s.trim().split_once('+').ok_or_else(error)?;
+        let minutes: u64 = minutes.trim().parse().map_err(|_| error())?;
+        let seconds: u64 = seconds.trim().parse().map_err(|_| error())?;
+        if minutes == 0 {
+            return Err(error());
+        }
+        Ok(TimeControl::new(
+            Duration::from_secs(minutes * 60),
+            Duration::from_secs(seconds),
+        ))
+    }
+}
+
+impl fmt::Display for TimeControl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}+{}",
+            self.initial.as_secs() / 60,
+            self.increment.as_secs()
+        )
+    }
+}
+
+/// The chess clock of the two players.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use chess::{Clock, Color, TimeControl};
+///
+/// let mut clock = Clock::new(TimeControl::new(Duration::from_secs(60), Duration::from_secs(2)));
+/// clock.consume(Color::White, Duration::from_secs(10));
+/// clock.add_increment(Color::White);
+///
+/// assert_eq!(clock.remaining(Color::White), Duration::from_secs(52));
+/// assert_eq!(clock.remaining(Color::Black), Duration::from_secs(60));
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Clock {
+    time_control: TimeControl,
+    remaining: [Duration; NUM_COLORS],
+}
+
+impl Clock {
+    /// Create a new [`Clock`], both players have the initial time.
+    pub fn new(time_control: TimeControl) -> Self {
+        Clock {
+            time_control,
+            remaining: [time_control.initial; NUM_COLORS],
+        }
+    }
+
+    /// Get the [`TimeControl`] of the clock.
+    pub fn time_control(&self) -> TimeControl {
+        self.time_control
+    }
+
+    /// Get the remaining time of a player.
+    pub fn remaining(&self, color: Color) -> Duration {
+        self.remaining[color.to_index()]
+    }
+
+    /// Set the remaining time of a player.
+    pub fn set_remaining(&mut self, color: Color, remaining: Duration) {
+        self.remaining[color.to_index()] = remaining;
+    }
+
+    /// Consume the time of a player.
+    ///
+    /// Return true if the flag of the player falls (i.e. no time left).
+    pub fn consume(&mut self, color: Color, delta: Duration) -> bool {
+        let remaining = &mut self.remaining[color.to_index()];
+        *remaining = remaining.saturating_sub(delta);
+        self.is_flagged(color)
+    }
+
+    /// Add the increment to a player (after his move).
+    pub fn add_increment(&mut self, color: Color) {
+        if !self.is_flagged(color) {
+            self.remaining[color.to_index()] += self.time_control.increment;
+        }
+    }
+
+    /// Verify if a player has no time left.
+    pub fn is_flagged(&self, color: Color) -> bool {
+        self.remaining(color).is_zero()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_control_from_str() {
+        assert_eq!(
+            TimeControl::from_str("10+0"),
+            Ok(TimeControl::new(Duration::from_secs(600), Duration::ZERO))
+        );
+        assert_eq!(
+            TimeControl::from_str(" 3 + 2 "),
+            Ok(TimeControl::new(
+                Duration::from_secs(180),
+                Duration::from_secs(2)
+            ))
+        );
+    }
+
+    #[test]
+    fn time_control_from_str_error() {
+        for s in ["", "10", "10+", "+2", "a+b", "0+5", "-1+0"] {
+            assert_eq!(
+                TimeControl::from_str(s),
+                Err(Error::invalid_time_control(s))
+            );
+        }
+    }
+
+    #[test]
+    fn consume() {
+        let mut clock = Clock::new(TimeControl::from_str("1+5").unwrap());
+        assert!(!clock.consume(Color::Black, Duration::from_secs(59)));
+        assert!(clock.consume(Color::Black, Duration::from_secs(2)));
+        assert!(clock.is_flagged(Color::Black));
+        assert_eq!(clock.remaining(Color::Black), Duration::ZERO);
+
+        // No increment once the flag fell
+        clock.add_increment(Color::Black);
+        assert!(clock.is_flagged(Color::Black));
+        assert!(!clock.is_flagged(Color::White));
+    }
+}
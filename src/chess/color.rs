@@ -1,7 +1,12 @@
-use std::ops::Not;
+use core::fmt;
+use core::ops::Not;
+use core::str::FromStr;
+
+use crate::Error;
 
 /// Represent a color in Chess game.
 #[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Color {
     White,
     Black,
@@ -33,3 +38,38 @@ impl Not for Color {
         }
     }
 }
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Color::White => write!(f, "white"),
+            Color::Black => write!(f, "black"),
+        }
+    }
+}
+
+impl FromStr for Color {
+    type Err = Error;
+
+    /// Only `white` or `black`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "white" => Ok(Color::White),
+            "black" => Ok(Color::Black),
+            _ => Err(Error::InvalidColor),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        for color in ALL_COLORS {
+            assert_eq!(Color::from_str(&color.to_string()), Ok(color));
+        }
+        assert_eq!(Color::from_str("White"), Err(Error::InvalidColor));
+    }
+}
@@ -5,20 +5,144 @@
 //! | foo.0     | x-axes |
 //! | foo.1     | y-axes |
 
-/// The pixel-size of the board only (the board have a square form).
+#[cfg(feature = "std")]
+use core::time::Duration;
+
+// Core
+
+/// Number of cells in the Board.
+pub const BOARD_SIZE: (i16, i16) = (8, 8);
+
+/// The maximum number of moves of a [`Piece`][crate::Piece] (a queen in the center of
+/// an empty board).
+pub const MAX_PIECE_MOVES: usize = 27;
+
+/// The maximum number of legal moves in a position.
+pub const MAX_LEGAL_MOVES: usize = 218;
+
+/// The default depth (in half-moves) of the [`Engine`][crate::Engine].
+#[cfg(feature = "alloc")]
+pub const ENGINE_DEPTH: u32 = 3;
+
+/// The maximum depth (in half-moves) of the built-in [`Engine`][crate::Engine] in the
+/// [`Analyser`][crate::Analyser].
+#[cfg(feature = "std")]
+pub const ANALYSIS_DEPTH: u32 = 5;
+
+/// The default number of lines searched by the [`Analyser`][crate::Analyser].
+#[cfg(feature = "std")]
+pub const ANALYSIS_LINES: usize = 3;
+
+/// The default time an [`UciEngine`][crate::UciEngine] can think for each move.
+#[cfg(feature = "std")]
+pub const UCI_MOVETIME: Duration = Duration::from_secs(1);
+
+/// The time an [`UciEngine`][crate::UciEngine] can think for each position of a
+/// [`GameReview`][crate::GameReview].
+#[cfg(feature = "std")]
+pub const REVIEW_MOVETIME: Duration = Duration::from_millis(200);
+
+/// The puzzle rating of a new user (see [`PuzzleStats`][crate::PuzzleStats]).
+#[cfg(feature = "std")]
+pub const PUZZLE_START_RATING: u32 = 1500;
+
+/// The maximum change of the puzzle rating after a puzzle (the K-factor of the Elo rating).
+#[cfg(feature = "std")]
+pub const PUZZLE_K_FACTOR: f64 = 32.0;
+
+/// The delay between two attempts to join a game hosted on the network
+/// (see [`Session::join`][crate::Session::join]).
+#[cfg(feature = "std")]
+pub const NETWORK_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// The interval between two reads of the PGN file followed by a
+/// [`Spectator`][crate::Spectator].
+#[cfg(feature = "std")]
+pub const SPECTATOR_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// Server
+
+/// The interval between two updates of the clocks of the [`GameServer`][crate::GameServer]
+/// (and between two reads of a client connection).
+#[cfg(feature = "server")]
+pub const SERVER_TICK: Duration = Duration::from_millis(50);
+
+/// The interval between two `clock` events sent to the clients of a game
+/// (see [`serve`][crate::serve]).
+#[cfg(feature = "server")]
+pub const SERVER_CLOCK_SYNC_INTERVAL: Duration = Duration::from_secs(1);
+
+// Tui
+
+/// The interval between two draws of the [`ChessTui`][crate::ChessTui] (and between two
+/// updates of its clocks) when no key is pressed.
+#[cfg(feature = "tui")]
+pub const TUI_TICK: Duration = Duration::from_millis(100);
+
+// Gui
+
+/// The default pixel-size of the board only (the board have a square form).
+///
+/// The window is resizable, the GUI is scaled according to this size (see [`Layout`][crate::Layout]).
+#[cfg(feature = "gui")]
 pub const BOARD_PX_SIZE: (f32, f32) = (800.0, 800.0);
 
-/// The pixel-size of the side screen.
+/// The default pixel-size of the side screen.
+#[cfg(feature = "gui")]
 pub const SIDE_SCREEN_PX_SIZE: (f32, f32) = (360.0, BOARD_PX_SIZE.1);
 
-/// The pixel-size of the screen.
+/// The default pixel-size of the screen.
+#[cfg(feature = "gui")]
 pub const SCREEN_PX_SIZE: (f32, f32) = (BOARD_PX_SIZE.0 + SIDE_SCREEN_PX_SIZE.0, BOARD_PX_SIZE.1);
 
-/// Number of cells in the Board.
-pub const BOARD_SIZE: (i16, i16) = (8, 8);
+/// The minimum pixel-size of the screen.
+#[cfg(feature = "gui")]
+pub const MIN_SCREEN_PX_SIZE: (f32, f32) = (400.0, 400.0);
 
-/// The pixel-size of a Board's cell.
+/// The default pixel-size of a Board's cell.
+#[cfg(feature = "gui")]
 pub const BOARD_CELL_PX_SIZE: (f32, f32) = (
     BOARD_PX_SIZE.0 / BOARD_SIZE.0 as f32,
     BOARD_PX_SIZE.1 / BOARD_SIZE.1 as f32,
 );
+
+/// The default duration of the pieces animations.
+#[cfg(feature = "gui")]
+pub const ANIMATION_DURATION: Duration = Duration::from_millis(200);
+
+/// The directory of the resources (images, fonts, sounds), the root of the paths of the
+/// [`Theme`][crate::Theme].
+#[cfg(feature = "gui")]
+pub const RESOURCES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/resources");
+
+/// The directory where the [`Theme`][crate::Theme] files are discovered at startup.
+#[cfg(feature = "gui")]
+pub const THEMES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/resources/themes");
+
+/// The default number of hints of the local player in a game
+/// (see [`Settings::hints_per_game`][crate::Settings::hints_per_game]).
+#[cfg(feature = "gui")]
+pub const HINTS_PER_GAME: usize = 3;
+
+/// The name of the [`Settings`][crate::Settings] file in the user config directory.
+#[cfg(feature = "gui")]
+pub const SETTINGS_FILE_NAME: &str = "settings.toml";
+
+/// The name of the file in the user data directory where the game in progress is saved
+/// after every move.
+#[cfg(feature = "gui")]
+pub const AUTOSAVE_FILE_NAME: &str = "autosave.pgn";
+
+/// The name of the file in the user data directory used by the Save and Load buttons.
+#[cfg(feature = "gui")]
+pub const SAVED_GAME_FILE_NAME: &str = "saved-game.pgn";
+
+/// The name of the file in the user data directory where the game report exports the
+/// annotated game.
+#[cfg(feature = "gui")]
+pub const REVIEW_FILE_NAME: &str = "review.pgn";
+
+/// The name of the file in the user data directory where the statistics of the puzzles are
+/// saved (see [`PuzzleStats`][crate::PuzzleStats]).
+#[cfg(feature = "gui")]
+pub const PUZZLE_STATS_FILE_NAME: &str = "puzzle-stats.txt";
@@ -0,0 +1,343 @@
+//! Play a correspondence game: every move is sent to the opponent as a small game file.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::{Chess, ChessMove, Error, Pgn};
+
+/// Tag of a correspondence file with the number of half-moves of the game.
+pub const PLY_TAG: &str = "Ply";
+
+/// Tag of a correspondence file with the checksum of the game (see [`Correspondence::checksum`]).
+pub const CHECKSUM_TAG: &str = "Checksum";
+
+/// Error that can happen when exporting or importing a correspondence move.
+#[derive(Error, Debug)]
+pub enum CorrespondenceError {
+    /// The correspondence file can't be read or written.
+    #[error("Can't access the correspondence file {}: {}", path.display(), source)]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    /// The file is not a valid PGN game (or a move is illegal).
+    #[error("Invalid correspondence file: {}", source)]
+    Pgn {
+        #[from]
+        source: Error,
+    },
+
+    /// No move has been played, there is nothing to send.
+    #[error("The game doesn't contain any move")]
+    NoMove,
+
+    /// The file has no checksum or no ply, it was not exported as a correspondence move.
+    #[error("The file is not a correspondence move (missing {} tag)", tag)]
+    MissingTag { tag: &'static str },
+
+    /// The moves or the position of the file have been modified after the export.
+    #[error(
+        "The file has been modified: its checksum is {} but its game gives {}",
+        found,
+        expected
+    )]
+    Tampered { expected: String, found: String },
+
+    /// The ply tag of the file doesn't match its moves.
+    #[error(
+        "The file has been modified: its ply is {} but it contains {} half-moves",
+        found,
+        expected
+    )]
+    PlyMismatch { expected: usize, found: String },
+
+    /// The file is not the next move of the game (an old, repeated or skipped move).
+    #[error(
+        "Move out of order: the game waits for the half-move {}, the file contains the half-move {}",
+        expected,
+        found
+    )]
+    OutOfOrder { expected: usize, found: usize },
+
+    /// The moves before the last one are not the moves of the game.
+    #[error("The file doesn't continue this game, the previous moves differ")]
+    Diverged,
+
+    /// The last move of the file is not legal in the game.
+    #[error("Illegal move {} in the game", invalid_move)]
+    IllegalMove { invalid_move: ChessMove },
+}
+
+/// A move of a correspondence game, exchanged as a PGN file.
+///
+/// The file contains the whole game in PGN with two more tags: the number of half-moves
+/// ([`PLY_TAG`]) and a checksum of the game ([`CHECKSUM_TAG`]). When it is
+/// [applied][Correspondence::apply] to the game of the opponent, the checksum, the sequence
+/// of the moves and the legality of the last move are verified.
+///
+/// The checksum detects the modifications of the file, it is not a cryptographic signature.
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+/// use chess::{Chess, Correspondence, Square};
+///
+/// // Alice plays and sends the file
+/// let mut alice = Chess::default();
+/// alice.play(Square::E2, Square::E4);
+/// let file = Correspondence::from_chess(&alice).unwrap().to_string();
+///
+/// // Bob imports the move in his game
+/// let mut bob = Chess::default();
+/// Correspondence::from_str(&file).unwrap().apply(&mut bob).unwrap();
+/// assert_eq!(bob.board(), alice.board());
+///
+/// // The same file can't be imported twice
+/// assert!(Correspondence::from_str(&file).unwrap().apply(&mut bob).is_err());
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Correspondence {
+    game: Chess,
+}
+
+impl Correspondence {
+    /// Export the last move of the game.
+    pub fn from_chess(chess: &Chess) -> Result<Self, CorrespondenceError> {
+        if chess.moves.is_empty() {
+            return Err(CorrespondenceError::NoMove);
+        }
+        Ok(Correspondence {
+            game: chess.clone(),
+        })
+    }
+
+    /// Get the number of half-moves of the game, the last move is the half-move `ply`.
+    pub fn ply(&self) -> usize {
+        self.game.moves.len()
+    }
+
+    /// Get the last move of the game, the one sent to the opponent.
+    pub fn last_move(&self) -> ChessMove {
+        self.game
+            .last_move()
+            .expect("a correspondence game has a move")
+    }
+
+    /// Get the checksum of the game: a 64-bit FNV-1a hash of the starting position,
+    /// the moves (in UCI) and the final position (in FEN).
+    pub fn checksum(&self) -> String {
+        let moves: Vec<String> = self.game.moves.iter().map(ToString::to_string).collect();
+        let text = format!(
+            "{}\n{}\n{}",
+            self.game.start_board(),
+            moves.join(" "),
+            self.game.board
+        );
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for byte in text.bytes() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        format!("{hash:016x}")
+    }
+
+    /// Play the move of the file in the game of the opponent.
+    ///
+    /// The file must contain the game with one more move, this move must be legal.
+    /// A draw offered with the move is kept.
+    pub fn apply(&self, chess: &mut Chess) -> Result<ChessMove, CorrespondenceError> {
+        let expected = chess.moves.len() + 1;
+        let found = self.ply();
+        if found != expected {
+            return Err(CorrespondenceError::OutOfOrder { expected, found });
+        }
+        if self.game.start_board() != chess.start_board()
+            || self.game.moves[..found - 1] != chess.moves[..]
+        {
+            return Err(CorrespondenceError::Diverged);
+        }
+        let m = self.last_move();
+        if !chess.state.is_ongoing() || !chess.board.is_legal(m) {
+            return Err(CorrespondenceError::IllegalMove { invalid_move: m });
+        }
+        chess.play(m.from, m.to);
+        if self.game.offer_draw {
+            chess.offer_draw();
+        }
+        Ok(m)
+    }
+
+    /// Save the move in a file, the parent directories are created if needed.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), CorrespondenceError> {
+        let path = path.as_ref();
+        let io_error = |source| CorrespondenceError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        fs::write(path, self.to_string()).map_err(io_error)
+    }
+
+    /// Load a move from a file, the checksum is verified.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, CorrespondenceError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|source| CorrespondenceError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Correspondence::from_str(&content)
+    }
+}
+
+impl fmt::Display for Correspondence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut pgn = Pgn::from_chess(&self.game);
+        pgn.set_tag(PLY_TAG, &self.ply().to_string());
+        pgn.set_tag(CHECKSUM_TAG, &self.checksum());
+        write!(f, "{pgn}")
+    }
+}
+
+impl FromStr for Correspondence {
+    type Err = CorrespondenceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let pgn = Pgn::from_str(s)?;
+        let tag = |tag| pgn.tag(tag).ok_or(CorrespondenceError::MissingTag { tag });
+        let (ply, found) = (tag(PLY_TAG)?, tag(CHECKSUM_TAG)?);
+        let correspondence = Correspondence::from_chess(&pgn.to_chess()?)?;
+        let expected = correspondence.checksum();
+        if found != expected {
+            return Err(CorrespondenceError::Tampered {
+                expected,
+                found: found.to_string(),
+            });
+        }
+        if ply != correspondence.ply().to_string() {
+            return Err(CorrespondenceError::PlyMismatch {
+                expected: correspondence.ply(),
+                found: ply.to_string(),
+            });
+        }
+        Ok(correspondence)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Square;
+
+    /// Play a move and export it.
+    fn send(chess: &mut Chess, from: Square, to: Square) -> String {
+        chess.play(from, to);
+        Correspondence::from_chess(chess).unwrap().to_string()
+    }
+
+    fn receive(chess: &mut Chess, file: &str) -> Result<ChessMove, CorrespondenceError> {
+        Correspondence::from_str(file)?.apply(chess)
+    }
+
+    #[test]
+    fn play_by_files() {
+        let (mut white, mut black) = (Chess::default(), Chess::default());
+        let file = send(&mut white, Square::E2, Square::E4);
+        assert!(file.contains("[Ply \"1\"]"));
+        assert_eq!(
+            receive(&mut black, &file).unwrap(),
+            ChessMove::new(Square::E2, Square::E4)
+        );
+        // A draw is offered with the move
+        black.play(Square::C7, Square::C5);
+        black.offer_draw();
+        let file = Correspondence::from_chess(&black).unwrap().to_string();
+        receive(&mut white, &file).unwrap();
+        assert!(white.offer_draw);
+        assert_eq!(white.board(), black.board());
+        assert!(matches!(
+            Correspondence::from_chess(&Chess::default()),
+            Err(CorrespondenceError::NoMove)
+        ));
+    }
+
+    #[test]
+    fn out_of_order() {
+        let (mut white, mut black) = (Chess::default(), Chess::default());
+        let first = send(&mut white, Square::E2, Square::E4);
+        receive(&mut black, &first).unwrap();
+        let second = send(&mut black, Square::E7, Square::E5);
+        let third = send(&mut black, Square::G1, Square::F3);
+
+        // Imported twice, or a move is skipped
+        assert!(matches!(
+            receive(&mut black, &first),
+            Err(CorrespondenceError::OutOfOrder {
+                expected: 4,
+                found: 1
+            })
+        ));
+        assert!(matches!(
+            receive(&mut white, &third),
+            Err(CorrespondenceError::OutOfOrder {
+                expected: 2,
+                found: 3
+            })
+        ));
+        receive(&mut white, &second).unwrap();
+
+        // Another game
+        let mut other = Chess::default();
+        other.play(Square::D2, Square::D4);
+        assert!(matches!(
+            receive(&mut other, &second),
+            Err(CorrespondenceError::Diverged)
+        ));
+    }
+
+    #[test]
+    fn tampered() {
+        let mut white = Chess::default();
+        let file = send(&mut white, Square::E2, Square::E4);
+        let error = receive(&mut Chess::default(), &file.replace("1. e4", "1. d4"));
+        assert!(matches!(error, Err(CorrespondenceError::Tampered { .. })));
+        let error = receive(
+            &mut Chess::default(),
+            &file.replace("[Ply \"1\"]", "[Ply \"3\"]"),
+        );
+        assert!(matches!(
+            error,
+            Err(CorrespondenceError::PlyMismatch { expected: 1, ref found }) if found == "3"
+        ));
+        let error = receive(&mut Chess::default(), &file.replace("1. e4", "1. e5"));
+        assert!(matches!(error, Err(CorrespondenceError::Pgn { .. })));
+
+        let pgn = Pgn::from_chess(&white).to_string();
+        assert!(matches!(
+            receive(&mut Chess::default(), &pgn),
+            Err(CorrespondenceError::MissingTag { tag: PLY_TAG })
+        ));
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join("rg-chess-test-correspondence.pgn");
+        let mut chess = Chess::default();
+        chess.play(Square::G1, Square::F3);
+        let correspondence = Correspondence::from_chess(&chess).unwrap();
+        correspondence.save(&path).unwrap();
+        assert_eq!(Correspondence::load(&path).unwrap(), correspondence);
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            Correspondence::load(&path),
+            Err(CorrespondenceError::Io { .. })
+        ));
+    }
+}
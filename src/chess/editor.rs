@@ -0,0 +1,242 @@
+//! Set up a position: place the pieces and choose the rights before playing from it.
+
+use alloc::string::{String, ToString};
+use core::str::FromStr;
+
+use crate::{Board, CastleRights, Chess, Color, Error, Piece, PositionError, Square};
+
+/// The position being set up by the editor of the GUI.
+///
+/// A piece of the palette is [selected][BoardEditor::select] then [put][BoardEditor::put]
+/// on the squares, with no piece selected the squares are emptied. The side to move, the
+/// castle rights and the en passant square are set apart. The position is only
+/// [played][BoardEditor::to_chess] when it is [valid][Board::validate].
+///
+/// # Examples
+///
+/// ```
+/// use chess::{BoardEditor, Color, Piece, PositionError, Square};
+///
+/// let mut editor = BoardEditor::default();
+/// editor.clear();
+/// editor.select(Some((Piece::King, Color::White)));
+/// editor.put(Square::E1);
+/// assert_eq!(editor.validate(), Err(PositionError::KingCount(Color::Black)));
+///
+/// editor.select(Some((Piece::King, Color::Black)));
+/// editor.put(Square::E8);
+/// assert_eq!(editor.fen(), "4k3/8/8/8/8/8/8/4K3 w - - 0 1");
+/// let chess = editor.to_chess().unwrap();
+/// assert_eq!(chess.board(), editor.board());
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct BoardEditor {
+    board: Board,
+    selected: Option<(Piece, Color)>,
+}
+
+impl BoardEditor {
+    /// Edit the given position.
+    pub fn new(board: Board) -> Self {
+        BoardEditor {
+            board,
+            selected: None,
+        }
+    }
+
+    /// Get the position being set up.
+    pub fn board(&self) -> Board {
+        self.board
+    }
+
+    /// Get the piece of the palette put on the squares ([`None`] to empty them).
+    pub fn selected(&self) -> Option<(Piece, Color)> {
+        self.selected
+    }
+
+    /// Select the piece of the palette put on the squares ([`None`] to empty them).
+    pub fn select(&mut self, selected: Option<(Piece, Color)>) {
+        self.selected = selected;
+    }
+
+    /// Put the selected piece on the square, or empty it if the piece is already there.
+    pub fn put(&mut self, square: Square) {
+        self.board[square] = match self.board.on(square) == self.selected {
+            true => None,
+            false => self.selected,
+        };
+    }
+
+    /// Remove all the pieces (and the rights that depend on them).
+    pub fn clear(&mut self) {
+        let side = self.board.side_to_move();
+        self.board = Board::new();
+        self.board.set_side_to_move(side);
+    }
+
+    /// Set up the initial position.
+    pub fn start_position(&mut self) {
+        self.board = Board::default();
+    }
+
+    /// Give the move to the other side, the en passant square is removed.
+    pub fn toggle_side_to_move(&mut self) {
+        self.board.set_side_to_move(!self.board.side_to_move());
+        self.board.set_en_passant(None);
+    }
+
+    /// Give or remove a castle right (the [`CastleRights::KingSide`] or the
+    /// [`CastleRights::QueenSide`]) of a side.
+    pub fn toggle_castle_rights(&mut self, color: Color, castle_rights: CastleRights) {
+        let index = self.board.castle_rights(color).to_index() ^ castle_rights.to_index();
+        self.board
+            .set_castle_rights(color, CastleRights::from_index(index));
+    }
+
+    /// Set the next possible en passant square (see [`Board::en_passant_targets`]), after the
+    /// last one there is no en passant square.
+    pub fn next_en_passant(&mut self) {
+        let targets = self.board.en_passant_targets();
+        let next = match self.board.en_passant() {
+            Some(square) => targets
+                .iter()
+                .skip_while(|&&target| target != square)
+                .nth(1),
+            None => targets.first(),
+        };
+        self.board.set_en_passant(next.copied());
+    }
+
+    /// Get the position in FEN.
+    pub fn fen(&self) -> String {
+        self.board.to_string()
+    }
+
+    /// Set up the position of a FEN (e.g. pasted from the clipboard).
+    pub fn set_fen(&mut self, fen: &str) -> Result<(), Error> {
+        self.board = Board::from_str(fen.trim())?;
+        Ok(())
+    }
+
+    /// Verify if the position can be played (see [`Board::validate`]).
+    pub fn validate(&self) -> Result<(), PositionError> {
+        self.board.validate()
+    }
+
+    /// Create a new game from the position (if it is valid).
+    pub fn to_chess(&self) -> Result<Chess, PositionError> {
+        self.validate()?;
+        Ok(Chess::new(self.board))
+    }
+}
+
+impl Default for BoardEditor {
+    fn default() -> Self {
+        BoardEditor::new(Board::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn put() {
+        let mut editor = BoardEditor::default();
+        editor.put(Square::E2);
+        assert_eq!(editor.board().on(Square::E2), None);
+        editor.select(Some((Piece::Queen, Color::Black)));
+        editor.put(Square::E2);
+        assert_eq!(
+            editor.board().on(Square::E2),
+            Some((Piece::Queen, Color::Black))
+        );
+        // Put again: removed
+        editor.put(Square::E2);
+        assert_eq!(editor.board().on(Square::E2), None);
+    }
+
+    #[test]
+    fn rights() {
+        let mut editor = BoardEditor::default();
+        editor.toggle_castle_rights(Color::White, CastleRights::KingSide);
+        editor.toggle_castle_rights(Color::Black, CastleRights::Both);
+        assert_eq!(
+            editor.fen(),
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w Q - 0 1"
+        );
+        editor.toggle_castle_rights(Color::White, CastleRights::KingSide);
+        assert_eq!(editor.validate(), Ok(()));
+
+        // The rook has moved
+        editor.select(None);
+        editor.put(Square::H1);
+        assert_eq!(
+            editor.validate(),
+            Err(PositionError::InvalidCastleRights(Color::White))
+        );
+        editor.clear();
+        assert_eq!(editor.fen(), "8/8/8/8/8/8/8/8 w - - 0 1");
+    }
+
+    #[test]
+    fn en_passant() {
+        let mut editor = BoardEditor::default();
+        editor
+            .set_fen("4k3/8/8/1pP1p1P1/8/8/8/4K3 w - - 0 1")
+            .unwrap();
+        editor.next_en_passant();
+        assert_eq!(editor.board().en_passant(), Some(Square::B6));
+        editor.next_en_passant();
+        assert_eq!(editor.board().en_passant(), Some(Square::E6));
+        editor.next_en_passant();
+        assert_eq!(editor.board().en_passant(), None);
+        editor.next_en_passant();
+        assert_eq!(editor.validate(), Ok(()));
+
+        // Not a pawn pushed by the opponent
+        editor.toggle_side_to_move();
+        assert_eq!(editor.board().en_passant(), None);
+        editor.set_fen("4k3/8/8/8/8/8/8/4K3 w - e6 0 1").unwrap();
+        assert_eq!(editor.validate(), Err(PositionError::InvalidEnPassant));
+    }
+
+    #[test]
+    fn validate() {
+        let mut editor = BoardEditor::default();
+        for (fen, error) in [
+            (
+                "4k3/8/8/8/8/8/8/8 w - - 0 1",
+                PositionError::KingCount(Color::White),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/3KK3 w - - 0 1",
+                PositionError::KingCount(Color::White),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/P3K3 w - - 0 1",
+                PositionError::PawnOnBackRank,
+            ),
+            (
+                "4k2R/8/8/8/8/8/8/4K3 w - - 0 1",
+                PositionError::OpponentInCheck,
+            ),
+            (
+                "4k3/8/8/8/8/8/8/4K3 w K - 0 1",
+                PositionError::InvalidCastleRights(Color::White),
+            ),
+            (
+                "4k3/pppppppp/8/8/8/p7/8/4K3 w - - 0 1",
+                PositionError::TooManyPawns(Color::Black),
+            ),
+        ] {
+            editor.set_fen(fen).unwrap();
+            assert_eq!(editor.validate(), Err(error), "{fen}");
+            assert_eq!(editor.to_chess(), Err(error));
+        }
+        // The side to move can be in check, or checkmate
+        editor.set_fen("4k2R/8/8/8/8/8/8/4K3 b - - 0 1").unwrap();
+        assert!(editor.to_chess().is_ok());
+        assert!(editor.set_fen("not a fen").is_err());
+    }
+}
@@ -0,0 +1,351 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{Board, ChessMove, Color, GameState, Piece, Rank, Square, ALL_SQUARES, ENGINE_DEPTH};
+
+/// The score of a checkmate (in centipawns), the distance to the mate is subtracted.
+pub const MATE_SCORE: i32 = 100_000;
+
+/// The result of the analysis of a position.
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Analysis {
+    /// The best move found.
+    pub best_move: ChessMove,
+    /// The score of the position in centipawns, from the point of view of the player who
+    /// has to play (see [`MATE_SCORE`]).
+    pub score: i32,
+    /// The principal variation: the best sequence of moves for both players.
+    pub pv: Vec<ChessMove>,
+    /// The depth of the search (in half-moves).
+    pub depth: u32,
+}
+
+impl Analysis {
+    /// Get the number of moves before the checkmate (if found).
+    ///
+    /// A positive number is a mate for the player who has to play, a negative one a mate
+    /// against him.
+    pub fn mate_in(&self) -> Option<i32> {
+        let distance = MATE_SCORE - self.score.abs();
+        if distance > MATE_SCORE / 2 {
+            return None;
+        }
+        let moves = (distance + 1) / 2;
+        Some(if self.score > 0 { moves } else { -moves })
+    }
+
+    /// Write the principal variation in SAN with the move numbers (e.g. `12... Nf6 13. e5`),
+    /// from the analysed position.
+    pub fn pv_san(&self, board: &Board) -> String {
+        let mut board = *board;
+        let mut line = Vec::new();
+        for (index, &m) in self.pv.iter().enumerate() {
+            if !board.is_legal(m) {
+                break;
+            }
+            match board.side_to_move() {
+                Color::White => line.push(format!("{}.", board.fullmoves())),
+                Color::Black if index == 0 => line.push(format!("{}...", board.fullmoves())),
+                Color::Black => {}
+            }
+            line.push(m.to_san(&board));
+            board.update(m);
+        }
+        line.join(" ")
+    }
+
+    /// Write the score from the point of view of white: in pawns (e.g. `+0.35`) or the
+    /// number of moves before the checkmate (e.g. `#3`, `-#2` for a mate of black).
+    pub fn score_text(&self, side_to_move: Color) -> String {
+        let sign = match side_to_move {
+            Color::White => 1,
+            Color::Black => -1,
+        };
+        match self.mate_in() {
+            Some(moves) if sign * moves > 0 => format!("#{}", moves.abs()),
+            Some(moves) => format!("-#{}", moves.abs()),
+            None => format!("{:+.2}", (sign * self.score) as f32 / 100.0),
+        }
+    }
+}
+
+/// A simple chess engine: an alpha-beta search with a material evaluation.
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+/// use chess::{Board, ChessMove, Engine, Square};
+///
+/// // The rook can mate on the 8th rank
+/// let board = Board::from_str("6k1/5ppp/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+/// let analysis = Engine::new(2).analyse(&board).unwrap();
+///
+/// assert_eq!(analysis.best_move, ChessMove::new(Square::A1, Square::A8));
+/// assert_eq!(analysis.mate_in(), Some(1));
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Engine {
+    depth: u32,
+}
+
+impl Engine {
+    /// Create an engine that searches at the given depth (in half-moves, at least 1).
+    pub fn new(depth: u32) -> Self {
+        Engine {
+            depth: depth.max(1),
+        }
+    }
+
+    /// Get the depth of the search (in half-moves).
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// Evaluate a position without search (in centipawns), from the point of view of the
+    /// player who has to play.
+    ///
+    /// The evaluation is the material plus small bonuses for the pieces in the center and
+    /// the advanced pawns.
+    pub fn evaluate(board: &Board) -> i32 {
+        let mut score = 0;
+        for square in ALL_SQUARES {
+            if let Some((piece, color)) = board.on(square) {
+                let value =
+                    piece.value() as i32 * 100 + Engine::position_bonus(piece, color, square);
+                if color == board.side_to_move() {
+                    score += value;
+                } else {
+                    score -= value;
+                }
+            }
+        }
+        score
+    }
+
+    /// The bonus of a [`Piece`] according to its [`Square`].
+    fn position_bonus(piece: Piece, color: Color, square: Square) -> i32 {
+        // Distance to the center: 0 for d4, d5, e4, e5 and 3 for the corners
+        let center = |index: usize| (2 * index as i32 - 7).abs() / 2;
+        let centralization =
+            6 - center(square.file().to_index()) - center(square.rank().to_index());
+        match piece {
+            Piece::Pawn => {
+                let advance =
+                    square.rank_for(color).to_index() as i32 - Rank::Second.to_index() as i32;
+                5 * advance + centralization
+            }
+            Piece::Knight | Piece::Bishop => 5 * centralization,
+            Piece::Queen => 2 * centralization,
+            Piece::Rook | Piece::King => 0,
+        }
+    }
+
+    /// Search the best move of the position.
+    ///
+    /// Return [`None`] if the game is over (no legal move).
+    pub fn analyse(&self, board: &Board) -> Option<Analysis> {
+        let mut nodes = 0;
+        let (score, pv) = self.negamax(
+            board,
+            self.depth,
+            0,
+            -MATE_SCORE - 1,
+            MATE_SCORE + 1,
+            &mut nodes,
+        );
+        let best_move = *pv.first()?;
+        Some(Analysis {
+            best_move,
+            score,
+            pv,
+            depth: self.depth,
+        })
+    }
+
+    /// Get the best move of the position (if exist).
+    pub fn best_move(&self, board: &Board) -> Option<ChessMove> {
+        self.analyse(board).map(|analysis| analysis.best_move)
+    }
+
+    /// Search the best lines of the position (MultiPV): each line starts with another move.
+    ///
+    /// Return at most `count` lines, from the best to the worst (none if the game is over),
+    /// and the number of positions searched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::{Board, Engine};
+    ///
+    /// let board = Board::default();
+    /// let (lines, nodes) = Engine::new(2).analyse_lines(&board, 3);
+    /// assert_eq!(lines.len(), 3);
+    /// assert!(lines[0].score >= lines[1].score && lines[1].score >= lines[2].score);
+    /// assert!(nodes > 20);
+    /// ```
+    pub fn analyse_lines(&self, board: &Board, count: usize) -> (Vec<Analysis>, u64) {
+        let count = count.max(1);
+        let mut nodes = 0;
+        let mut lines: Vec<Analysis> = vec![];
+        for m in Engine::ordered_moves(board) {
+            // Only the moves better than the last line are searched exactly
+            let alpha = match lines.len() >= count {
+                true => lines[count - 1].score,
+                false => -MATE_SCORE - 1,
+            };
+            let mut next = *board;
+            next.update(m);
+            let (score, mut pv) = self.negamax(
+                &next,
+                self.depth - 1,
+                1,
+                -MATE_SCORE - 1,
+                -alpha,
+                &mut nodes,
+            );
+            let score = -score;
+            if lines.len() < count || score > alpha {
+                pv.insert(0, m);
+                let index = lines.partition_point(|line| line.score >= score);
+                lines.insert(
+                    index,
+                    Analysis {
+                        best_move: m,
+                        score,
+                        pv,
+                        depth: self.depth,
+                    },
+                );
+                lines.truncate(count);
+            }
+        }
+        (lines, nodes)
+    }
+
+    /// Get the legal moves, the captures of the most valuable pieces first to prune more.
+    fn ordered_moves(board: &Board) -> Vec<ChessMove> {
+        let mut moves = board.get_all_legal_moves();
+        moves.sort_by_key(|m| {
+            core::cmp::Reverse(board.piece_on(m.to).map_or(0, |piece| piece.value() + 1))
+        });
+        moves
+    }
+
+    /// Negamax search with alpha-beta pruning, return the score and the principal variation.
+    ///
+    /// The positions searched are counted in `nodes`.
+    fn negamax(
+        &self,
+        board: &Board,
+        depth: u32,
+        ply: i32,
+        mut alpha: i32,
+        beta: i32,
+        nodes: &mut u64,
+    ) -> (i32, Vec<ChessMove>) {
+        *nodes += 1;
+        let moves = Engine::ordered_moves(board);
+        if moves.is_empty() {
+            return match board.state() {
+                GameState::Checkmates(_) => (-MATE_SCORE + ply, vec![]),
+                _ => (0, vec![]),
+            };
+        }
+        if depth == 0 {
+            return (Engine::evaluate(board), vec![]);
+        }
+
+        let mut best = (-MATE_SCORE - 1, vec![]);
+        for m in moves {
+            let mut next = *board;
+            next.update(m);
+            let (score, mut pv) = self.negamax(&next, depth - 1, ply + 1, -beta, -alpha, nodes);
+            let score = -score;
+            if score > best.0 {
+                pv.insert(0, m);
+                best = (score, pv);
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        best
+    }
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Engine::new(ENGINE_DEPTH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn evaluate() {
+        assert_eq!(Engine::evaluate(&Board::default()), 0);
+
+        let board = Board::from_str("4k3/8/8/8/8/8/8/3QK3 w - - 0 1").unwrap();
+        assert!(Engine::evaluate(&board) > 800);
+        let board = Board::from_str("4k3/8/8/8/8/8/8/3QK3 b - - 0 1").unwrap();
+        assert!(Engine::evaluate(&board) < -800);
+    }
+
+    #[test]
+    fn capture() {
+        // The queen is hanging
+        let board = Board::from_str("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            Engine::new(1).best_move(&board),
+            Some(ChessMove::new(Square::D2, Square::D5))
+        );
+    }
+
+    #[test]
+    fn mate_in_two() {
+        let board = Board::from_str("k7/8/2K5/8/8/8/8/1R6 w - - 0 1").unwrap();
+        let analysis = Engine::new(3).analyse(&board).unwrap();
+        assert_eq!(analysis.mate_in(), Some(2));
+        assert_eq!(analysis.pv.len(), 3);
+    }
+
+    #[test]
+    fn lines() {
+        // Only one move saves the queen, the other lines lose it
+        let board = Board::from_str("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1").unwrap();
+        let (lines, _) = Engine::new(2).analyse_lines(&board, 3);
+        assert_eq!(lines[0], Engine::new(2).analyse(&board).unwrap());
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].score < lines[0].score - 500);
+        assert_ne!(lines[1].best_move, lines[2].best_move);
+        assert!(lines[0].pv_san(&board).starts_with("1. Rxd5 K"));
+        assert_eq!(lines[0].score_text(Color::White), "+5.00");
+
+        // Less legal moves than lines
+        let board = Board::from_str("k7/8/2K5/8/8/8/8/1R6 b - - 0 1").unwrap();
+        let (lines, _) = Engine::new(2).analyse_lines(&board, 5);
+        assert_eq!(lines.len(), 1);
+
+        // Black mates
+        let board = Board::from_str("6K1/8/6k1/8/8/8/8/1r6 b - - 0 1").unwrap();
+        let (lines, _) = Engine::new(1).analyse_lines(&board, 2);
+        assert_eq!(lines[0].score_text(Color::Black), "-#1");
+        assert_eq!(lines[0].pv_san(&board), "1... Rb8#");
+    }
+
+    #[test]
+    fn game_over() {
+        let board = Board::from_str("k7/1R6/1K6/8/8/8/8/8 b - - 0 1").unwrap();
+        assert_eq!(Engine::default().analyse(&board), None);
+    }
+}
@@ -1,30 +1,132 @@
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, string::String};
+
 use thiserror::Error;
 
-use crate::{Board, ChessMove};
+#[cfg(feature = "alloc")]
+use crate::Board;
+use crate::{ChessMove, Color};
 
 /// Error that can happen during the game.
 ///
+/// Without the `alloc` feature, the variants don't keep the invalid text.
+///
 /// derive from PartialEq for UnitTest
 #[derive(Error, Clone, PartialEq, Eq, Debug)]
+#[allow(missing_copy_implementations)] // Only without the `alloc` feature
 pub enum Error {
     /// The move on a particular board doesn't respect the chess rules.
-    #[error(
-        "Invalid move ({}) on the given board ({}) according to the chess rule",
-        invalid_move,
-        board
+    #[cfg_attr(
+        feature = "alloc",
+        error(
+            "Invalid move ({}) on the given board ({}) according to the chess rule",
+            invalid_move,
+            board
+        )
+    )]
+    #[cfg_attr(
+        not(feature = "alloc"),
+        error("Invalid move ({}) according to the chess rule", invalid_move)
     )]
     InvalidMove {
-        board: Board,
+        #[cfg(feature = "alloc")]
+        board: Box<Board>,
         invalid_move: ChessMove,
     },
 
     /// The FEN (Forsyth-Edwards Notation) string is invalid.
-    #[error("Invalid FEN string: {}", fen)]
-    InvalidFen { fen: String },
+    #[cfg_attr(feature = "alloc", error("Invalid FEN string: {}", fen))]
+    #[cfg_attr(not(feature = "alloc"), error("Invalid FEN string"))]
+    InvalidFen {
+        #[cfg(feature = "alloc")]
+        fen: String,
+    },
+
+    /// The PGN (Portable Game Notation) text is invalid.
+    #[cfg_attr(feature = "alloc", error("Invalid PGN: {}", reason))]
+    #[cfg_attr(not(feature = "alloc"), error("Invalid PGN"))]
+    InvalidPgn {
+        #[cfg(feature = "alloc")]
+        reason: String,
+    },
+
+    /// The player string is invalid (expected `human`, `engine`, `engine:<depth>`,
+    /// `level:<level>`, `elo:<rating>` or a path).
+    #[cfg_attr(feature = "alloc", error("Invalid player: {}", player))]
+    #[cfg_attr(not(feature = "alloc"), error("Invalid player"))]
+    InvalidPlayer {
+        #[cfg(feature = "alloc")]
+        player: String,
+    },
+
+    /// The level string is invalid (expected `novice`, `beginner`, `casual`, `intermediate`,
+    /// `advanced` or `expert`).
+    #[cfg_attr(feature = "alloc", error("Invalid level: {}", level))]
+    #[cfg_attr(not(feature = "alloc"), error("Invalid level"))]
+    InvalidLevel {
+        #[cfg(feature = "alloc")]
+        level: String,
+    },
+
+    /// The puzzle is invalid (see [`Puzzle`][crate::Puzzle]).
+    #[cfg_attr(feature = "alloc", error("Invalid puzzle: {}", reason))]
+    #[cfg_attr(not(feature = "alloc"), error("Invalid puzzle"))]
+    InvalidPuzzle {
+        #[cfg(feature = "alloc")]
+        reason: String,
+    },
+
+    /// The time control string is invalid (expected `<minutes>+<seconds>`).
+    #[cfg_attr(
+        feature = "alloc",
+        error(
+            "Invalid time control: {} (expected <minutes>+<seconds>)",
+            time_control
+        )
+    )]
+    #[cfg_attr(
+        not(feature = "alloc"),
+        error("Invalid time control (expected <minutes>+<seconds>)")
+    )]
+    InvalidTimeControl {
+        #[cfg(feature = "alloc")]
+        time_control: String,
+    },
 
-    /// An attempt was made to create a move from an invalid SAN string.
-    #[error("The string specified does not contain a valid SAN notation move")]
-    InvalidSanMove,
+    /// An attempt was made to create a move from an invalid SAN string
+    /// (or the move is not legal on the board).
+    #[cfg_attr(
+        feature = "alloc",
+        error(
+            "The string specified does not contain a valid SAN notation move: {}",
+            san
+        )
+    )]
+    #[cfg_attr(
+        not(feature = "alloc"),
+        error("The string specified does not contain a valid SAN notation move")
+    )]
+    InvalidSanMove {
+        #[cfg(feature = "alloc")]
+        san: String,
+    },
+
+    /// An attempt was made to create a move from an invalid UCI string.
+    #[cfg_attr(
+        feature = "alloc",
+        error(
+            "The string specified does not contain a valid UCI notation move: {}",
+            uci
+        )
+    )]
+    #[cfg_attr(
+        not(feature = "alloc"),
+        error("The string specified does not contain a valid UCI notation move")
+    )]
+    InvalidUciMove {
+        #[cfg(feature = "alloc")]
+        uci: String,
+    },
 
     /// An attempt was made to create a square from an invalid string.
     #[error("The string specified does not contain a valid algebraic notation square")]
@@ -37,4 +139,67 @@ pub enum Error {
     /// An attempt was made to convert a string not equal to "a"-"h" to a file.
     #[error("The string specified does not contain a valid file")]
     InvalidFile,
+
+    /// An attempt was made to convert a string not equal to "white" or "black" to a color.
+    #[error("The string specified does not contain a valid color")]
+    InvalidColor,
+}
+
+/// Reason why a position can't be played (see [`Board::validate`][crate::Board::validate]).
+#[derive(Error, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PositionError {
+    /// A side has no king or more than one.
+    #[error("The {} side must have exactly one king", _0)]
+    KingCount(Color),
+
+    /// A side has more than 8 pawns.
+    #[error("The {} side has more than 8 pawns", _0)]
+    TooManyPawns(Color),
+
+    /// A side has more than 16 pieces.
+    #[error("The {} side has more than 16 pieces", _0)]
+    TooManyPieces(Color),
+
+    /// A pawn is on the first or the last rank.
+    #[error("A pawn can't be on the first or the last rank")]
+    PawnOnBackRank,
+
+    /// The king of the side that doesn't play is in check.
+    #[error("The side that doesn't play can't be in check")]
+    OpponentInCheck,
+
+    /// A side can castle without its king or its rook on their initial square.
+    #[error(
+        "The {} side can't castle without its king and rook on their squares",
+        _0
+    )]
+    InvalidCastleRights(Color),
+
+    /// No pawn has just been pushed by two squares to the en passant square.
+    #[error("The en passant square doesn't follow a pawn pushed by two squares")]
+    InvalidEnPassant,
+}
+
+// The errors built by the core, the invalid text is only kept with the `alloc` feature.
+impl Error {
+    pub(crate) fn invalid_fen(_fen: &str) -> Self {
+        Error::InvalidFen {
+            #[cfg(feature = "alloc")]
+            fen: _fen.into(),
+        }
+    }
+
+    pub(crate) fn invalid_time_control(_time_control: &str) -> Self {
+        Error::InvalidTimeControl {
+            #[cfg(feature = "alloc")]
+            time_control: _time_control.into(),
+        }
+    }
+
+    pub(crate) fn invalid_uci_move(_uci: &str) -> Self {
+        Error::InvalidUciMove {
+            #[cfg(feature = "alloc")]
+            uci: _uci.into(),
+        }
+    }
 }
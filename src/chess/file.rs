@@ -1,4 +1,5 @@
-use std::str::FromStr;
+use core::fmt;
+use core::str::FromStr;
 
 use crate::{Error, BOARD_SIZE};
 
@@ -77,6 +78,12 @@ impl File {
     }
 }
 
+impl fmt::Display for File {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", (b'a' + *self as u8) as char)
+    }
+}
+
 impl FromStr for File {
     type Err = Error;
 
@@ -176,4 +183,11 @@ mod tests {
         assert_eq!(File::from_str(" a"), Err(Error::InvalidFile));
         assert_eq!(File::from_str("A"), Err(Error::InvalidFile));
     }
+
+    #[test]
+    fn display() {
+        for file in ALL_FILES {
+            assert_eq!(File::from_str(&file.to_string()), Ok(file));
+        }
+    }
 }
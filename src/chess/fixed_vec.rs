@@ -0,0 +1,175 @@
+//! A vector with a fixed capacity, used by the move generation without allocation.
+
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ops::Deref;
+use core::slice;
+
+use crate::{ChessMove, Square, MAX_LEGAL_MOVES, MAX_PIECE_MOVES};
+
+/// The destinations of a [`Piece`][crate::Piece] (see [`Board::get_legal_moves_fixed`][crate::Board::get_legal_moves_fixed]).
+pub type SquareList = FixedVec<Square, MAX_PIECE_MOVES>;
+
+/// The moves of a position (see [`Board::get_all_legal_moves_fixed`][crate::Board::get_all_legal_moves_fixed]).
+pub type MoveList = FixedVec<ChessMove, MAX_LEGAL_MOVES>;
+
+/// A vector of at most `N` items, stored inline (i.e. without heap allocation).
+///
+/// It dereferences to a slice, so it can be used like a `Vec` that can't grow.
+///
+/// ```
+/// use chess::{Board, Square};
+///
+/// let moves = Board::default().get_legal_moves_fixed(Square::G1);
+/// assert_eq!(moves.len(), 2);
+/// assert!(moves.contains(&Square::F3));
+/// assert!(moves.contains(&Square::H3));
+/// ```
+#[derive(Clone, Copy)]
+pub struct FixedVec<T: Copy, const N: usize> {
+    items: [MaybeUninit<T>; N],
+    len: usize,
+}
+
+impl<T: Copy, const N: usize> FixedVec<T, N> {
+    /// Create an empty [`FixedVec`].
+    pub const fn new() -> Self {
+        FixedVec {
+            items: [MaybeUninit::uninit(); N],
+            len: 0,
+        }
+    }
+
+    /// The maximum number of items.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Append an item at the end.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [`FixedVec`] is full.
+    pub fn push(&mut self, item: T) {
+        assert!(self.len < N, "FixedVec capacity ({N}) exceeded");
+        self.items[self.len] = MaybeUninit::new(item);
+        self.len += 1;
+    }
+
+    /// Extract a slice containing the items.
+    pub fn as_slice(&self) -> &[T] {
+        // SAFETY: the first `len` items have been initialized by `push`.
+        unsafe { slice::from_raw_parts(self.items.as_ptr().cast(), self.len) }
+    }
+}
+
+impl<T: Copy, const N: usize> Default for FixedVec<T, N> {
+    fn default() -> Self {
+        FixedVec::new()
+    }
+}
+
+impl<T: Copy, const N: usize> Deref for FixedVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<T: Copy + fmt::Debug, const N: usize> fmt::Debug for FixedVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Copy + PartialEq, const N: usize, const M: usize> PartialEq<FixedVec<T, M>>
+    for FixedVec<T, N>
+{
+    fn eq(&self, other: &FixedVec<T, M>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Copy + Eq, const N: usize> Eq for FixedVec<T, N> {}
+
+impl<T: Copy + PartialEq, const N: usize, const M: usize> PartialEq<[T; M]> for FixedVec<T, N> {
+    fn eq(&self, other: &[T; M]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<T: Copy + PartialEq, const N: usize> PartialEq<[T]> for FixedVec<T, N> {
+    fn eq(&self, other: &[T]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<'a, T: Copy, const N: usize> IntoIterator for &'a FixedVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: Copy, const N: usize> IntoIterator for FixedVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            vec: self,
+            index: 0,
+        }
+    }
+}
+
+/// An iterator that moves out of a [`FixedVec`].
+#[derive(Clone, Debug)]
+pub struct IntoIter<T: Copy, const N: usize> {
+    vec: FixedVec<T, N>,
+    index: usize,
+}
+
+impl<T: Copy, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.vec.get(self.index).copied();
+        self.index += 1;
+        item
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.vec.len().saturating_sub(self.index);
+        (len, Some(len))
+    }
+}
+
+impl<T: Copy, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push() {
+        let mut vec = FixedVec::<u8, 3>::new();
+        assert!(vec.is_empty());
+        vec.push(1);
+        vec.push(2);
+        assert_eq!(vec, [1, 2]);
+        assert_eq!(vec.capacity(), 3);
+        assert_eq!(vec.into_iter().sum::<u8>(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity")]
+    fn push_full() {
+        let mut vec = FixedVec::<u8, 1>::new();
+        vec.push(1);
+        vec.push(2);
+    }
+}
@@ -0,0 +1,34 @@
+use crate::Color;
+
+/// The Result of the game.
+#[derive(Copy, Clone, Eq, PartialEq, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameState {
+    /// The game is still ongoing.
+    #[default]
+    Ongoing,
+    /// A player is checkmates.
+    Checkmates(Color),
+    /// Draw by Stalemate.
+    Stalemate,
+    /// Draw by request accepted (ie. Mutual Agreement).
+    DrawAccepted,
+    /// Draw declared by a player.
+    DrawDeclared,
+    /// The [`Color`] has resigns.
+    Resigns(Color),
+    /// The flag of the [`Color`] has fallen (i.e. no time left).
+    Timeout(Color),
+}
+
+impl GameState {
+    /// Verify if the game is ongoing.
+    pub fn is_ongoing(&self) -> bool {
+        matches!(self, GameState::Ongoing)
+    }
+
+    /// Verify if the game is finish.
+    pub fn is_finish(&self) -> bool {
+        !matches!(self, GameState::Ongoing)
+    }
+}
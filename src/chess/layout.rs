@@ -0,0 +1,492 @@
+//! Describe the layout of the GUI according to the size of the window.
+
+use ggez::graphics::Rect;
+
+use crate::{
+    Color, File, Rank, Square, BOARD_CELL_PX_SIZE, BOARD_PX_SIZE, BOARD_SIZE, SCREEN_PX_SIZE,
+    SIDE_SCREEN_PX_SIZE,
+};
+
+/// Number of rows of buttons in the side screen.
+const NUM_BUTTON_ROWS: usize = 5;
+
+/// Number of columns of buttons in the side screen.
+const NUM_BUTTON_COLUMNS: usize = 2;
+
+/// Number of rows of cells of the board editor (the pieces of each color and the rights).
+const NUM_EDITOR_ROWS: usize = 3;
+
+/// Number of columns of cells of the board editor (one per kind of piece).
+const NUM_EDITOR_COLUMNS: usize = 6;
+
+/// The position of every component of the GUI for a given window size.
+///
+/// The board is always a square. In landscape the side screen is at the right of the board,
+/// in portrait it is under the board and its content is split in two columns.
+///
+/// Every size is computed relatively to the default size ([`SCREEN_PX_SIZE`]),
+/// use [`Layout::scale`] to scale images and fonts.
+///
+/// When the board is [flipped][Layout::set_flipped] it is seen from the black side.
+///
+/// # Examples
+///
+/// ```
+/// use chess::{Layout, Square, SCREEN_PX_SIZE};
+///
+/// let layout = Layout::new(SCREEN_PX_SIZE.0, SCREEN_PX_SIZE.1);
+/// assert_eq!(layout.scale(), 1.0);
+/// assert_eq!(layout.square_at(10.0, 10.0), Some(Square::A8));
+///
+/// // Twice bigger
+/// let layout = Layout::new(2.0 * SCREEN_PX_SIZE.0, 2.0 * SCREEN_PX_SIZE.1);
+/// assert_eq!(layout.scale(), 2.0);
+/// assert_eq!(layout.square_to_screen(Square::B8), (200.0, 0.0));
+///
+/// let mut layout = Layout::default();
+/// layout.set_flipped(true);
+/// assert_eq!(layout.square_at(10.0, 10.0), Some(Square::H1));
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Layout {
+    width: f32,
+    height: f32,
+    board: Rect,
+    side: Rect,
+    portrait: bool,
+    flipped: bool,
+}
+
+impl Layout {
+    /// Compute the layout for a window of the given size (in pixels).
+    pub fn new(width: f32, height: f32) -> Self {
+        let portrait = width < height;
+        let (board_size, side) = if portrait {
+            // The side screen needs half of its height since it is split in two columns
+            let side_height = SIDE_SCREEN_PX_SIZE.1 / 2.0;
+            let size = width.min(height * BOARD_PX_SIZE.1 / (BOARD_PX_SIZE.1 + side_height));
+            (size, Rect::new(0.0, size, width, height - size))
+        } else {
+            let size = height.min(width * BOARD_PX_SIZE.0 / SCREEN_PX_SIZE.0);
+            (size, Rect::new(size, 0.0, width - size, height))
+        };
+        Layout {
+            width,
+            height,
+            board: Rect::new(0.0, 0.0, board_size, board_size),
+            side,
+            portrait,
+            flipped: false,
+        }
+    }
+
+    /// Get the size of the window.
+    pub fn size(&self) -> (f32, f32) {
+        (self.width, self.height)
+    }
+
+    /// Verify if the side screen is under the board.
+    pub fn is_portrait(&self) -> bool {
+        self.portrait
+    }
+
+    /// Verify if the board is seen from the black side.
+    pub fn is_flipped(&self) -> bool {
+        self.flipped
+    }
+
+    /// Show the board from the black side (or from the white side).
+    pub fn set_flipped(&mut self, flipped: bool) {
+        self.flipped = flipped;
+    }
+
+    /// The scale of the GUI compared to its default size.
+    pub fn scale(&self) -> f32 {
+        self.board.w / BOARD_PX_SIZE.0
+    }
+
+    /// Get the area of the board.
+    pub fn board(&self) -> Rect {
+        self.board
+    }
+
+    /// Get the area of the side screen.
+    pub fn side(&self) -> Rect {
+        self.side
+    }
+
+    /// Get the pixel-size of a board's cell.
+    pub fn cell_size(&self) -> (f32, f32) {
+        (
+            self.board.w / BOARD_SIZE.0 as f32,
+            self.board.h / BOARD_SIZE.1 as f32,
+        )
+    }
+
+    /// Transform a screen coordinate into a [`Square`].
+    ///
+    /// Return [`None`] if the coordinate is not on the board.
+    pub fn square_at(&self, x: f32, y: f32) -> Option<Square> {
+        // The right and bottom edges belong to the next cells, outside the board
+        let board = self.board;
+        if x < board.x || x >= board.right() || y < board.y || y >= board.bottom() {
+            return None;
+        }
+        let (cell_w, cell_h) = self.cell_size();
+        // Clamp against the rounding of the division
+        let column = (((x - board.x) / cell_w) as usize).min(BOARD_SIZE.0 as usize - 1);
+        let row = (((y - board.y) / cell_h) as usize).min(BOARD_SIZE.1 as usize - 1);
+        let (file, rank) = self.transpose(column, row);
+        Some(Square::make_square(File::new(file), Rank::new(rank)))
+    }
+
+    /// Transform a [`Square`] into the screen coordinate of its top left corner.
+    pub fn square_to_screen(&self, square: Square) -> (f32, f32) {
+        let (cell_w, cell_h) = self.cell_size();
+        // The transposition is its own inverse
+        let (x, y) = self.transpose(square.file().to_index(), square.rank().to_index());
+        (
+            self.board.x + x as f32 * cell_w,
+            self.board.y + y as f32 * cell_h,
+        )
+    }
+
+    /// Transpose a (column, row) of the screen into a (file, rank) of the board.
+    fn transpose(&self, x: usize, y: usize) -> (usize, usize) {
+        let (width, height) = (BOARD_SIZE.0 as usize, BOARD_SIZE.1 as usize);
+        if self.flipped {
+            // Rotate the board (reverse the x-axis)
+            (width - x - 1, y)
+        } else {
+            // Return the y-axis
+            (x, height - y - 1)
+        }
+    }
+
+    /// Get the area of a row of the screens drawn over the board (e.g. the settings).
+    ///
+    /// The first row is the title of the screen.
+    pub fn overlay_row(&self, row: usize) -> Rect {
+        let margin = 2.0 * self.margin();
+        let h = 50.0 * self.scale();
+        Rect::new(
+            self.board.x + margin,
+            self.board.y + margin + row as f32 * h,
+            self.board.w - 2.0 * margin,
+            h,
+        )
+    }
+
+    /// Get the area of the evaluation bar of the analysis mode, between the board and the
+    /// side screen (under the board in portrait).
+    pub fn eval_bar(&self) -> Rect {
+        let margin = self.margin();
+        if self.portrait {
+            Rect::new(
+                self.board.x,
+                self.side.y + margin / 4.0,
+                self.board.w,
+                margin / 2.0,
+            )
+        } else {
+            Rect::new(
+                self.side.x + margin / 4.0,
+                self.board.y,
+                margin / 2.0,
+                self.board.h,
+            )
+        }
+    }
+
+    /// The margin between the components of the side screen.
+    fn margin(&self) -> f32 {
+        20.0 * self.scale()
+    }
+
+    /// The area of the side screen that contains the information (timers, material, state).
+    fn info_area(&self) -> Rect {
+        let margin = self.margin();
+        let mut area = Rect::new(
+            self.side.x + margin,
+            self.side.y + margin,
+            self.side.w - 2.0 * margin,
+            self.side.h - 2.0 * margin,
+        );
+        if self.portrait {
+            area.w = (self.side.w - 3.0 * margin) / 2.0;
+        }
+        area
+    }
+
+    /// The area of the side screen that contains the buttons.
+    fn buttons_area(&self) -> Rect {
+        let margin = self.margin();
+        let height =
+            NUM_BUTTON_ROWS as f32 * 50.0 * self.scale() + (NUM_BUTTON_ROWS - 1) as f32 * margin;
+        let info = self.info_area();
+        if self.portrait {
+            Rect::new(info.right() + margin, info.y, info.w, height)
+        } else {
+            Rect::new(info.x, info.bottom() - height, info.w, height)
+        }
+    }
+
+    /// Get the area of the timer of the given [`Color`].
+    pub fn timer(&self, color: Color) -> Rect {
+        let scale = self.scale();
+        let info = self.info_area();
+        Rect::new(
+            info.x + color.to_index() as f32 * 135.0 * scale,
+            info.y,
+            115.0 * scale,
+            50.0 * scale,
+        )
+    }
+
+    /// Get the area of the button that change the theme.
+    pub fn theme_button(&self) -> Rect {
+        let size = 50.0 * self.scale();
+        let info = self.info_area();
+        Rect::new(info.right() - size, info.y, size, size)
+    }
+
+    /// Get the area of the row of captured pieces of the given [`Color`].
+    pub fn material(&self, color: Color) -> Rect {
+        let scale = self.scale();
+        let info = self.info_area();
+        Rect::new(
+            info.x,
+            info.y + (65.0 + color.to_index() as f32 * 33.0) * scale,
+            info.w,
+            28.0 * scale,
+        )
+    }
+
+    /// Get the area that display the state of the game.
+    pub fn status(&self) -> Rect {
+        let info = self.info_area();
+        let top = info.y + 140.0 * self.scale();
+        let bottom = self.move_input().y - self.margin();
+        Rect::new(info.x, top, info.w, (bottom - top).max(0.0))
+    }
+
+    /// Get the area where a move is typed, the matching moves are listed under the input box.
+    pub fn move_input(&self) -> Rect {
+        let info = self.info_area();
+        let bottom = if self.portrait {
+            info.bottom()
+        } else {
+            self.buttons_area().y - self.margin()
+        };
+        let h = 80.0 * self.scale();
+        Rect::new(info.x, bottom - h, info.w, h)
+    }
+
+    /// Get the area of the hint button, at the right of the box of the typed move.
+    pub fn hint_button(&self) -> Rect {
+        let input = self.move_input();
+        let w = 90.0 * self.scale();
+        Rect::new(input.right() - w, input.y, w, input.h / 2.0)
+    }
+
+    /// Get the area of the move list, in place of the first three rows of buttons.
+    pub fn move_list(&self) -> Rect {
+        let top = self.button(0, 0);
+        let bottom = self.button(2, NUM_BUTTON_COLUMNS - 1);
+        Rect::new(
+            top.x,
+            top.y,
+            bottom.right() - top.x,
+            bottom.bottom() - top.y,
+        )
+    }
+
+    /// Get the area of a cell of the board editor (the piece palette and the castle rights),
+    /// under the timers.
+    ///
+    /// # Panics
+    ///
+    /// Panic if the row is not in the range 0..3 or the column not in the range 0..6.
+    pub fn editor_cell(&self, row: usize, column: usize) -> Rect {
+        assert!(row < NUM_EDITOR_ROWS && column < NUM_EDITOR_COLUMNS);
+        let info = self.info_area();
+        let w = info.w / NUM_EDITOR_COLUMNS as f32;
+        let h = 45.0 * self.scale();
+        Rect::new(
+            info.x + column as f32 * w,
+            info.y + 65.0 * self.scale() + row as f32 * h,
+            w,
+            h,
+        )
+    }
+
+    /// Get the area of the FEN and the validation of the position in the board editor.
+    pub fn editor_status(&self) -> Rect {
+        let top = self.editor_cell(NUM_EDITOR_ROWS - 1, 0).bottom() + self.margin();
+        let bottom = self.move_input().bottom();
+        let info = self.info_area();
+        Rect::new(info.x, top, info.w, (bottom - top).max(0.0))
+    }
+
+    /// Get the area of the button at the given row and column of the side screen.
+    ///
+    /// # Panics
+    ///
+    /// Panic if the row is not in the range 0..5 or the column not in the range 0..2.
+    pub fn button(&self, row: usize, column: usize) -> Rect {
+        assert!(row < NUM_BUTTON_ROWS && column < NUM_BUTTON_COLUMNS);
+        let margin = self.margin();
+        let area = self.buttons_area();
+        let w = (area.w - margin) / NUM_BUTTON_COLUMNS as f32;
+        let h = 50.0 * self.scale();
+        Rect::new(
+            area.x + column as f32 * (w + margin),
+            area.y + row as f32 * (h + margin),
+            w,
+            h,
+        )
+    }
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Layout::new(SCREEN_PX_SIZE.0, SCREEN_PX_SIZE.1)
+    }
+}
+
+/// Screen coordinates of the squares for the default size of the window.
+impl Square {
+    /// Transform a screen coordinate into a [`Square`].
+    ///
+    /// > **Reciprocal**: see [`Square::to_screen`].
+    ///
+    /// > **Note**: Only valid for the default size of the window, the GUI uses [`Layout`].
+    ///
+    /// The result depend of:
+    /// - [`BOARD_SIZE`]
+    /// - [`BOARD_CELL_PX_SIZE`]
+    #[inline]
+    pub fn from_screen(x: f32, y: f32) -> Square {
+        // Transpose to grid space
+        let x = x / BOARD_CELL_PX_SIZE.0;
+        let y = y / BOARD_CELL_PX_SIZE.1;
+
+        // transpose to Square (return the y-axis)
+        let y = BOARD_SIZE.1 - y as i16 - 1;
+        Square::make_square(File::new(x as usize), Rank::new(y as usize))
+    }
+
+    /// Transform a [`Square`] into a screen coordinate.
+    ///
+    /// > **Reciprocal**: see [`Square::from_screen`].
+    ///
+    /// > **Note**: Only valid for the default size of the window, the GUI uses [`Layout`].
+    ///
+    /// The result depend of:
+    /// - [`BOARD_SIZE`]
+    /// - [`BOARD_CELL_PX_SIZE`]
+    #[inline]
+    pub fn to_screen(&self) -> (f32, f32) {
+        // transpose to grid space (return the y-axis)
+        let x = self.file().to_index() as f32;
+        let y = (BOARD_SIZE.1 as usize - self.rank().to_index() - 1) as f32;
+
+        // Transpose to screen space
+        let x = x * BOARD_CELL_PX_SIZE.0;
+        let y = y * BOARD_CELL_PX_SIZE.1;
+        (x, y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_layout() {
+        let layout = Layout::default();
+        assert!(!layout.is_portrait());
+        assert_eq!(layout.board(), Rect::new(0.0, 0.0, 800.0, 800.0));
+        assert_eq!(layout.side(), Rect::new(800.0, 0.0, 360.0, 800.0));
+        assert_eq!(
+            layout.timer(Color::White),
+            Rect::new(820.0, 20.0, 115.0, 50.0)
+        );
+        assert_eq!(
+            layout.timer(Color::Black),
+            Rect::new(955.0, 20.0, 115.0, 50.0)
+        );
+        assert_eq!(layout.theme_button(), Rect::new(1090.0, 20.0, 50.0, 50.0));
+        assert_eq!(layout.eval_bar(), Rect::new(805.0, 0.0, 10.0, 800.0));
+        assert_eq!(layout.status(), Rect::new(820.0, 160.0, 320.0, 170.0));
+        assert_eq!(layout.move_input(), Rect::new(820.0, 350.0, 320.0, 80.0));
+        assert_eq!(layout.hint_button(), Rect::new(1050.0, 350.0, 90.0, 40.0));
+        assert_eq!(layout.button(0, 0), Rect::new(820.0, 450.0, 150.0, 50.0));
+        assert_eq!(layout.button(4, 1), Rect::new(990.0, 730.0, 150.0, 50.0));
+        assert_eq!(layout.move_list(), Rect::new(820.0, 450.0, 320.0, 190.0));
+        assert_eq!(
+            layout.editor_cell(2, 5),
+            Rect::new(1086.6666, 175.0, 53.333332, 45.0)
+        );
+        assert_eq!(
+            layout.editor_status(),
+            Rect::new(820.0, 240.0, 320.0, 190.0)
+        );
+    }
+
+    #[test]
+    fn portrait_layout() {
+        let layout = Layout::new(800.0, 1200.0);
+        assert!(layout.is_portrait());
+        assert_eq!(layout.board(), Rect::new(0.0, 0.0, 800.0, 800.0));
+        assert_eq!(layout.side(), Rect::new(0.0, 800.0, 800.0, 400.0));
+        assert_eq!(layout.eval_bar(), Rect::new(0.0, 805.0, 800.0, 10.0));
+        // Buttons are on the right column
+        assert!(layout.button(0, 0).x > layout.status().right());
+        assert!(layout.button(4, 0).bottom() <= layout.side().bottom());
+        assert_eq!(
+            layout.move_input().bottom(),
+            layout.status().bottom() + 100.0
+        );
+    }
+
+    #[test]
+    fn square_at() {
+        let layout = Layout::new(580.0, 400.0);
+        assert_eq!(layout.square_at(0.0, 0.0), Some(Square::A8));
+        assert_eq!(layout.square_at(399.0, 399.0), Some(Square::H1));
+        assert_eq!(layout.square_at(401.0, 10.0), None);
+        assert_eq!(layout.square_at(400.0, 10.0), None);
+        assert_eq!(layout.square_at(10.0, 400.0), None);
+        assert_eq!(layout.square_at(399.99, 399.99), Some(Square::H1));
+        for square in crate::ALL_SQUARES {
+            let (x, y) = layout.square_to_screen(square);
+            assert_eq!(layout.square_at(x + 1.0, y + 1.0), Some(square));
+        }
+    }
+
+    #[test]
+    fn flipped() {
+        let mut layout = Layout::default();
+        layout.set_flipped(true);
+        assert_eq!(layout.square_at(0.0, 0.0), Some(Square::H1));
+        assert_eq!(layout.square_at(799.0, 799.0), Some(Square::A8));
+        assert_eq!(layout.square_at(800.0, 0.0), None);
+        assert_eq!(layout.square_at(0.0, 800.0), None);
+        assert_eq!(layout.square_to_screen(Square::E1), (300.0, 0.0));
+        for square in crate::ALL_SQUARES {
+            let (x, y) = layout.square_to_screen(square);
+            assert_eq!(layout.square_at(x + 1.0, y + 1.0), Some(square));
+        }
+    }
+
+    #[test]
+    fn square_screen() {
+        let layout = Layout::default();
+        for square in crate::ALL_SQUARES {
+            let (x, y) = square.to_screen();
+            assert_eq!((x, y), layout.square_to_screen(square));
+            assert_eq!(Square::from_screen(x + 1.0, y + 1.0), square);
+        }
+    }
+}
@@ -0,0 +1,302 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::{Board, ChessMove, Engine, Error, MAX_LEGAL_MOVES};
+
+/// Number of strength levels.
+pub const NUM_LEVELS: usize = 6;
+
+/// A strength level of the built-in [`Engine`], from novice to expert (see [`Strength`]).
+///
+/// Every level has an approximate Elo rating, a target rating is played by the closest level.
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+/// use chess::Level;
+///
+/// assert_eq!(Level::from_str("novice"), Ok(Level::Novice));
+/// assert_eq!(Level::from_elo(1450), Level::Intermediate);
+/// assert!(Level::Expert.strength().depth > Level::Novice.strength().depth);
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Level {
+    Novice,
+    Beginner,
+    Casual,
+    #[default]
+    Intermediate,
+    Advanced,
+    Expert,
+}
+
+/// List of all the levels, from the weakest to the strongest.
+pub const ALL_LEVELS: [Level; NUM_LEVELS] = [
+    Level::Novice,
+    Level::Beginner,
+    Level::Casual,
+    Level::Intermediate,
+    Level::Advanced,
+    Level::Expert,
+];
+
+/// How the built-in [`Engine`] searches and chooses its move at a [`Level`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Strength {
+    /// The maximum depth of the search (in half-moves).
+    pub depth: u32,
+    /// The search stops deepening once this number of positions is searched.
+    pub max_nodes: u64,
+    /// The maximum random noise added to the score of every move (in centipawns).
+    pub noise: i32,
+    /// The choice of a worse move: a move `tolerance` centipawns worse than the best one is
+    /// chosen 4 times less often than the best one (0 always chooses the best move).
+    pub tolerance: i32,
+}
+
+impl Level {
+    /// Get the parameters of the search of the level.
+    pub fn strength(self) -> Strength {
+        let (depth, max_nodes, noise, tolerance) = match self {
+            Level::Novice => (1, 1_000, 150, 300),
+            Level::Beginner => (2, 5_000, 80, 150),
+            Level::Casual => (2, 20_000, 40, 60),
+            Level::Intermediate => (3, 100_000, 20, 25),
+            Level::Advanced => (4, 500_000, 5, 0),
+            Level::Expert => (5, 2_000_000, 0, 0),
+        };
+        Strength {
+            depth,
+            max_nodes,
+            noise,
+            tolerance,
+        }
+    }
+
+    /// Get the approximate Elo rating of the level.
+    pub fn elo(self) -> u32 {
+        match self {
+            Level::Novice => 600,
+            Level::Beginner => 900,
+            Level::Casual => 1200,
+            Level::Intermediate => 1500,
+            Level::Advanced => 1800,
+            Level::Expert => 2000,
+        }
+    }
+
+    /// Get the level with the closest Elo rating.
+    pub fn from_elo(elo: u32) -> Level {
+        ALL_LEVELS
+            .into_iter()
+            .min_by_key(|level| level.elo().abs_diff(elo))
+            .expect("at least one level")
+    }
+
+    /// Get the next level, the strongest one is followed by the weakest one.
+    pub fn next(self) -> Level {
+        let index = ALL_LEVELS
+            .iter()
+            .position(|&level| level == self)
+            .unwrap_or(0);
+        ALL_LEVELS[(index + 1) % NUM_LEVELS]
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Level::Novice => "novice",
+            Level::Beginner => "beginner",
+            Level::Casual => "casual",
+            Level::Intermediate => "intermediate",
+            Level::Advanced => "advanced",
+            Level::Expert => "expert",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for Level {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ALL_LEVELS
+            .into_iter()
+            .find(|level| level.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| Error::InvalidLevel {
+                level: s.to_string(),
+            })
+    }
+}
+
+/// The built-in [`Engine`] playing at a [`Level`].
+///
+/// The search deepens up to the depth of the level, until its number of positions is
+/// reached. Every move is scored, a random noise is added to the scores and the move is
+/// chosen at random: the worse the move, the less often it is chosen (see [`Strength`]).
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+/// use chess::{Board, ChessMove, Level, LevelEngine, Square};
+///
+/// // The queen is hanging
+/// let board = Board::from_str("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1").unwrap();
+/// let mut engine = LevelEngine::new(Level::Expert);
+/// assert_eq!(
+///     engine.choose_move(&board),
+///     Some(ChessMove::new(Square::D2, Square::D5))
+/// );
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct LevelEngine {
+    level: Level,
+    /// The state of the random generator (xorshift).
+    seed: u64,
+}
+
+impl LevelEngine {
+    /// Create an engine at a level, the random choices depend on the current time (if
+    /// available).
+    pub fn new(level: Level) -> Self {
+        #[cfg(feature = "std")]
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |time| time.as_nanos() as u64);
+        #[cfg(not(feature = "std"))]
+        let seed = 0;
+        LevelEngine::with_seed(level, seed)
+    }
+
+    /// Create an engine at a level with a seed, the same seed chooses the same moves.
+    pub fn with_seed(level: Level, seed: u64) -> Self {
+        LevelEngine {
+            level,
+            // The state of xorshift must not be 0
+            seed: seed | 1,
+        }
+    }
+
+    /// Get the level of the engine.
+    pub fn level(&self) -> Level {
+        self.level
+    }
+
+    /// Choose the move to play (if exist).
+    pub fn choose_move(&mut self, board: &Board) -> Option<ChessMove> {
+        let strength = self.level.strength();
+        // Only the best move is needed without random choice
+        let count = match strength.noise == 0 && strength.tolerance == 0 {
+            true => 1,
+            false => MAX_LEGAL_MOVES,
+        };
+        let mut lines = Vec::new();
+        let mut nodes = 0;
+        for depth in 1..=strength.depth {
+            let (searched_lines, searched) = Engine::new(depth).analyse_lines(board, count);
+            lines = searched_lines;
+            nodes += searched;
+            if nodes >= strength.max_nodes {
+                break;
+            }
+        }
+        let scores: Vec<i32> = lines
+            .iter()
+            .map(|line| line.score + self.noise(strength.noise))
+            .collect();
+        let best = *scores.iter().max()?;
+        let weights: Vec<u64> = scores
+            .iter()
+            .map(|&score| weight(best - score, strength.tolerance))
+            .collect();
+        let mut choice = self.next_random() % weights.iter().sum::<u64>();
+        for (line, weight) in lines.iter().zip(weights) {
+            if choice < weight {
+                return Some(line.best_move);
+            }
+            choice -= weight;
+        }
+        None
+    }
+
+    /// Get a random noise between `-noise` and `noise`.
+    fn noise(&mut self, noise: i32) -> i32 {
+        match noise {
+            0 => 0,
+            _ => (self.next_random() % (2 * noise as u64 + 1)) as i32 - noise,
+        }
+    }
+
+    /// Get the next random number (xorshift64).
+    fn next_random(&mut self) -> u64 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        self.seed
+    }
+}
+
+/// The weight of the choice of a move according to its gap to the best score (only the best
+/// moves have a weight with no tolerance).
+fn weight(gap: i32, tolerance: i32) -> u64 {
+    match tolerance {
+        0 => (gap == 0) as u64,
+        _ => {
+            let ratio = 1000 * tolerance as u64 / (tolerance + gap) as u64;
+            ratio * ratio
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels() {
+        for level in ALL_LEVELS {
+            assert_eq!(Level::from_str(&level.to_string()), Ok(level));
+            assert_eq!(Level::from_elo(level.elo()), level);
+        }
+        assert_eq!(Level::from_str("EXPERT"), Ok(Level::Expert));
+        assert!(Level::from_str("master").is_err());
+        assert_eq!(Level::from_elo(0), Level::Novice);
+        assert_eq!(Level::from_elo(3000), Level::Expert);
+        assert_eq!(Level::Expert.next(), Level::Novice);
+    }
+
+    #[test]
+    fn weights() {
+        assert_eq!(weight(0, 0), 1);
+        assert_eq!(weight(10, 0), 0);
+        assert_eq!(weight(0, 100), 1_000_000);
+        assert_eq!(weight(100, 100), 250_000);
+    }
+
+    #[test]
+    fn novice_makes_mistakes() {
+        // The queen is hanging, the novice doesn't always take it
+        let board = Board::from_str("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1").unwrap();
+        let capture = ChessMove::new(crate::Square::D2, crate::Square::D5);
+        let mut engine = LevelEngine::with_seed(Level::Novice, 42);
+        let moves: Vec<ChessMove> = (0..50)
+            .map(|_| engine.choose_move(&board).unwrap())
+            .collect();
+        assert!(moves.contains(&capture));
+        assert!(moves.iter().any(|&m| m != capture));
+
+        // The same seed plays the same moves
+        let mut engine = LevelEngine::with_seed(Level::Novice, 42);
+        assert_eq!(engine.choose_move(&board), Some(moves[0]));
+    }
+}
@@ -2,17 +2,63 @@
 //!
 //! This is a chess library for rust with Gui.
 //!
+//! The rules (board, moves, notations and engines) only depend on `thiserror`,
+//! the GUI needs the `gui` feature (enabled by default), the terminal interface the `tui`
+//! feature and the WebSocket game server the `server` feature.
+//!
+//! Without the `std` feature the library is `#![no_std]`: the [`Board`], the moves and FEN
+//! are always available (see the `*_fixed` methods of the [`Board`] to generate the moves
+//! without allocation), the games and the notations need the `alloc` feature.
+//!
 //! ## Examples
 //!
+//! ```
+//! use chess::{Board, Color, GameState, Square};
+//!
+//! // Play the fool's mate
+//! let mut board = Board::default();
+//! for (from, to) in [
+//!     (Square::F2, Square::F3),
+//!     (Square::E7, Square::E5),
+//!     (Square::G2, Square::G4),
+//!     (Square::D8, Square::H4),
+//! ] {
+//!     assert!(board.get_legal_moves_fixed(from).contains(&to));
+//!     board.update(chess::ChessMove::new(from, to));
+//! }
+//! assert_eq!(board.state(), GameState::Checkmates(Color::White));
+//! ```
+//!
+//! With the `alloc` feature:
+//!
+//! ```
+//! # #[cfg(feature = "alloc")]
+//! # {
+//! use chess::{Chess, Color, GameState, Square};
+//!
+//! // Play the fool's mate
+//! let mut chess = Chess::default();
+//! chess.play(Square::F2, Square::F3);
+//! chess.play(Square::E7, Square::E5);
+//! chess.play(Square::G2, Square::G4);
+//! chess.play(Square::D8, Square::H4);
+//! assert_eq!(chess.state(), GameState::Checkmates(Color::White));
+//! # }
+//! ```
+//!
+//! With the `gui` feature:
+//!
 //! ```no_run
+//! # #[cfg(feature = "gui")]
+//! # {
 //! use chess::{run, ChessGui};
 //!
-//! fn main() {
-//!     // Create and run the Gui game
-//!     run(ChessGui::default());
-//! }
+//! // Create and run the Gui game
+//! run(ChessGui::default());
+//! # }
 //! ```
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![allow(clippy::needless_doctest_main, clippy::collapsible_if)]
 #![deny(
     // missing_docs, // This lint ask to document all variants/fields of enum/struct.
@@ -26,13 +72,19 @@
     unused_qualifications
 )]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 // Core
 
 mod board;
 pub use crate::board::*;
 
-mod chess;
-pub use crate::chess::*;
+mod game_state;
+pub use crate::game_state::*;
+
+mod fixed_vec;
+pub use crate::fixed_vec::*;
 
 mod piece;
 pub use crate::piece::*;
@@ -64,24 +116,171 @@ pub use crate::chess_move::*;
 mod direction;
 pub use crate::direction::*;
 
+mod clock;
+pub use crate::clock::*;
+
+// Alloc
+
+#[cfg(feature = "alloc")]
+mod chess;
+#[cfg(feature = "alloc")]
+pub use crate::chess::*;
+
+#[cfg(feature = "alloc")]
+mod pgn;
+#[cfg(feature = "alloc")]
+pub use crate::pgn::*;
+
+#[cfg(feature = "alloc")]
+mod engine;
+#[cfg(feature = "alloc")]
+pub use crate::engine::*;
+
+#[cfg(feature = "alloc")]
+mod level;
+#[cfg(feature = "alloc")]
+pub use crate::level::*;
+
+#[cfg(feature = "alloc")]
+mod editor;
+#[cfg(feature = "alloc")]
+pub use crate::editor::*;
+
+#[cfg(feature = "alloc")]
+mod premove;
+#[cfg(feature = "alloc")]
+pub use crate::premove::*;
+
+#[cfg(feature = "alloc")]
+mod annotation;
+#[cfg(feature = "alloc")]
+pub use crate::annotation::*;
+
+#[cfg(feature = "serde")]
+mod serde_impls;
+
+// Std
+
+#[cfg(feature = "std")]
+mod uci;
+#[cfg(feature = "std")]
+pub use crate::uci::*;
+
+#[cfg(feature = "std")]
+mod player;
+#[cfg(feature = "std")]
+pub use crate::player::*;
+
+#[cfg(feature = "std")]
+mod analyser;
+#[cfg(feature = "std")]
+pub use crate::analyser::*;
+
+#[cfg(feature = "std")]
+mod review;
+#[cfg(feature = "std")]
+pub use crate::review::*;
+
+#[cfg(feature = "std")]
+mod puzzle;
+#[cfg(feature = "std")]
+pub use crate::puzzle::*;
+
+#[cfg(feature = "std")]
+mod save;
+#[cfg(feature = "std")]
+pub use crate::save::*;
+
+#[cfg(feature = "std")]
+mod network;
+#[cfg(feature = "std")]
+pub use crate::network::*;
+
+#[cfg(feature = "std")]
+mod spectator;
+#[cfg(feature = "std")]
+pub use crate::spectator::*;
+
+#[cfg(feature = "std")]
+mod correspondence;
+#[cfg(feature = "std")]
+pub use crate::correspondence::*;
+
+// Server
+
+#[cfg(feature = "server")]
+mod server;
+#[cfg(feature = "server")]
+pub use crate::server::*;
+
+// Tui
+
+#[cfg(feature = "tui")]
+mod tui;
+#[cfg(feature = "tui")]
+pub use crate::tui::*;
+
 // Gui
 
+#[cfg(feature = "gui")]
 mod chess_gui;
+#[cfg(feature = "gui")]
 pub use crate::chess_gui::*;
 
+#[cfg(feature = "gui")]
 mod button;
+#[cfg(feature = "gui")]
 pub use crate::button::*;
 
+#[cfg(feature = "gui")]
 mod theme;
+#[cfg(feature = "gui")]
 pub use crate::theme::*;
 
+#[cfg(feature = "gui")]
+mod animation;
+#[cfg(feature = "gui")]
+pub use crate::animation::*;
+
+#[cfg(feature = "gui")]
+mod layout;
+#[cfg(feature = "gui")]
+pub use crate::layout::*;
+
+#[cfg(feature = "gui")]
+mod settings;
+#[cfg(feature = "gui")]
+pub use crate::settings::*;
+
 // Function
 
 /// Run the GUI.
-pub fn run(game: ChessGui) {
+///
+/// The [`Settings`] are loaded from the user config directory (see [`Settings::default_path`]),
+/// unless they were already [loaded][ChessGui::load_settings] from another file.
+///
+/// The game is saved after every move in the user data directory (see [`Chess::autosave_path`])
+/// and the unfinished game of the last run is proposed, unless the autosave was already
+/// [enabled][ChessGui::enable_autosave].
+#[cfg(feature = "gui")]
+pub fn run(mut game: ChessGui) {
+    if game.settings_path().is_none() {
+        if let Some(path) = Settings::default_path() {
+            game.load_settings(path);
+        }
+    }
+    if game.autosave_path().is_none() {
+        if let Some(path) = Chess::autosave_path() {
+            game.propose_resume(&path);
+            game.enable_autosave(path);
+        }
+    }
+
     let default_conf = ggez::conf::Conf {
         window_mode: ggez::conf::WindowMode::default()
-            .dimensions(SCREEN_PX_SIZE.0, SCREEN_PX_SIZE.1),
+            .dimensions(SCREEN_PX_SIZE.0, SCREEN_PX_SIZE.1)
+            .min_dimensions(MIN_SCREEN_PX_SIZE.0, MIN_SCREEN_PX_SIZE.1)
+            .resizable(true),
         window_setup: ggez::conf::WindowSetup::default()
             .title("Chess")
             .icon("/images/icon.png"),
@@ -93,9 +292,7 @@ pub fn run(game: ChessGui) {
     };
     let (ctx, event_loop) =
         ggez::ContextBuilder::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_AUTHORS"))
-            .add_resource_path::<std::path::PathBuf>(
-                [env!("CARGO_MANIFEST_DIR"), "resources"].iter().collect(),
-            )
+            .add_resource_path(RESOURCES_DIR)
             .default_conf(default_conf)
             .build()
             .expect("Failed to build ggez context");
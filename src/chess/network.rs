@@ -0,0 +1,958 @@
+//! Play a game over the network: a player hosts the game on a TCP port and the other joins it,
+//! the spectators can watch it.
+
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::mem;
+use std::net::{IpAddr, Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::str::FromStr;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use thiserror::Error;
+
+use crate::{Chess, ChessMove, Color, Pgn, NETWORK_RECONNECT_DELAY};
+
+/// The version of the [`Message`] protocol, both players must use the same.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Error that can happen when talking to the other player.
+#[derive(Error, Debug)]
+pub enum NetworkError {
+    /// The connection can't be read or written.
+    #[error("Can't communicate with the other player: {}", source)]
+    Io {
+        #[from]
+        source: io::Error,
+    },
+
+    /// The other player closed the connection.
+    #[error("The other player has quit")]
+    Closed,
+
+    /// The other player sent something unexpected.
+    #[error("Unexpected message of the other player: {}", line)]
+    Protocol { line: String },
+
+    /// The other player uses another version of the protocol.
+    #[error(
+        "The other player uses the version {} of the protocol (expected {})",
+        version,
+        PROTOCOL_VERSION
+    )]
+    Version { version: u32 },
+}
+
+/// A message of the network protocol, written on one line.
+///
+/// | Message                       | Sent by | Meaning                                          |
+/// |-------------------------------|---------|--------------------------------------------------|
+/// | `hello <version>`             | guest   | Join the game, or rejoin it after a disconnection |
+/// | `watch <version>`             | spectator | Watch the game, without playing it             |
+/// | `welcome <version> <color>`   | host    | The guest is accepted and plays the given color  |
+/// | `sync <pgn>`                  | host    | The whole game in PGN (newlines written `\n`)    |
+/// | `move <uci>`                  | both    | A move in UCI notation (e.g. `move e2e4`)        |
+/// | `clock <white ms> <black ms>` | host    | The remaining time, sent after every move        |
+/// | `offer-draw`                  | both    | The player offers a draw                         |
+/// | `accept-draw`                 | both    | The player accepts the draw offer                |
+/// | `declare-draw`                | both    | The player declares a draw (repetition, 50 moves) |
+/// | `resign`                      | both    | The player resigns                               |
+///
+/// The host is authoritative: the game of the guest is replaced by the `sync` message,
+/// after every (re)connection or when the games don't match. A spectator only receives
+/// `sync` messages, after its connection and after every change of the game.
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+/// use chess::{ChessMove, Message, Square};
+///
+/// let m = Message::Move(ChessMove::new(Square::E2, Square::E4));
+/// assert_eq!(m.to_string(), "move e2e4");
+/// assert_eq!(Message::from_str("move e2e4").unwrap(), m);
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Message {
+    Hello { version: u32 },
+    Watch { version: u32 },
+    Welcome { version: u32, color: Color },
+    Sync(Pgn),
+    Move(ChessMove),
+    Clock { white: Duration, black: Duration },
+    OfferDraw,
+    AcceptDraw,
+    DeclareDraw,
+    Resign,
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Message::Hello { version } => write!(f, "hello {version}"),
+            Message::Watch { version } => write!(f, "watch {version}"),
+            Message::Welcome { version, color } => write!(f, "welcome {version} {color}"),
+            Message::Sync(pgn) => {
+                write!(f, "sync ")?;
+                for c in pgn.to_string().chars() {
+                    match c {
+                        '\\' => write!(f, "\\\\")?,
+                        '\n' => write!(f, "\\n")?,
+                        '\r' => {}
+                        c => write!(f, "{c}")?,
+                    }
+                }
+                Ok(())
+            }
+            Message::Move(m) => write!(f, "move {m}"),
+            Message::Clock { white, black } => {
+                write!(f, "clock {} {}", white.as_millis(), black.as_millis())
+            }
+            Message::OfferDraw => write!(f, "offer-draw"),
+            Message::AcceptDraw => write!(f, "accept-draw"),
+            Message::DeclareDraw => write!(f, "declare-draw"),
+            Message::Resign => write!(f, "resign"),
+        }
+    }
+}
+
+impl FromStr for Message {
+    type Err = NetworkError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || NetworkError::Protocol {
+            line: s.to_string(),
+        };
+        let (command, arguments) = s.trim_end().split_once(' ').unwrap_or((s.trim_end(), ""));
+        let mut tokens = arguments.split_whitespace();
+        let mut next = || tokens.next().ok_or_else(error);
+        let message = match command {
+            "hello" => Message::Hello {
+                version: next()?.parse().map_err(|_| error())?,
+            },
+            "watch" => Message::Watch {
+                version: next()?.parse().map_err(|_| error())?,
+            },
+            "welcome" => Message::Welcome {
+                version: next()?.parse().map_err(|_| error())?,
+                color: Color::from_str(next()?).map_err(|_| error())?,
+            },
+            "sync" => {
+                let mut pgn = String::new();
+                let mut chars = arguments.chars();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => match chars.next() {
+                            Some('n') => pgn.push('\n'),
+                            Some('\\') => pgn.push('\\'),
+                            _ => return Err(error()),
+                        },
+                        c => pgn.push(c),
+                    }
+                }
+                return Ok(Message::Sync(Pgn::from_str(&pgn).map_err(|_| error())?));
+            }
+            "move" => Message::Move(ChessMove::from_str(next()?).map_err(|_| error())?),
+            "clock" => {
+                let mut millis = || -> Result<Duration, NetworkError> {
+                    Ok(Duration::from_millis(next()?.parse().map_err(|_| error())?))
+                };
+                Message::Clock {
+                    white: millis()?,
+                    black: millis()?,
+                }
+            }
+            "offer-draw" => Message::OfferDraw,
+            "accept-draw" => Message::AcceptDraw,
+            "declare-draw" => Message::DeclareDraw,
+            "resign" => Message::Resign,
+            _ => return Err(error()),
+        };
+        match tokens.next() {
+            Some(_) => Err(error()),
+            None => Ok(message),
+        }
+    }
+}
+
+/// A TCP connection with the other player, the messages are read in another thread.
+#[derive(Debug)]
+struct Connection {
+    stream: TcpStream,
+    receiver: mpsc::Receiver<Result<Message, NetworkError>>,
+}
+
+impl Connection {
+    /// Start to read the messages of the stream.
+    fn new(stream: TcpStream) -> Result<Self, NetworkError> {
+        stream.set_nonblocking(false)?;
+        stream.set_nodelay(true)?;
+        let reader = BufReader::new(stream.try_clone()?);
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for line in reader.lines() {
+                let message = line
+                    .map_err(NetworkError::from)
+                    .and_then(|line| Message::from_str(&line));
+                #[cfg(feature = "log")]
+                if let Ok(message) = &message {
+                    log::debug!("network < {message}");
+                }
+                let failed = message.is_err();
+                // Stop reading if the connection is dropped or broken
+                if sender.send(message).is_err() || failed {
+                    return;
+                }
+            }
+            let _ = sender.send(Err(NetworkError::Closed));
+        });
+        Ok(Connection { stream, receiver })
+    }
+
+    /// Send a message.
+    fn send(&mut self, message: &Message) -> Result<(), NetworkError> {
+        #[cfg(feature = "log")]
+        log::debug!("network > {message}");
+        self.stream.write_all(format!("{message}\n").as_bytes())?;
+        Ok(())
+    }
+
+    /// Get the address of the other computer.
+    fn peer_ip(&self) -> Option<IpAddr> {
+        self.stream.peer_addr().ok().map(|address| address.ip())
+    }
+
+    /// Get the next message received (if exist), without waiting.
+    fn try_recv(&self) -> Option<Result<Message, NetworkError>> {
+        match self.receiver.try_recv() {
+            Ok(message) => Some(message),
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => Some(Err(NetworkError::Closed)),
+        }
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        // Stop the thread that reads the messages
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+}
+
+/// The side of the [`Session`].
+#[derive(Debug)]
+enum Role {
+    /// Wait for the guest on a TCP port.
+    Host { listener: TcpListener, color: Color },
+    /// Connect to the host, the color is given by the host (a spectator has no color).
+    Guest {
+        address: String,
+        spectator: bool,
+        color: Option<Color>,
+        connecting: Option<mpsc::Receiver<io::Result<TcpStream>>>,
+        last_attempt: Option<Instant>,
+    },
+}
+
+/// A game played over the network, against a player on another computer.
+///
+/// One player [hosts][Session::host] the game and the other [joins][Session::join] it.
+/// The [`Session`] [updates][Session::update] the [`Chess`] game with the [messages][Message]
+/// of the other player, the moves and the actions of the local player are [sent][Session::send].
+///
+/// When the connection drops, the guest joins the game again every
+/// [`NETWORK_RECONNECT_DELAY`] and its game is synchronised with the one of the host.
+///
+/// Any number of spectators can [watch][Session::watch] the game, their game is replaced by
+/// the one of the host after every change.
+///
+/// # Examples
+///
+/// ```no_run
+/// use chess::{Chess, Color, Message, Session, Square, ChessMove};
+///
+/// let mut session = Session::host("0.0.0.0:7878", Color::White).unwrap();
+/// let mut chess = Chess::default();
+/// loop {
+///     session.update(&mut chess);
+///     if session.is_local_turn(&chess) {
+///         chess.play(Square::E2, Square::E4);
+///         session.send(Message::Move(ChessMove::new(Square::E2, Square::E4)));
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Session {
+    role: Role,
+    connection: Option<Connection>,
+    ready: bool,
+    /// The connections of the host that haven't said if they play or watch.
+    pending: Vec<Connection>,
+    /// The connections of the spectators (only for the host).
+    spectators: Vec<Connection>,
+    /// The game last sent to the spectators.
+    spectated: Option<Chess>,
+    /// The player that made the pending draw offer.
+    draw_offer: Option<Color>,
+}
+
+impl Session {
+    /// Host a game on the given address (e.g. `0.0.0.0:7878`), the local player plays
+    /// the given [`Color`].
+    pub fn host<A: ToSocketAddrs>(address: A, color: Color) -> Result<Self, NetworkError> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        Ok(Session::new(Role::Host { listener, color }))
+    }
+
+    /// Join the game hosted on the given address (e.g. `192.168.1.10:7878`).
+    ///
+    /// The connection is made during the [updates][Session::update].
+    pub fn join<S: Into<String>>(address: S) -> Self {
+        Session::new(Role::Guest {
+            address: address.into(),
+            spectator: false,
+            color: None,
+            connecting: None,
+            last_attempt: None,
+        })
+    }
+
+    /// Watch the game hosted on the given address, the local player can't play.
+    ///
+    /// The connection is made during the [updates][Session::update].
+    pub fn watch<S: Into<String>>(address: S) -> Self {
+        Session::new(Role::Guest {
+            address: address.into(),
+            spectator: true,
+            color: None,
+            connecting: None,
+            last_attempt: None,
+        })
+    }
+
+    fn new(role: Role) -> Self {
+        Session {
+            role,
+            connection: None,
+            ready: false,
+            pending: vec![],
+            spectators: vec![],
+            spectated: None,
+            draw_offer: None,
+        }
+    }
+
+    /// Verify if the local player hosts the game.
+    pub fn is_host(&self) -> bool {
+        matches!(self.role, Role::Host { .. })
+    }
+
+    /// Verify if the local player only watches the game.
+    pub fn is_spectator(&self) -> bool {
+        matches!(
+            self.role,
+            Role::Guest {
+                spectator: true,
+                ..
+            }
+        )
+    }
+
+    /// Get the number of spectators of the game (only for the host).
+    pub fn spectators(&self) -> usize {
+        self.spectators.len()
+    }
+
+    /// Get the address where the game is hosted (only for the host).
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        match &self.role {
+            Role::Host { listener, .. } => listener.local_addr().ok(),
+            Role::Guest { .. } => None,
+        }
+    }
+
+    /// Get the [`Color`] of the local player (unknown until the guest is welcomed).
+    pub fn color(&self) -> Option<Color> {
+        match self.role {
+            Role::Host { color, .. } => Some(color),
+            Role::Guest { color, .. } => color,
+        }
+    }
+
+    /// Verify if the other player is connected (or the host, for a spectator).
+    pub fn is_connected(&self) -> bool {
+        self.ready
+    }
+
+    /// Verify if the local player can play: the other player is connected and it is the turn
+    /// of the local player.
+    pub fn is_local_turn(&self, chess: &Chess) -> bool {
+        self.ready && self.color() == Some(chess.board.side_to_move())
+    }
+
+    /// Send a message to the other player.
+    ///
+    /// The message is lost if the other player is not connected, the game is synchronised
+    /// when he joins again. A spectator doesn't send anything, and only the host sends the
+    /// clocks.
+    pub fn send(&mut self, message: Message) {
+        match message {
+            Message::OfferDraw => self.draw_offer = self.color(),
+            Message::Move(_) => self.draw_offer = None,
+            _ => {}
+        }
+        let clock = matches!(message, Message::Clock { .. });
+        if self.ready && !self.is_spectator() && (self.is_host() || !clock) {
+            if let Err(_error) = self.send_now(&message) {
+                #[cfg(feature = "log")]
+                log::warn!("{_error}");
+                self.disconnect();
+            }
+        }
+    }
+
+    /// Send the whole game to the guest (only for the host), e.g. after an undo.
+    pub fn sync(&mut self, chess: &Chess) {
+        if self.is_host() {
+            self.send(Message::Sync(Pgn::from_chess(chess)));
+        }
+    }
+
+    /// Accept or join the other player, and apply his messages to the game.
+    ///
+    /// The host also accepts the spectators and sends them the game when it changed.
+    pub fn update(&mut self, chess: &mut Chess) {
+        self.connect();
+        while let Some(message) = self.connection.as_ref().and_then(Connection::try_recv) {
+            if let Err(_error) = message.and_then(|message| self.receive(message, chess)) {
+                #[cfg(feature = "log")]
+                log::warn!("{_error}");
+                self.disconnect();
+            }
+        }
+        // After the messages of the guest, to know if the guest is still connected
+        self.welcome(chess);
+        self.update_spectators(chess);
+    }
+
+    /// Handle the first message of the new connections (only for the host): a guest replaces
+    /// the previous one if it is disconnected or joins again from the same computer, a
+    /// spectator receives the game.
+    fn welcome(&mut self, chess: &mut Chess) {
+        for mut connection in mem::take(&mut self.pending) {
+            match connection.try_recv() {
+                None => self.pending.push(connection),
+                Some(Ok(hello @ Message::Hello { .. })) => {
+                    if let Some(guest) = &self.connection {
+                        if guest.peer_ip() != connection.peer_ip() {
+                            #[cfg(feature = "log")]
+                            log::warn!("The game already has a guest, refuse the new one");
+                            continue;
+                        }
+                    }
+                    self.disconnect();
+                    self.connection = Some(connection);
+                    if let Err(_error) = self.receive(hello, chess) {
+                        #[cfg(feature = "log")]
+                        log::warn!("{_error}");
+                        self.disconnect();
+                    }
+                }
+                Some(Ok(Message::Watch { version })) if version == PROTOCOL_VERSION => {
+                    match connection.send(&Message::Sync(Pgn::from_chess(chess))) {
+                        Ok(()) => self.spectators.push(connection),
+                        Err(_error) => {
+                            #[cfg(feature = "log")]
+                            log::warn!("{_error}");
+                        }
+                    }
+                }
+                Some(Ok(_message)) => {
+                    #[cfg(feature = "log")]
+                    log::warn!("Unexpected first message: {_message}");
+                }
+                Some(Err(_error)) => {
+                    #[cfg(feature = "log")]
+                    log::warn!("{_error}");
+                }
+            }
+        }
+    }
+
+    /// Send the game to the spectators if it changed (only for the host).
+    ///
+    /// The clocks are not sent on every tick, the spectators run them.
+    fn update_spectators(&mut self, chess: &Chess) {
+        // Only the disconnections are read, the spectators don't send anything
+        self.spectators
+            .retain(|connection| !matches!(connection.try_recv(), Some(Err(_))));
+        if self.spectators.is_empty()
+            || self
+                .spectated
+                .as_ref()
+                .is_some_and(|spectated| is_same_game(spectated, chess))
+        {
+            return;
+        }
+        let sync = Message::Sync(Pgn::from_chess(chess));
+        self.spectators
+            .retain_mut(|connection| connection.send(&sync).is_ok());
+        self.spectated = Some(chess.clone());
+    }
+
+    /// Send a message, even before the handshake.
+    fn send_now(&mut self, message: &Message) -> Result<(), NetworkError> {
+        match &mut self.connection {
+            Some(connection) => connection.send(message),
+            None => Err(NetworkError::Closed),
+        }
+    }
+
+    /// Close the connection, the guest will join the game again.
+    fn disconnect(&mut self) {
+        self.connection = None;
+        self.ready = false;
+    }
+
+    /// Accept a guest or a spectator (for the host) or connect to the host (for the guest).
+    ///
+    /// A guest joining again from the same computer replaces the previous connection, so a
+    /// guest can join again after a disconnection that the host didn't notice.
+    fn connect(&mut self) {
+        let stream = match &mut self.role {
+            Role::Host { listener, .. } => match listener.accept() {
+                Ok((stream, _address)) => {
+                    #[cfg(feature = "log")]
+                    log::info!("The guest is connected from {_address}");
+                    Some(stream)
+                }
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => None,
+                Err(_error) => {
+                    #[cfg(feature = "log")]
+                    log::warn!("{_error}");
+                    None
+                }
+            },
+            Role::Guest { .. } if self.connection.is_some() => None,
+            Role::Guest {
+                address,
+                connecting,
+                last_attempt,
+                ..
+            } => match connecting.as_ref().map(mpsc::Receiver::try_recv) {
+                Some(Ok(result)) => {
+                    *connecting = None;
+                    result
+                        .inspect_err(|_error| {
+                            #[cfg(feature = "log")]
+                            log::warn!("Can't join the game at {address}: {_error}");
+                        })
+                        .ok()
+                }
+                Some(Err(mpsc::TryRecvError::Empty)) => None,
+                Some(Err(mpsc::TryRecvError::Disconnected)) | None => {
+                    if last_attempt.is_none_or(|last| last.elapsed() >= NETWORK_RECONNECT_DELAY) {
+                        // Connect in another thread to keep the caller responsive
+                        let address = address.clone();
+                        let (sender, receiver) = mpsc::channel();
+                        thread::spawn(move || {
+                            let _ = sender.send(TcpStream::connect(address.as_str()));
+                        });
+                        *connecting = Some(receiver);
+                        *last_attempt = Some(Instant::now());
+                    }
+                    None
+                }
+            },
+        };
+        if let Some(stream) = stream {
+            let connection = match Connection::new(stream) {
+                Ok(connection) => connection,
+                Err(_error) => {
+                    #[cfg(feature = "log")]
+                    log::warn!("{_error}");
+                    return;
+                }
+            };
+            if self.is_host() {
+                // The guest or a spectator, known with its first message
+                self.pending.push(connection);
+            } else {
+                self.disconnect();
+                self.connection = Some(connection);
+                self.hello();
+            }
+        }
+    }
+
+    /// Ask the host to (re)join or watch the game, he answers with the whole game.
+    fn hello(&mut self) {
+        self.ready = false;
+        let hello = if self.is_spectator() {
+            Message::Watch {
+                version: PROTOCOL_VERSION,
+            }
+        } else {
+            Message::Hello {
+                version: PROTOCOL_VERSION,
+            }
+        };
+        if let Err(_error) = self.send_now(&hello) {
+            #[cfg(feature = "log")]
+            log::warn!("{_error}");
+            self.disconnect();
+        }
+    }
+
+    /// Handle a message of the other player.
+    fn receive(&mut self, message: Message, chess: &mut Chess) -> Result<(), NetworkError> {
+        match message {
+            Message::Hello { version } if self.is_host() => {
+                let welcome = Message::Welcome {
+                    version: PROTOCOL_VERSION,
+                    color: !self.color().expect("the host has a color"),
+                };
+                self.send_now(&welcome)?;
+                if version != PROTOCOL_VERSION {
+                    return Err(NetworkError::Version { version });
+                }
+                self.send_now(&Message::Sync(Pgn::from_chess(chess)))?;
+                self.ready = true;
+            }
+            Message::Welcome { version, color } if !self.is_host() => {
+                if version != PROTOCOL_VERSION {
+                    return Err(NetworkError::Version { version });
+                }
+                if let Role::Guest { color: local, .. } = &mut self.role {
+                    *local = Some(color);
+                }
+            }
+            // The game of the host after every change
+            Message::Sync(pgn) if self.is_spectator() => {
+                *chess = pgn.to_chess().map_err(|_| NetworkError::Protocol {
+                    line: Message::Sync(pgn).to_string(),
+                })?;
+                self.ready = true;
+            }
+            // The game of the host after the welcome
+            Message::Sync(pgn) if !self.is_host() && !self.ready && self.color().is_some() => {
+                *chess = pgn.to_chess().map_err(|_| NetworkError::Protocol {
+                    line: Message::Sync(pgn).to_string(),
+                })?;
+                self.ready = true;
+            }
+            message if self.ready && !self.is_spectator() => self.apply(message, chess),
+            message => {
+                return Err(NetworkError::Protocol {
+                    line: message.to_string(),
+                })
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply an action of the other player to the game.
+    fn apply(&mut self, message: Message, chess: &mut Chess) {
+        let remote = !self.color().expect("the color is known once connected");
+        match message {
+            Message::Move(m) => {
+                if chess.state.is_ongoing()
+                    && chess.board.side_to_move() == remote
+                    && chess.board.is_legal(m)
+                {
+                    chess.play(m.from, m.to);
+                    self.draw_offer = None;
+                } else if self.is_host() {
+                    #[cfg(feature = "log")]
+                    log::warn!("Unexpected move {m} of the guest, synchronise the game");
+                    self.sync(chess);
+                } else {
+                    #[cfg(feature = "log")]
+                    log::warn!("Unexpected move {m} of the host, join the game again");
+                    self.hello();
+                }
+            }
+            // The host owns the clocks, the guest can't change them
+            Message::Clock { white, black } if !self.is_host() => {
+                if let Some(clock) = &mut chess.clock {
+                    clock.set_remaining(Color::White, white);
+                    clock.set_remaining(Color::Black, black);
+                }
+            }
+            Message::OfferDraw => {
+                chess.offer_draw();
+                self.draw_offer = Some(remote);
+            }
+            // Only the offer of the local player can be accepted by the other one
+            Message::AcceptDraw if chess.offer_draw && self.draw_offer == Some(!remote) => {
+                chess.accept_draw()
+            }
+            Message::DeclareDraw if chess.can_declare_draw() => chess.declare_draw(),
+            Message::Resign if chess.state.is_ongoing() => chess.resign(remote),
+            Message::Sync(pgn) if !self.is_host() => {
+                if let Ok(synced) = pgn.to_chess() {
+                    *chess = synced;
+                }
+            }
+            _message => {
+                #[cfg(feature = "log")]
+                log::warn!("Ignore the message: {_message}");
+            }
+        }
+    }
+}
+
+/// Verify if two games are the same, apart from the clocks.
+pub(crate) fn is_same_game(chess: &Chess, other: &Chess) -> bool {
+    chess.board == other.board
+        && chess.moves == other.moves
+        && chess.state == other.state
+        && chess.offer_draw == other.offer_draw
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Board, GameState, Square, TimeControl};
+
+    /// Update the sessions until the condition is true (or panic after 5 seconds).
+    fn wait_until(
+        players: &mut [(&mut Session, &mut Chess)],
+        condition: impl Fn(&[(&mut Session, &mut Chess)]) -> bool,
+    ) {
+        let start = Instant::now();
+        while !condition(players) {
+            assert!(start.elapsed() < Duration::from_secs(5), "timeout");
+            for (session, chess) in players.iter_mut() {
+                session.update(chess);
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    fn connected(players: &[(&mut Session, &mut Chess)]) -> bool {
+        players.iter().all(|(session, _)| session.is_connected())
+    }
+
+    /// Play a local move and send it.
+    fn play(session: &mut Session, chess: &mut Chess, from: Square, to: Square) {
+        assert!(session.is_local_turn(chess));
+        chess.play(from, to);
+        session.send(Message::Move(ChessMove::new(from, to)));
+    }
+
+    #[test]
+    fn messages() {
+        let mut chess = Chess::with_time_control(Board::default(), "5+3".parse().unwrap());
+        chess.play(Square::E2, Square::E4);
+        chess.offer_draw();
+        for message in [
+            Message::Hello { version: 1 },
+            Message::Watch { version: 1 },
+            Message::Welcome {
+                version: 1,
+                color: Color::Black,
+            },
+            Message::Sync(Pgn::from_chess(&chess)),
+            Message::Move(ChessMove::new(Square::E7, Square::E5)),
+            Message::Clock {
+                white: Duration::from_millis(297_500),
+                black: Duration::from_secs(300),
+            },
+            Message::OfferDraw,
+            Message::AcceptDraw,
+            Message::DeclareDraw,
+            Message::Resign,
+        ] {
+            let line = message.to_string();
+            assert!(!line.contains('\n'));
+            assert_eq!(Message::from_str(&line).unwrap(), message);
+        }
+        for line in ["", "hello", "hello x", "move e2", "resign now", "sync \\x"] {
+            assert!(matches!(
+                Message::from_str(line),
+                Err(NetworkError::Protocol { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn host_and_join() {
+        let time_control = TimeControl::from_str("5+3").unwrap();
+        let mut host_chess = Chess::with_time_control(Board::default(), time_control);
+        let mut host = Session::host("127.0.0.1:0", Color::White).unwrap();
+        let mut guest_chess = Chess::default();
+        let mut guest = Session::join(host.local_addr().unwrap().to_string());
+        let mut players = [(&mut host, &mut host_chess), (&mut guest, &mut guest_chess)];
+        wait_until(&mut players, connected);
+        let [(host, host_chess), (guest, guest_chess)] = &mut players;
+        assert_eq!(guest.color(), Some(Color::Black));
+        // The guest plays with the time control of the host
+        assert_eq!(guest_chess.clock(), host_chess.clock());
+        assert!(!guest.is_local_turn(guest_chess));
+
+        play(host, host_chess, Square::E2, Square::E4);
+        wait_until(&mut players, |players| players[1].1.moves().len() == 1);
+        let [_, (guest, guest_chess)] = &mut players;
+        play(guest, guest_chess, Square::E7, Square::E5);
+        guest.send(Message::OfferDraw);
+        wait_until(&mut players, |players| players[0].1.offer_draw);
+        let [(host, host_chess), (_, guest_chess)] = &mut players;
+        assert_eq!(host_chess.board(), guest_chess.board());
+
+        host.send(Message::Resign);
+        host_chess.resign(Color::White);
+        wait_until(&mut players, |players| players[1].1.state.is_finish());
+        assert_eq!(players[1].1.state, GameState::Resigns(Color::White));
+    }
+
+    #[test]
+    fn reconnect() {
+        let mut host_chess = Chess::default();
+        let mut host = Session::host("127.0.0.1:0", Color::Black).unwrap();
+        let address = host.local_addr().unwrap().to_string();
+        let mut guest_chess = Chess::default();
+        let mut guest = Session::join(address.clone());
+        let mut players = [(&mut host, &mut host_chess), (&mut guest, &mut guest_chess)];
+        wait_until(&mut players, connected);
+        let [(_, _), (guest, guest_chess)] = &mut players;
+        play(guest, guest_chess, Square::D2, Square::D4);
+        wait_until(&mut players, |players| players[0].1.moves().len() == 1);
+
+        // The connection drops and the games differ
+        let [(host, host_chess), (guest, guest_chess)] = &mut players;
+        guest.disconnect();
+        guest_chess.undo();
+        assert!(!guest.is_local_turn(guest_chess));
+        play(host, host_chess, Square::D7, Square::D5);
+        wait_until(&mut players, |players| {
+            connected(players) && players[1].1.moves().len() == 2
+        });
+        assert_eq!(players[0].1.board(), players[1].1.board());
+
+        // A new process joins the game
+        let mut new_chess = Chess::default();
+        let mut new_guest = Session::join(address);
+        let [(host, host_chess), _] = players;
+        let mut players = [(host, host_chess), (&mut new_guest, &mut new_chess)];
+        wait_until(&mut players, |players| {
+            connected(players) && players[1].1.moves().len() == 2
+        });
+        assert_eq!(players[1].0.color(), Some(Color::White));
+    }
+
+    #[test]
+    fn spectators() {
+        let mut host_chess = Chess::default();
+        let mut host = Session::host("127.0.0.1:0", Color::White).unwrap();
+        let address = host.local_addr().unwrap().to_string();
+        let mut guest_chess = Chess::default();
+        let mut guest = Session::join(address.clone());
+        let mut spectator_chess = Chess::default();
+        let mut spectator = Session::watch(address);
+        assert!(spectator.is_spectator());
+        let mut players = [
+            (&mut host, &mut host_chess),
+            (&mut guest, &mut guest_chess),
+            (&mut spectator, &mut spectator_chess),
+        ];
+        wait_until(&mut players, connected);
+        assert_eq!(players[0].0.spectators(), 1);
+        assert_eq!(players[2].0.color(), None);
+        assert!(!players[2].0.is_local_turn(players[2].1));
+
+        // The moves of both players are seen by the spectator
+        let [(host, host_chess), ..] = &mut players;
+        play(host, host_chess, Square::E2, Square::E4);
+        wait_until(&mut players, |players| players[1].1.moves().len() == 1);
+        let [_, (guest, guest_chess), _] = &mut players;
+        play(guest, guest_chess, Square::E7, Square::E5);
+        guest.send(Message::Resign);
+        guest_chess.resign(Color::Black);
+        wait_until(&mut players, |players| {
+            players[2].1.state == GameState::Resigns(Color::Black)
+        });
+        assert_eq!(players[2].1.moves(), players[0].1.moves());
+
+        // The spectator doesn't send anything
+        let [_, _, (spectator, _)] = &mut players;
+        spectator.send(Message::OfferDraw);
+        wait_until(&mut players, |_| true);
+        assert!(!players[0].1.offer_draw);
+    }
+
+    #[test]
+    fn draw_offer() {
+        let mut chess = Chess::default();
+        let mut host = Session::host("127.0.0.1:0", Color::White).unwrap();
+        let mut stream = TcpStream::connect(host.local_addr().unwrap()).unwrap();
+        // The guest can't accept its own offer
+        stream
+            .write_all(b"hello 1\noffer-draw\naccept-draw\n")
+            .unwrap();
+        let start = Instant::now();
+        while !chess.offer_draw {
+            assert!(start.elapsed() < Duration::from_secs(5), "timeout");
+            host.update(&mut chess);
+            thread::sleep(Duration::from_millis(5));
+        }
+        for _ in 0..10 {
+            host.update(&mut chess);
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert!(chess.state.is_ongoing());
+
+        // The offer of the host
+        chess.offer_draw();
+        host.send(Message::OfferDraw);
+        stream.write_all(b"accept-draw\n").unwrap();
+        while chess.state.is_ongoing() {
+            assert!(start.elapsed() < Duration::from_secs(5), "timeout");
+            host.update(&mut chess);
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(chess.state, GameState::DrawAccepted);
+    }
+
+    #[test]
+    fn guest_clock() {
+        let time_control = TimeControl::from_str("5+3").unwrap();
+        let mut chess = Chess::with_time_control(Board::default(), time_control);
+        let mut host = Session::host("127.0.0.1:0", Color::White).unwrap();
+        let mut stream = TcpStream::connect(host.local_addr().unwrap()).unwrap();
+        stream
+            .write_all(b"hello 1\nclock 999999999 1\noffer-draw\n")
+            .unwrap();
+        let start = Instant::now();
+        while !chess.offer_draw {
+            assert!(start.elapsed() < Duration::from_secs(5), "timeout");
+            host.update(&mut chess);
+            thread::sleep(Duration::from_millis(5));
+        }
+        let clock = chess.clock().unwrap();
+        assert_eq!(clock.remaining(Color::White), Duration::from_secs(300));
+        assert_eq!(clock.remaining(Color::Black), Duration::from_secs(300));
+    }
+
+    #[test]
+    fn version_mismatch() {
+        let mut chess = Chess::default();
+        let mut host = Session::host("127.0.0.1:0", Color::White).unwrap();
+        let mut stream = TcpStream::connect(host.local_addr().unwrap()).unwrap();
+        stream.write_all(b"hello 999\n").unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        let start = Instant::now();
+        while line.is_empty() {
+            assert!(start.elapsed() < Duration::from_secs(5), "timeout");
+            host.update(&mut chess);
+            reader.get_mut().set_nonblocking(true).unwrap();
+            let _ = reader.read_line(&mut line);
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(line, format!("welcome {PROTOCOL_VERSION} black\n"));
+        assert!(!host.is_connected());
+    }
+}
@@ -0,0 +1,706 @@
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+
+use crate::annotation::parse_comment;
+use crate::{
+    Annotations, Board, Chess, ChessMove, Clock, Color, Error, GameState, Level, TimeControl,
+    ALL_COLORS, NUM_COLORS,
+};
+
+/// The result of a game written in PGN.
+const RESULTS: [&str; 4] = ["1-0", "0-1", "1/2-1/2", "*"];
+
+/// Custom tag with the state of the [`Clock`]: `<time control> <white ms> <black ms>`
+/// (e.g. `5+3 287500 300000`).
+pub const CLOCK_TAG: &str = "Clock";
+
+/// Custom tag written (with the value `1`) when a draw is offered in an ongoing game.
+pub const DRAW_OFFER_TAG: &str = "DrawOffer";
+
+/// Custom tags with the [`Level`] of the built-in engine playing white and black (e.g.
+/// `novice`).
+pub const LEVEL_TAGS: [&str; NUM_COLORS] = ["WhiteLevel", "BlackLevel"];
+
+/// The tags with the names and the Elo ratings of white and black.
+const PLAYER_TAGS: [(&str, &str); NUM_COLORS] = [("White", "WhiteElo"), ("Black", "BlackElo")];
+
+/// Maximum length of a line of the movetext.
+const LINE_LENGTH: usize = 80;
+
+/// A game in PGN (Portable Game Notation).
+///
+/// Only the main line is kept: variations are skipped, the comments keep their text and their
+/// arrows and circles (see [`Annotations`]), and a NAG (Numeric Annotation Glyph, e.g. `$2`
+/// for a mistake) is kept for each move.
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+/// use chess::{Pgn, Square};
+///
+/// let pgn = Pgn::from_str(r#"
+/// [Event "Casual game"]
+/// [White "Alice"]
+///
+/// 1. e4 e5 2. Nf3 {Develop} Nc6 *
+/// "#).unwrap();
+/// assert_eq!(pgn.tag("White"), Some("Alice"));
+/// assert_eq!(pgn.moves(), ["e4", "e5", "Nf3", "Nc6"]);
+/// assert_eq!(pgn.comment(3), Some("Develop"));
+///
+/// let mut chess = pgn.to_chess().unwrap();
+/// assert_eq!(chess.board().on(Square::C6).map(|(piece, _)| piece), Some(chess::Piece::Knight));
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pgn {
+    tags: Vec<(String, String)>,
+    moves: Vec<String>,
+    result: String,
+    annotations: BTreeMap<usize, Annotations>,
+    comments: BTreeMap<usize, String>,
+    nags: BTreeMap<usize, u8>,
+}
+
+impl Pgn {
+    /// Create an empty [`Pgn`] (no tags, no moves and an unknown result).
+    pub fn new() -> Self {
+        Pgn {
+            tags: vec![],
+            moves: vec![],
+            result: "*".to_string(),
+            annotations: BTreeMap::new(),
+            comments: BTreeMap::new(),
+            nags: BTreeMap::new(),
+        }
+    }
+
+    /// Create the [`Pgn`] of a game.
+    ///
+    /// The Seven Tag Roster comes first, filled with unknown values (`?`, `*` for the result
+    /// until it is known), the starting position
+    /// is written in the `FEN` tag when it is not the [default][Board::default] one.
+    ///
+    /// The clock and the draw offer are kept in custom tags ([`CLOCK_TAG`] and
+    /// [`DRAW_OFFER_TAG`]) to resume the game later. A player with a [`Level`] is named after
+    /// it, with its approximate Elo rating and its level in the [`LEVEL_TAGS`].
+    pub fn from_chess(chess: &Chess) -> Self {
+        let mut pgn = Pgn::new();
+        for name in ["Event", "Site", "Date", "Round", "White", "Black"] {
+            pgn.set_tag(name, "?");
+        }
+        pgn.set_tag("Result", "*");
+        let start = chess.start_board();
+        if start != Board::default() {
+            pgn.set_tag("SetUp", "1");
+            pgn.set_tag("FEN", &start.to_string());
+        }
+        for color in ALL_COLORS {
+            if let Some(level) = chess.level(color) {
+                let (name_tag, elo_tag) = PLAYER_TAGS[color as usize];
+                pgn.set_tag(name_tag, &format!("Engine ({level})"));
+                pgn.set_tag(elo_tag, &level.elo().to_string());
+                pgn.set_tag(LEVEL_TAGS[color as usize], &level.to_string());
+            }
+        }
+        for (fen, m) in chess.history.iter().zip(&chess.moves) {
+            let board = Board::from_str(fen).expect("valid fen from history");
+            pgn.moves.push(m.to_san(&board));
+        }
+        pgn.result = match chess.state {
+            GameState::Ongoing => "*",
+            GameState::Checkmates(color)
+            | GameState::Resigns(color)
+            | GameState::Timeout(color) => match color {
+                Color::White => "0-1",
+                Color::Black => "1-0",
+            },
+            GameState::Stalemate | GameState::DrawAccepted | GameState::DrawDeclared => "1/2-1/2",
+        }
+        .to_string();
+        pgn.set_tag("Result", &pgn.result.clone());
+        match chess.state {
+            GameState::Ongoing => {}
+            GameState::Timeout(_) => pgn.set_tag("Termination", "time forfeit"),
+            _ => pgn.set_tag("Termination", "normal"),
+        }
+        if let Some(clock) = chess.clock {
+            let time_control = clock.time_control();
+            pgn.set_tag(
+                "TimeControl",
+                &format!(
+                    "{}+{}",
+                    time_control.initial.as_secs(),
+                    time_control.increment.as_secs()
+                ),
+            );
+            pgn.set_tag(
+                CLOCK_TAG,
+                &format!(
+                    "{} {} {}",
+                    time_control,
+                    clock.remaining(Color::White).as_millis(),
+                    clock.remaining(Color::Black).as_millis()
+                ),
+            );
+        }
+        if chess.offer_draw && chess.state.is_ongoing() {
+            pgn.set_tag(DRAW_OFFER_TAG, "1");
+        }
+        pgn.annotations = chess.annotations.clone();
+        pgn
+    }
+
+    /// Replay the game.
+    ///
+    /// The state of the game is restored from the board, or from the result and the
+    /// `Termination` tag when the game didn't end on the board (i.e. resignation, timeout
+    /// or draw by agreement).
+    ///
+    /// The clock, the draw offer and the levels are restored from the custom tags (if exist).
+    pub fn to_chess(&self) -> Result<Chess, Error> {
+        let board = match self.tag("FEN") {
+            Some(fen) => Board::from_str(fen)?,
+            None => Board::default(),
+        };
+        let mut chess = Chess::new(board);
+        for (index, san) in self.moves.iter().enumerate() {
+            let m = ChessMove::from_san(&chess.board, san).map_err(|_| Error::InvalidPgn {
+                reason: format!("illegal move {} ({})", san, index / 2 + 1),
+            })?;
+            chess.play(m.from, m.to);
+        }
+        if chess.state.is_ongoing() {
+            let loser = match self.result.as_str() {
+                "1-0" => Some(Color::Black),
+                "0-1" => Some(Color::White),
+                _ => None,
+            };
+            chess.state = match (loser, self.tag("Termination")) {
+                (Some(color), Some("time forfeit")) => GameState::Timeout(color),
+                (Some(color), _) => GameState::Resigns(color),
+                (None, _) if self.result == "1/2-1/2" => {
+                    if chess.can_declare_draw() {
+                        GameState::DrawDeclared
+                    } else {
+                        GameState::DrawAccepted
+                    }
+                }
+                (None, _) => GameState::Ongoing,
+            };
+        }
+        if let Some(clock) = self.tag(CLOCK_TAG) {
+            chess.clock = Some(parse_clock(clock)?);
+        }
+        chess.offer_draw = chess.state.is_ongoing() && self.tag(DRAW_OFFER_TAG) == Some("1");
+        for color in ALL_COLORS {
+            if let Some(level) = self.tag(LEVEL_TAGS[color as usize]) {
+                chess.set_level(color, Some(Level::from_str(level)?));
+            }
+        }
+        chess.annotations = self
+            .annotations
+            .range(..=chess.moves.len())
+            .map(|(&ply, annotations)| (ply, annotations.clone()))
+            .collect();
+        Ok(chess)
+    }
+
+    /// Get all the tags, in order.
+    pub fn tags(&self) -> &[(String, String)] {
+        &self.tags
+    }
+
+    /// Get the value of a tag (if exist).
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Set the value of a tag, it replaces the previous value (if exist).
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag, _)| tag == name) {
+            Some((_, old)) => *old = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// Get the moves of the main line in SAN (Standard Algebraic Notation).
+    pub fn moves(&self) -> &[String] {
+        &self.moves
+    }
+
+    /// Get the arrows and circles drawn on the position after a number of moves (0 for the
+    /// start position), written in the comment that follows the move.
+    pub fn annotations(&self, ply: usize) -> Option<&Annotations> {
+        self.annotations.get(&ply)
+    }
+
+    /// Get the text of the comment on the position after a number of moves (0 for the start
+    /// position), without the arrows and circles.
+    pub fn comment(&self, ply: usize) -> Option<&str> {
+        self.comments.get(&ply).map(String::as_str)
+    }
+
+    /// Set the text of the comment on the position after a number of moves, an empty text
+    /// removes it.
+    pub fn set_comment(&mut self, ply: usize, text: &str) {
+        // The braces delimit the comment
+        let text = text.replace('{', "(").replace('}', ")");
+        if text.trim().is_empty() {
+            self.comments.remove(&ply);
+        } else {
+            self.comments.insert(ply, text.trim().to_string());
+        }
+    }
+
+    /// Get the NAG of the move that leads to the position after a number of moves (e.g.
+    /// `2` for a mistake, written `$2`).
+    pub fn nag(&self, ply: usize) -> Option<u8> {
+        self.nags.get(&ply).copied()
+    }
+
+    /// Set the NAG of the move that leads to the position after a number of moves,
+    /// [`None`] removes it.
+    pub fn set_nag(&mut self, ply: usize, nag: Option<u8>) {
+        match nag {
+            Some(nag) => self.nags.insert(ply, nag),
+            None => self.nags.remove(&ply),
+        };
+    }
+
+    /// Get the result of the game (`1-0`, `0-1`, `1/2-1/2` or `*`).
+    pub fn result(&self) -> &str {
+        &self.result
+    }
+}
+
+impl Default for Pgn {
+    fn default() -> Self {
+        Pgn::new()
+    }
+}
+
+/// Parse a tag pair: `[Name "Value"]`.
+fn parse_tag(line: &str) -> Result<(String, String), Error> {
+    let error = || Error::InvalidPgn {
+        reason: format!("invalid tag {line}"),
+    };
+    let content = line
+        .strip_prefix('[')
+        .and_then(|line| line.strip_suffix(']'))
+        .ok_or_else(error)?;
+    let (name, value) = content.split_once(char::is_whitespace).ok_or_else(error)?;
+    let value = value
+        .trim()
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .ok_or_else(error)?;
+    Ok((
+        name.to_string(),
+        value.replace("\\\"", "\"").replace("\\\\", "\\"),
+    ))
+}
+
+/// Parse the value of the [`CLOCK_TAG`].
+fn parse_clock(value: &str) -> Result<Clock, Error> {
+    let error = || Error::InvalidPgn {
+        reason: format!("invalid clock {value}"),
+    };
+    let mut tokens = value.split_whitespace();
+    let time_control = TimeControl::from_str(tokens.next().ok_or_else(error)?)?;
+    let mut clock = Clock::new(time_control);
+    for color in [Color::White, Color::Black] {
+        let millis = tokens.next().and_then(|millis| millis.parse().ok());
+        clock.set_remaining(color, Duration::from_millis(millis.ok_or_else(error)?));
+    }
+    match tokens.next() {
+        Some(_) => Err(error()),
+        None => Ok(clock),
+    }
+}
+
+impl FromStr for Pgn {
+    type Err = Error;
+
+    /// Parse the first game of a PGN text.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut pgn = Pgn::new();
+        let mut lines = s.lines().map(str::trim).peekable();
+
+        // Tag pairs section
+        while let Some(line) = lines.peek() {
+            if line.is_empty() {
+                lines.next();
+            } else if line.starts_with('[') {
+                let (name, value) = parse_tag(line)?;
+                pgn.set_tag(&name, &value);
+                lines.next();
+            } else {
+                break;
+            }
+        }
+
+        // Movetext section
+        let movetext: Vec<&str> = lines
+            .take_while(|line| !line.starts_with('['))
+            .map(|line| line.split_once(';').map_or(line, |(line, _)| line))
+            .collect();
+        let movetext = movetext.join("\n");
+        let mut tokens = Vec::new();
+        let mut token = String::new();
+        let mut depth = 0;
+        let mut chars = movetext.chars();
+        while let Some(c) = chars.next() {
+            if c.is_whitespace() || matches!(c, '{' | '(' | ')') {
+                tokens.push(core::mem::take(&mut token));
+            }
+            match c {
+                // Comments can't be nested, those of the main line are kept as tokens
+                '{' => {
+                    let mut comment = String::from("{");
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => comment.push(c),
+                            None => {
+                                return Err(Error::InvalidPgn {
+                                    reason: "unterminated comment".to_string(),
+                                })
+                            }
+                        }
+                    }
+                    if depth == 0 {
+                        tokens.push(comment);
+                    }
+                }
+                '(' => depth += 1,
+                ')' if depth == 0 => {
+                    return Err(Error::InvalidPgn {
+                        reason: "unexpected end of variation".to_string(),
+                    })
+                }
+                ')' => depth -= 1,
+                c if c.is_whitespace() => {}
+                c if depth == 0 => token.push(c),
+                _ => {}
+            }
+        }
+        if depth > 0 {
+            return Err(Error::InvalidPgn {
+                reason: "unterminated variation".to_string(),
+            });
+        }
+        tokens.push(token);
+
+        for token in tokens.iter().map(String::as_str) {
+            if RESULTS.contains(&token) {
+                pgn.result = token.to_string();
+                break;
+            }
+            if let Some(comment) = token.strip_prefix('{') {
+                let (annotations, text) = parse_comment(comment)?;
+                if !annotations.is_empty() {
+                    pgn.annotations.insert(pgn.moves.len(), annotations);
+                }
+                pgn.set_comment(pgn.moves.len(), &text);
+                continue;
+            }
+            if let Some(nag) = token.strip_prefix('$') {
+                let nag = nag.parse().map_err(|_| Error::InvalidPgn {
+                    reason: format!("invalid NAG {token}"),
+                })?;
+                pgn.nags.insert(pgn.moves.len(), nag);
+                continue;
+            }
+            // Skip the move numbers (e.g. "12." or "12...")
+            let token = match token.rfind('.') {
+                Some(index)
+                    if token[..index]
+                        .chars()
+                        .all(|c| c.is_ascii_digit() || c == '.') =>
+                {
+                    &token[index + 1..]
+                }
+                _ => token,
+            };
+            if token.is_empty() {
+                continue;
+            }
+            pgn.moves.push(token.to_string());
+        }
+        Ok(pgn)
+    }
+}
+
+impl fmt::Display for Pgn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, value) in &self.tags {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            writeln!(f, "[{name} \"{value}\"]")?;
+        }
+        if !self.tags.is_empty() {
+            writeln!(f)?;
+        }
+
+        let start = self
+            .tag("FEN")
+            .and_then(|fen| Board::from_str(fen).ok())
+            .unwrap_or_default();
+        let mut number = start.fullmoves();
+        let mut side = start.side_to_move();
+        let comment = |ply| {
+            let text = self.comments.get(&ply).map(String::as_str);
+            let annotations = self.annotations.get(&ply).map(Annotations::to_string);
+            match (text, annotations) {
+                (Some(text), Some(annotations)) => Some(format!("{{ {text} {annotations} }}")),
+                (Some(text), None) => Some(format!("{{ {text} }}")),
+                (None, Some(annotations)) => Some(format!("{{ {annotations} }}")),
+                (None, None) => None,
+            }
+        };
+        let mut tokens = Vec::new();
+        tokens.extend(comment(0));
+        for (index, san) in self.moves.iter().enumerate() {
+            // The number is written again after a comment
+            let after_comment =
+                self.annotations.contains_key(&index) || self.comments.contains_key(&index);
+            match side {
+                Color::White => tokens.push(format!("{number}. {san}")),
+                Color::Black if index == 0 || after_comment => {
+                    tokens.push(format!("{number}... {san}"))
+                }
+                Color::Black => tokens.push(san.clone()),
+            }
+            tokens.extend(self.nags.get(&(index + 1)).map(|nag| format!("${nag}")));
+            tokens.extend(comment(index + 1));
+            if side == Color::Black {
+                number += 1;
+            }
+            side = !side;
+        }
+        tokens.push(self.result.clone());
+
+        let mut line_length = 0;
+        for token in tokens {
+            if line_length > 0 && line_length + token.len() + 1 > LINE_LENGTH {
+                writeln!(f)?;
+                line_length = 0;
+            } else if line_length > 0 {
+                write!(f, " ")?;
+                line_length += 1;
+            }
+            write!(f, "{token}")?;
+            line_length += token.len();
+        }
+        writeln!(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AnnotationColor, Square};
+
+    #[test]
+    fn parse() {
+        let pgn = Pgn::from_str(
+            r#"[Event "F/S Return Match"]
+[Site "Belgrade, Serbia JUG"]
+[Result "1/2-1/2"]
+[Annotator "A \"quoted\" name"]
+
+1. e4 e5 2. Nf3 Nc6 3. Bb5 {This opening is called the Ruy Lopez.} 3... a6
+4. Ba4 (4. Bxc6 dxc6 (4... bxc6) 5. O-O) Nf6 $1 5. O-O ; end of line comment
+Be7 1/2-1/2"#,
+        )
+        .unwrap();
+        assert_eq!(pgn.tag("Site"), Some("Belgrade, Serbia JUG"));
+        assert_eq!(pgn.tag("Annotator"), Some("A \"quoted\" name"));
+        assert_eq!(pgn.tag("Unknown"), None);
+        assert_eq!(
+            pgn.moves(),
+            ["e4", "e5", "Nf3", "Nc6", "Bb5", "a6", "Ba4", "Nf6", "O-O", "Be7"]
+        );
+        assert_eq!(pgn.result(), "1/2-1/2");
+        assert_eq!(
+            pgn.comment(5),
+            Some("This opening is called the Ruy Lopez.")
+        );
+        assert_eq!(pgn.nag(8), Some(1));
+        assert_eq!(pgn.nag(7), None);
+
+        let chess = pgn.to_chess().unwrap();
+        assert_eq!(chess.moves().len(), 10);
+        assert_eq!(chess.state, GameState::DrawAccepted);
+    }
+
+    #[test]
+    fn parse_error() {
+        assert!(Pgn::from_str("[Event]\n\n1. e4").is_err());
+        assert!(Pgn::from_str("1. e4 {comment").is_err());
+        assert!(Pgn::from_str("1. e4 (1. d4").is_err());
+        assert!(Pgn::from_str("1. e4 $x").is_err());
+
+        let pgn = Pgn::from_str("1. e4 e5 2. Ke3 *").unwrap();
+        assert_eq!(
+            pgn.to_chess(),
+            Err(Error::InvalidPgn {
+                reason: "illegal move Ke3 (2)".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut chess = Chess::default();
+        for (from, to) in [
+            (Square::F2, Square::F3),
+            (Square::E7, Square::E5),
+            (Square::G2, Square::G4),
+            (Square::D8, Square::H4),
+        ] {
+            chess.play(from, to);
+        }
+        assert_eq!(chess.state, GameState::Checkmates(Color::White));
+
+        let pgn = Pgn::from_chess(&chess);
+        assert_eq!(pgn.result(), "0-1");
+        assert_eq!(pgn.moves(), ["f3", "e5", "g4", "Qh4#"]);
+        let text = pgn.to_string();
+        assert!(text.ends_with("\n1. f3 e5 2. g4 Qh4# 0-1\n"));
+        assert_eq!(Pgn::from_str(&text).unwrap(), pgn);
+        assert_eq!(pgn.to_chess().unwrap().moves(), chess.moves());
+    }
+
+    #[test]
+    fn from_position() {
+        let board = Board::from_str("4k3/8/8/8/8/8/4P3/4K3 b - - 0 12").unwrap();
+        let mut chess = Chess::new(board);
+        chess.play(Square::E8, Square::D8);
+        chess.resign(Color::White);
+
+        let pgn = Pgn::from_chess(&chess);
+        assert_eq!(pgn.tag("FEN"), Some(board.to_string().as_str()));
+        // The Seven Tag Roster first, in its order
+        let names: Vec<&str> = pgn.tags().iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names[..9],
+            ["Event", "Site", "Date", "Round", "White", "Black", "Result", "SetUp", "FEN"]
+        );
+        assert_eq!(pgn.tag("Result"), Some("0-1"));
+        assert!(pgn.to_string().ends_with("\n12... Kd8 0-1\n"));
+
+        let replayed = pgn.to_chess().unwrap();
+        assert_eq!(replayed.state, GameState::Resigns(Color::White));
+        assert_eq!(replayed.start_board(), board);
+    }
+
+    #[test]
+    fn clock_and_draw_offer() {
+        let time_control = TimeControl::from_str("5+3").unwrap();
+        let mut chess = Chess::with_time_control(Board::default(), time_control);
+        chess.play(Square::E2, Square::E4);
+        chess.tick(Duration::from_millis(12_345));
+        chess.offer_draw();
+
+        let pgn = Pgn::from_chess(&chess);
+        assert_eq!(pgn.tag("TimeControl"), Some("300+3"));
+        assert_eq!(pgn.tag(CLOCK_TAG), Some("5+3 303000 287655"));
+        assert_eq!(pgn.tag(DRAW_OFFER_TAG), Some("1"));
+        let replayed = Pgn::from_str(&pgn.to_string()).unwrap().to_chess().unwrap();
+        assert_eq!(replayed, chess);
+
+        let mut pgn = Pgn::from_str("1. e4 *").unwrap();
+        for clock in ["5+3", "5+3 1000", "5+3 1000 x", "5+3 1 2 3", "0+1 1 2"] {
+            pgn.set_tag(CLOCK_TAG, clock);
+            assert!(pgn.to_chess().is_err(), "{clock}");
+        }
+    }
+
+    #[test]
+    fn levels() {
+        let mut chess = Chess::default();
+        chess.set_level(Color::Black, Some(Level::Novice));
+        chess.play(Square::E2, Square::E4);
+
+        let pgn = Pgn::from_chess(&chess);
+        assert_eq!(pgn.tag("White"), Some("?"));
+        assert_eq!(pgn.tag("Black"), Some("Engine (novice)"));
+        assert_eq!(pgn.tag("BlackElo"), Some("600"));
+        assert_eq!(pgn.tag(LEVEL_TAGS[1]), Some("novice"));
+        assert_eq!(pgn.tag(LEVEL_TAGS[0]), None);
+        let replayed = Pgn::from_str(&pgn.to_string()).unwrap().to_chess().unwrap();
+        assert_eq!(replayed, chess);
+
+        let mut pgn = Pgn::from_str("1. e4 *").unwrap();
+        pgn.set_tag(LEVEL_TAGS[0], "master");
+        assert!(pgn.to_chess().is_err());
+    }
+
+    #[test]
+    fn annotations() {
+        let mut chess = Chess::default();
+        let mut annotations = Annotations::default();
+        annotations.toggle_circle(Square::E4, AnnotationColor::Red);
+        chess.set_annotations(0, annotations.clone());
+        chess.play(Square::E2, Square::E4);
+        annotations.toggle_arrow(Square::G8, Square::F6, AnnotationColor::Green);
+        chess.set_annotations(1, annotations.clone());
+        chess.play(Square::E7, Square::E5);
+
+        let text = Pgn::from_chess(&chess).to_string();
+        assert!(text.ends_with("\n{ [%csl Re4] } 1. e4 { [%csl Re4] [%cal Gg8f6] } 1... e5 *\n"));
+        let pgn = Pgn::from_str(&text).unwrap();
+        assert_eq!(pgn.annotations(1), Some(&annotations));
+        assert_eq!(pgn.annotations(2), None);
+        assert_eq!(pgn.to_chess().unwrap(), chess);
+
+        // Undone with the moves
+        chess.undo();
+        chess.undo();
+        assert_eq!(chess.annotations(1), None);
+        assert!(chess.annotations(0).is_some());
+        assert!(Pgn::from_str("1. e4 { [%cal Ge2] } *").is_err());
+    }
+
+    #[test]
+    fn comments_and_nags() {
+        let mut pgn = Pgn::from_str("1. e4 e5 2. Qh5 { [%cal Gh5f7] } Nc6 *").unwrap();
+        pgn.set_comment(0, "Short");
+        pgn.set_nag(3, Some(6));
+        pgn.set_comment(3, "Too early {really}");
+        pgn.set_nag(4, Some(1));
+        let text = pgn.to_string();
+        assert_eq!(
+            text,
+            "{ Short } 1. e4 e5 2. Qh5 $6 { Too early (really) [%cal Gh5f7] } 2... Nc6 $1 *\n"
+        );
+        assert_eq!(Pgn::from_str(&text).unwrap(), pgn);
+
+        pgn.set_comment(3, " ");
+        pgn.set_nag(3, None);
+        assert_eq!(pgn.comment(3), None);
+        assert_eq!(pgn.nag(3), None);
+    }
+
+    #[test]
+    fn long_game() {
+        let pgn = Pgn {
+            moves: vec!["Nf3".to_string(); 60],
+            ..Default::default()
+        };
+        for line in pgn.to_string().lines() {
+            assert!(line.len() <= LINE_LENGTH);
+        }
+    }
+}
@@ -1,9 +1,12 @@
-use std::fmt;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::fmt;
 
 use super::Color;
 
 /// Represent a chess piece.
 #[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Piece {
     Pawn,
     Knight,
@@ -39,6 +42,28 @@ impl Piece {
         *self as usize
     }
 
+    /// Get the material value of the [`Piece`] (in pawns).
+    ///
+    /// > **Note**: The [`Piece::King`] can't be captured so it's worth nothing.
+    ///
+    /// ```
+    /// use chess::Piece;
+    ///
+    /// assert_eq!(Piece::Pawn.value(), 1);
+    /// assert_eq!(Piece::Queen.value(), 9);
+    /// ```
+    #[inline]
+    pub fn value(&self) -> u32 {
+        match self {
+            Piece::Pawn => 1,
+            Piece::Knight => 3,
+            Piece::Bishop => 3,
+            Piece::Rook => 5,
+            Piece::Queen => 9,
+            Piece::King => 0,
+        }
+    }
+
     /// Convert a piece with a [`Color`] to a string.
     ///
     /// > **Note**: White pieces are uppercase, black pieces are lowercase.
@@ -49,11 +74,34 @@ impl Piece {
     /// assert_eq!(Piece::King.to_string(Color::White), "K");
     /// assert_eq!(Piece::Knight.to_string(Color::Black), "n");
     /// ```
+    #[cfg(feature = "alloc")]
     #[inline]
     pub fn to_string(&self, color: Color) -> String {
-        let piece = format!("{}", self);
+        String::from(self.to_char(color))
+    }
+
+    /// Convert a piece with a [`Color`] to a FEN character.
+    ///
+    /// > **Note**: White pieces are uppercase, black pieces are lowercase.
+    ///
+    /// ```
+    /// use chess::{Piece, Color};
+    ///
+    /// assert_eq!(Piece::King.to_char(Color::White), 'K');
+    /// assert_eq!(Piece::Knight.to_char(Color::Black), 'n');
+    /// ```
+    #[inline]
+    pub fn to_char(&self, color: Color) -> char {
+        let piece = match self {
+            Piece::Pawn => 'p',
+            Piece::Knight => 'n',
+            Piece::Bishop => 'b',
+            Piece::Rook => 'r',
+            Piece::Queen => 'q',
+            Piece::King => 'k',
+        };
         match color {
-            Color::White => piece.to_uppercase(),
+            Color::White => piece.to_ascii_uppercase(),
             Color::Black => piece,
         }
     }
@@ -61,18 +109,7 @@ impl Piece {
 
 impl fmt::Display for Piece {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Piece::Pawn => "p",
-                Piece::Knight => "n",
-                Piece::Bishop => "b",
-                Piece::Rook => "r",
-                Piece::Queen => "q",
-                Piece::King => "k",
-            }
-        )
+        write!(f, "{}", self.to_char(Color::Black))
     }
 }
 
@@ -91,6 +128,25 @@ mod tests {
     }
 
     #[test]
+    fn value() {
+        assert_eq!(Piece::Pawn.value(), 1);
+        assert_eq!(Piece::Knight.value(), 3);
+        assert_eq!(Piece::Bishop.value(), 3);
+        assert_eq!(Piece::Rook.value(), 5);
+        assert_eq!(Piece::Queen.value(), 9);
+        assert_eq!(Piece::King.value(), 0);
+    }
+
+    #[test]
+    fn to_char() {
+        assert_eq!(Piece::Pawn.to_char(Color::White), 'P');
+        assert_eq!(Piece::Queen.to_char(Color::White), 'Q');
+        assert_eq!(Piece::Knight.to_char(Color::Black), 'n');
+        assert_eq!(Piece::King.to_char(Color::Black), 'k');
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
     fn to_string_per_color() {
         assert_eq!(Piece::Pawn.to_string(Color::White), "P");
         assert_eq!(Piece::Knight.to_string(Color::White), "N");
@@ -0,0 +1,211 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+#[cfg(any(feature = "gui", feature = "tui"))]
+use std::sync::{Arc, Mutex};
+
+use crate::{Board, ChessMove, Engine, Error, Level, LevelEngine, UciEngine, UciError};
+
+/// A program that chooses the moves of a player.
+pub trait Bot: Send + fmt::Debug {
+    /// Get the name of the bot.
+    fn name(&self) -> String;
+
+    /// Choose the move to play (if exist).
+    fn choose_move(&mut self, board: &Board) -> Option<ChessMove>;
+}
+
+impl Bot for Engine {
+    fn name(&self) -> String {
+        format!("Engine (depth {})", self.depth())
+    }
+
+    fn choose_move(&mut self, board: &Board) -> Option<ChessMove> {
+        self.best_move(board)
+    }
+}
+
+impl Bot for LevelEngine {
+    fn name(&self) -> String {
+        format!("Engine ({})", self.level())
+    }
+
+    fn choose_move(&mut self, board: &Board) -> Option<ChessMove> {
+        LevelEngine::choose_move(self, board)
+    }
+}
+
+impl Bot for UciEngine {
+    fn name(&self) -> String {
+        UciEngine::name(self).to_string()
+    }
+
+    fn choose_move(&mut self, board: &Board) -> Option<ChessMove> {
+        match self.analyse(board) {
+            Ok(analysis) => Some(analysis.best_move),
+            Err(_error) => {
+                #[cfg(feature = "log")]
+                log::error!("{_error}");
+                None
+            }
+        }
+    }
+}
+
+/// A [`Bot`] shared with the thread where it thinks.
+#[cfg(any(feature = "gui", feature = "tui"))]
+pub(crate) type SharedBot = Arc<Mutex<Box<dyn Bot>>>;
+
+/// Who plays a side of the game.
+///
+/// It is written `human`, `engine`, `engine:<depth>`, `level:<level>`, `elo:<rating>` (the
+/// closest [`Level`]) or the path of an UCI engine.
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+/// use chess::{Level, Player};
+///
+/// assert_eq!(Player::from_str("human"), Ok(Player::Human));
+/// assert_eq!(Player::from_str("engine:4"), Ok(Player::Engine(4)));
+/// assert_eq!(Player::from_str("level:novice"), Ok(Player::Level(Level::Novice)));
+/// assert_eq!(Player::from_str("elo:1850"), Ok(Player::Level(Level::Advanced)));
+/// assert_eq!(
+///     Player::from_str("/usr/bin/stockfish"),
+///     Ok(Player::Uci("/usr/bin/stockfish".into()))
+/// );
+/// ```
+#[derive(Clone, Eq, PartialEq, Default, Debug)]
+pub enum Player {
+    /// The moves are played with the mouse.
+    #[default]
+    Human,
+    /// The built-in [`Engine`] with the given depth.
+    Engine(u32),
+    /// The built-in engine at a strength level (see [`LevelEngine`]).
+    Level(Level),
+    /// An UCI engine (path of the executable).
+    Uci(PathBuf),
+}
+
+impl Player {
+    /// Verify if the player is a human.
+    pub fn is_human(&self) -> bool {
+        matches!(self, Player::Human)
+    }
+
+    /// Get the strength level of the player (if he is a [`Player::Level`]).
+    pub fn level(&self) -> Option<Level> {
+        match self {
+            Player::Level(level) => Some(*level),
+            _ => None,
+        }
+    }
+
+    /// Get the depth of the built-in engine of the player (the depth of its level for a
+    /// [`Player::Level`]), [`None`] for a human or an UCI engine.
+    pub fn depth(&self) -> Option<u32> {
+        match self {
+            Player::Engine(depth) => Some(*depth),
+            Player::Level(level) => Some(level.strength().depth),
+            Player::Human | Player::Uci(_) => None,
+        }
+    }
+
+    /// Create the [`Bot`] that plays for this player ([`None`] for a human).
+    pub fn bot(&self) -> Result<Option<Box<dyn Bot>>, UciError> {
+        Ok(match self {
+            Player::Human => None,
+            Player::Engine(depth) => Some(Box::new(Engine::new(*depth))),
+            Player::Level(level) => Some(Box::new(LevelEngine::new(*level))),
+            Player::Uci(path) => Some(Box::new(UciEngine::spawn(path)?)),
+        })
+    }
+}
+
+impl FromStr for Player {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => Err(Error::InvalidPlayer {
+                player: s.to_string(),
+            }),
+            "human" => Ok(Player::Human),
+            "engine" => Ok(Player::Engine(Engine::default().depth())),
+            _ => {
+                let invalid = || Error::InvalidPlayer {
+                    player: s.to_string(),
+                };
+                if let Some(depth) = s.strip_prefix("engine:") {
+                    match depth.parse() {
+                        Ok(depth) if depth > 0 => Ok(Player::Engine(depth)),
+                        _ => Err(invalid()),
+                    }
+                } else if let Some(level) = s.strip_prefix("level:") {
+                    Level::from_str(level)
+                        .map(Player::Level)
+                        .map_err(|_| invalid())
+                } else if let Some(elo) = s.strip_prefix("elo:") {
+                    elo.parse()
+                        .map(|elo| Player::Level(Level::from_elo(elo)))
+                        .map_err(|_| invalid())
+                } else {
+                    Ok(Player::Uci(PathBuf::from(s)))
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Player {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Player::Human => write!(f, "human"),
+            Player::Engine(depth) => write!(f, "engine:{depth}"),
+            Player::Level(level) => write!(f, "level:{level}"),
+            Player::Uci(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!(
+            Player::from_str("engine"),
+            Ok(Player::Engine(Engine::default().depth()))
+        );
+        for player in [
+            "",
+            "engine:",
+            "engine:0",
+            "engine:-1",
+            "level:master",
+            "elo:high",
+        ] {
+            assert_eq!(
+                Player::from_str(player),
+                Err(Error::InvalidPlayer {
+                    player: player.to_string()
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn display() {
+        for player in [
+            Player::Human,
+            Player::Engine(2),
+            Player::Level(Level::Novice),
+            Player::Uci("./sf".into()),
+        ] {
+            assert_eq!(Player::from_str(&player.to_string()), Ok(player));
+        }
+    }
+}
@@ -0,0 +1,174 @@
+//! Moves queued by a player during the turn of the opponent.
+
+use alloc::vec::Vec;
+
+use crate::{Board, ChessMove, Color, Piece};
+
+/// The premoves of a player: the moves queued while the opponent (an engine or a remote
+/// player) is thinking, played as soon as the turn comes back.
+///
+/// A premove is [validated][Premoves::push] on the position where the queued moves are
+/// already played and the pieces of the opponent didn't move: the piece must be able to go
+/// to the destination, a capture is accepted even if the square is empty (or holds one of
+/// the player's pieces, for a recapture). When the turn comes back, the first premove is
+/// [played][Premoves::pop] if it is legal, otherwise the whole queue is cancelled.
+///
+/// # Examples
+///
+/// ```
+/// use chess::{Board, ChessMove, Color, Premoves, Square};
+///
+/// let mut board = Board::default();
+/// board.update(ChessMove::new(Square::E2, Square::E4));
+///
+/// // White premoves while black is thinking
+/// let mut premoves = Premoves::default();
+/// assert!(premoves.push(&board, Color::White, ChessMove::new(Square::G1, Square::F3)));
+/// assert!(premoves.push(&board, Color::White, ChessMove::new(Square::F1, Square::C4)));
+/// assert!(!premoves.push(&board, Color::White, ChessMove::new(Square::C4, Square::C8)));
+///
+/// board.update(ChessMove::new(Square::E7, Square::E5));
+/// assert_eq!(premoves.pop(&board), Some(ChessMove::new(Square::G1, Square::F3)));
+/// assert_eq!(premoves.len(), 1);
+/// ```
+#[derive(Clone, Eq, PartialEq, Default, Debug)]
+pub struct Premoves {
+    moves: Vec<ChessMove>,
+}
+
+impl Premoves {
+    /// Get the queued moves, the first one is played next.
+    pub fn moves(&self) -> &[ChessMove] {
+        &self.moves
+    }
+
+    /// Get the number of queued moves.
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    /// Verify if no move is queued.
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Cancel all the queued moves.
+    pub fn clear(&mut self) {
+        self.moves.clear();
+    }
+
+    /// Get the position after the queued moves of the player, the opponent doesn't move.
+    pub fn position(&self, board: &Board, color: Color) -> Board {
+        let mut position = *board;
+        position.set_en_passant(None);
+        for &m in &self.moves {
+            position.set_side_to_move(color);
+            position.update(m);
+        }
+        position.set_side_to_move(color);
+        position
+    }
+
+    /// Queue a move of the player if it is possible after the queued ones (see [`Premoves`]).
+    ///
+    /// Return true if the move is queued.
+    pub fn push(&mut self, board: &Board, color: Color, m: ChessMove) -> bool {
+        let position = self.position(board, color);
+        if m.from == m.to {
+            return false;
+        }
+        // The destination may be captured later, an opponent pawn is put there to allow it
+        let mut capture = position;
+        if capture.piece_on(m.to) != Some(Piece::King) {
+            capture[m.to] = Some((Piece::Pawn, !color));
+        }
+        let possible = position.is_valid(m) || capture.is_valid(m);
+        if possible {
+            self.moves.push(m);
+        }
+        possible
+    }
+
+    /// Take the first queued move when the turn of the player comes back (the side to move of
+    /// the board).
+    ///
+    /// If the move is not legal, all the queued moves are cancelled and [`None`] is returned.
+    pub fn pop(&mut self, board: &Board) -> Option<ChessMove> {
+        if self.moves.is_empty() {
+            return None;
+        }
+        let m = self.moves.remove(0);
+        if board.is_legal(m) {
+            Some(m)
+        } else {
+            self.moves.clear();
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Square;
+    use core::str::FromStr;
+
+    #[test]
+    fn push() {
+        let board = Board::default();
+        let mut premoves = Premoves::default();
+        // Not a piece of the player, or not a move of the piece
+        assert!(!premoves.push(&board, Color::Black, ChessMove::new(Square::E2, Square::E4)));
+        assert!(!premoves.push(&board, Color::Black, ChessMove::new(Square::E7, Square::E4)));
+        assert!(!premoves.push(&board, Color::Black, ChessMove::new(Square::E7, Square::E7)));
+        assert!(!premoves.push(&board, Color::Black, ChessMove::new(Square::A8, Square::A1)));
+        assert!(!premoves.push(&board, Color::Black, ChessMove::new(Square::D8, Square::E8)));
+        assert!(premoves.is_empty());
+
+        // A recapture on a piece of the player
+        assert!(premoves.push(&board, Color::Black, ChessMove::new(Square::D8, Square::D7)));
+        premoves.clear();
+
+        // The pawn can capture on an empty square, then the queen goes through its square
+        assert!(premoves.push(&board, Color::Black, ChessMove::new(Square::E7, Square::D6)));
+        assert!(premoves.push(&board, Color::Black, ChessMove::new(Square::D8, Square::E7)));
+        assert!(premoves.push(&board, Color::Black, ChessMove::new(Square::E7, Square::H4)));
+        assert_eq!(premoves.len(), 3);
+        let position = premoves.position(&board, Color::Black);
+        assert_eq!(position.on(Square::H4), Some((Piece::Queen, Color::Black)));
+        assert_eq!(position.on(Square::D6), Some((Piece::Pawn, Color::Black)));
+        assert_eq!(position.side_to_move(), Color::Black);
+        // The opponent didn't move
+        assert_eq!(position.on(Square::E2), Some((Piece::Pawn, Color::White)));
+    }
+
+    #[test]
+    fn pop() {
+        let board = Board::from_str("4k3/8/8/8/8/8/3p4/R5K1 w - - 0 1").unwrap();
+        let mut premoves = Premoves::default();
+        assert_eq!(premoves.pop(&board), None);
+        assert!(premoves.push(&board, Color::Black, ChessMove::new(Square::E8, Square::D8)));
+        assert!(premoves.push(&board, Color::Black, ChessMove::new(Square::D2, Square::D1)));
+
+        // The king is in check on the 8th rank: the whole queue is cancelled
+        let mut check = board;
+        check.update(ChessMove::new(Square::A1, Square::A8));
+        let mut cancelled = premoves.clone();
+        assert_eq!(cancelled.pop(&check), None);
+        assert!(cancelled.is_empty());
+
+        let mut quiet = board;
+        quiet.update(ChessMove::new(Square::G1, Square::H1));
+        assert_eq!(
+            premoves.pop(&quiet),
+            Some(ChessMove::new(Square::E8, Square::D8))
+        );
+        quiet.update(ChessMove::new(Square::E8, Square::D8));
+        quiet.update(ChessMove::new(Square::H1, Square::G1));
+        assert_eq!(
+            premoves.pop(&quiet),
+            Some(ChessMove::new(Square::D2, Square::D1))
+        );
+        assert!(premoves.is_empty());
+    }
+}
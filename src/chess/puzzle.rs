@@ -0,0 +1,692 @@
+//! Tactical puzzles: find the best moves of a position, the opponent replies automatically.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+#[cfg(feature = "gui")]
+use crate::PUZZLE_STATS_FILE_NAME;
+use crate::{Board, ChessMove, Color, Error, GameState, PUZZLE_K_FACTOR, PUZZLE_START_RATING};
+
+/// Error that can happen when loading puzzles or saving the statistics of the puzzles.
+#[derive(Error, Debug)]
+pub enum PuzzleError {
+    /// The file can't be read or written.
+    #[error("Can't access the puzzle file {}: {}", path.display(), source)]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    /// The file contains no valid puzzle, or invalid statistics.
+    #[error("Invalid puzzle file {}: {}", path.display(), source)]
+    Invalid { path: PathBuf, source: Error },
+}
+
+/// A tactical puzzle.
+///
+/// It is written as a line of CSV, like the puzzles of lichess.org:
+/// `PuzzleId,FEN,Moves,Rating,...,Themes,...`. The moves are written in UCI, the first one is
+/// played by the opponent, the user finds the next ones (the other moves of the opponent are
+/// replies). Only the first four fields are required, the themes are the 8th field.
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+/// use chess::{ChessMove, Color, Puzzle, Square};
+///
+/// let puzzle = Puzzle::from_str(
+///     "00008,r6k/pp2r2p/4Rp1Q/3p4/8/1N1P2R1/PqP2bPP/7K b - - 0 24,f2g3 e6e7 b2b1 b3c1 b1c1 h6c1,1913,75,94,6230,crushing hangingPiece long middlegame,https://lichess.org/787zsVup/black#48,",
+/// )
+/// .unwrap();
+/// assert_eq!(puzzle.id, "00008");
+/// assert_eq!(puzzle.moves[0], ChessMove::new(Square::F2, Square::G3));
+/// assert_eq!(puzzle.rating, 1913);
+/// assert_eq!(puzzle.color(), Color::White);
+/// assert!(puzzle.themes.contains(&"hangingPiece".to_string()));
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Puzzle {
+    /// The identifier of the puzzle.
+    pub id: String,
+    /// The position before the first move of the opponent.
+    pub board: Board,
+    /// The solution: the first move of the opponent, then the moves of the user and the
+    /// replies of the opponent.
+    pub moves: Vec<ChessMove>,
+    /// The rating of the puzzle.
+    pub rating: u32,
+    /// The themes of the puzzle (e.g. `mateIn2` or `fork`).
+    pub themes: Vec<String>,
+}
+
+impl Puzzle {
+    /// Get the color of the user (the opponent plays the first move).
+    pub fn color(&self) -> Color {
+        !self.board.side_to_move()
+    }
+
+    /// Load the puzzles of a CSV file (see [`Puzzle`]), the header and the empty lines are
+    /// skipped.
+    ///
+    /// The puzzles that can't be played are skipped (e.g. with a promotion to another piece
+    /// than a queen), the file is invalid only if it contains no puzzle.
+    pub fn load_csv<P: AsRef<Path>>(path: P) -> Result<Vec<Puzzle>, PuzzleError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|source| PuzzleError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let mut puzzles = vec![];
+        let mut first_error = None;
+        for line in content.lines() {
+            if line.trim().is_empty() || line.starts_with("PuzzleId") {
+                continue;
+            }
+            match Puzzle::from_str(line) {
+                Ok(puzzle) => puzzles.push(puzzle),
+                Err(error) => {
+                    first_error.get_or_insert(error);
+                }
+            }
+        }
+        match (puzzles.is_empty(), first_error) {
+            (true, error) => Err(PuzzleError::Invalid {
+                path: path.to_path_buf(),
+                source: error.unwrap_or_else(|| Error::InvalidPuzzle {
+                    reason: "no puzzle".to_string(),
+                }),
+            }),
+            (false, _) => Ok(puzzles),
+        }
+    }
+}
+
+impl FromStr for Puzzle {
+    type Err = Error;
+
+    /// From a line of CSV (see [`Puzzle`]), every move of the solution must be legal.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = |reason: &str| Error::InvalidPuzzle {
+            reason: format!("{reason} ({s})"),
+        };
+        let fields: Vec<&str> = s.trim().split(',').collect();
+        let [id, fen, moves, rating, ..] = fields.as_slice() else {
+            return Err(error("expected id, FEN, moves and rating"));
+        };
+        let board = Board::from_str(fen)?;
+        let moves = moves
+            .split_whitespace()
+            .map(ChessMove::from_str)
+            .collect::<Result<Vec<ChessMove>, Error>>()?;
+        // The opponent plays first, and the user plays last
+        if moves.len() < 2 || moves.len() % 2 != 0 {
+            return Err(error("the solution must end with a move of the user"));
+        }
+        let mut position = board;
+        for &m in moves.iter() {
+            if !position.is_legal(m) {
+                return Err(error(&format!("illegal move {m}")));
+            }
+            position.update(m);
+        }
+        let rating = rating.parse().map_err(|_| error("invalid rating"))?;
+        let themes = fields
+            .get(7)
+            .map(|themes| themes.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_default();
+        Ok(Puzzle {
+            id: id.to_string(),
+            board,
+            moves,
+            rating,
+            themes,
+        })
+    }
+}
+
+/// The result of a move of the user in a [`PuzzleAttempt`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum PuzzleMove {
+    /// The move is not legal, or the puzzle is over.
+    Illegal,
+    /// The move is not the solution, the user can try again.
+    Wrong,
+    /// The move is the solution, the opponent replied.
+    Correct,
+    /// The move is the last one of the solution, or a checkmate.
+    Solved,
+}
+
+/// An attempt to solve a [`Puzzle`].
+///
+/// The first move of the opponent is played at once, then every move of the user is checked:
+/// the move of the solution is played and the opponent replies. Any checkmate solves the
+/// puzzle, even if it is not the move of the solution.
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+/// use chess::{ChessMove, Puzzle, PuzzleAttempt, PuzzleMove, Square};
+///
+/// // Black plays a6, white mates with Rd8
+/// let puzzle = Puzzle::from_str("mate1,7k/p5pp/8/8/8/8/6PP/3R2K1 b - - 0 1,a7a6 d1d8,600").unwrap();
+/// let mut attempt = PuzzleAttempt::new(puzzle);
+/// assert_eq!(attempt.played(), &[ChessMove::new(Square::A7, Square::A6)]);
+/// assert_eq!(attempt.play(ChessMove::new(Square::D1, Square::D7)), PuzzleMove::Wrong);
+/// assert_eq!(attempt.play(ChessMove::new(Square::D1, Square::D8)), PuzzleMove::Solved);
+/// assert!(attempt.is_solved());
+/// assert_eq!(attempt.mistakes(), 1);
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct PuzzleAttempt {
+    puzzle: Puzzle,
+    board: Board,
+    played: Vec<ChessMove>,
+    mistakes: usize,
+    solved: bool,
+    solution_shown: bool,
+    last: Option<PuzzleMove>,
+}
+
+impl PuzzleAttempt {
+    /// Start to solve a puzzle, the first move of the opponent is played.
+    pub fn new(puzzle: Puzzle) -> Self {
+        let mut attempt = PuzzleAttempt {
+            board: puzzle.board,
+            puzzle,
+            played: vec![],
+            mistakes: 0,
+            solved: false,
+            solution_shown: false,
+            last: None,
+        };
+        attempt.play_solution_move();
+        attempt
+    }
+
+    /// Get the puzzle.
+    pub fn puzzle(&self) -> &Puzzle {
+        &self.puzzle
+    }
+
+    /// Get the current position.
+    pub fn board(&self) -> Board {
+        self.board
+    }
+
+    /// Get the moves played from the position of the puzzle (the first one is the move of
+    /// the opponent).
+    pub fn played(&self) -> &[ChessMove] {
+        &self.played
+    }
+
+    /// Get the number of wrong moves of the user.
+    pub fn mistakes(&self) -> usize {
+        self.mistakes
+    }
+
+    /// Get the result of the last move of the user (if exist).
+    pub fn last_move(&self) -> Option<PuzzleMove> {
+        self.last
+    }
+
+    /// Verify if the user found the solution.
+    pub fn is_solved(&self) -> bool {
+        self.solved
+    }
+
+    /// Verify if the solution was shown.
+    pub fn is_solution_shown(&self) -> bool {
+        self.solution_shown
+    }
+
+    /// Verify if the puzzle is over: solved, or the solution was shown.
+    pub fn is_over(&self) -> bool {
+        self.solved || self.solution_shown
+    }
+
+    /// Play a move of the user, the opponent replies if it is the solution.
+    pub fn play(&mut self, m: ChessMove) -> PuzzleMove {
+        if self.is_over() || !self.board.is_legal(m) {
+            return PuzzleMove::Illegal;
+        }
+        let expected = self.puzzle.moves[self.played.len()];
+        let mut after = self.board;
+        after.update(m);
+        let checkmate = matches!(after.state(), GameState::Checkmates(_));
+        let result = if m != expected && !checkmate {
+            self.mistakes += 1;
+            PuzzleMove::Wrong
+        } else {
+            self.board = after;
+            self.played.push(m);
+            if checkmate || self.played.len() == self.puzzle.moves.len() {
+                self.solved = true;
+                PuzzleMove::Solved
+            } else {
+                self.play_solution_move();
+                PuzzleMove::Correct
+            }
+        };
+        self.last = Some(result);
+        result
+    }
+
+    /// Play the rest of the solution.
+    pub fn show_solution(&mut self) {
+        if self.is_over() {
+            return;
+        }
+        while self.played.len() < self.puzzle.moves.len() {
+            self.play_solution_move();
+        }
+        self.solution_shown = true;
+    }
+
+    /// Play the next move of the solution.
+    fn play_solution_move(&mut self) {
+        let m = self.puzzle.moves[self.played.len()];
+        self.board.update(m);
+        self.played.push(m);
+    }
+}
+
+/// The statistics of the puzzles of the user, kept between runs.
+///
+/// The rating changes like an Elo rating after the first try of every puzzle: it increases
+/// more when a puzzle rated above the user is solved.
+///
+/// They are written on one line: `<rating> <solved> <failed>` (e.g. `1523 12 4`).
+///
+/// # Examples
+///
+/// ```
+/// use chess::PuzzleStats;
+///
+/// let mut stats = PuzzleStats::default();
+/// stats.record(1500, true);
+/// assert_eq!(stats.rating, 1516);
+/// stats.record(1500, false);
+/// assert_eq!(stats.success_rate(), 50.0);
+/// assert_eq!(stats.to_string(), "1499 1 1");
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct PuzzleStats {
+    /// The puzzle rating of the user.
+    pub rating: u32,
+    /// The number of puzzles solved at the first try.
+    pub solved: u32,
+    /// The number of puzzles failed (a wrong move or the solution shown).
+    pub failed: u32,
+}
+
+impl PuzzleStats {
+    /// Get the default path of the statistics file (in the user data directory).
+    ///
+    /// Return [`None`] if the platform has no data directory.
+    #[cfg(feature = "gui")]
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| {
+            dir.join(env!("CARGO_PKG_NAME"))
+                .join(PUZZLE_STATS_FILE_NAME)
+        })
+    }
+
+    /// Record the first try of a puzzle with the given rating.
+    pub fn record(&mut self, puzzle_rating: u32, success: bool) {
+        let expected =
+            1.0 / (1.0 + 10f64.powf((puzzle_rating as f64 - self.rating as f64) / 400.0));
+        let score = success as u8 as f64;
+        let rating = self.rating as f64 + PUZZLE_K_FACTOR * (score - expected);
+        self.rating = rating.round().max(0.0) as u32;
+        match success {
+            true => self.solved += 1,
+            false => self.failed += 1,
+        }
+    }
+
+    /// Get the number of puzzles tried.
+    pub fn attempts(&self) -> u32 {
+        self.solved + self.failed
+    }
+
+    /// Get the percentage of puzzles solved at the first try (0 if none was tried).
+    pub fn success_rate(&self) -> f32 {
+        match self.attempts() {
+            0 => 0.0,
+            attempts => 100.0 * self.solved as f32 / attempts as f32,
+        }
+    }
+
+    /// Load the statistics from a file.
+    ///
+    /// A missing file is not an error, the default statistics are returned.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<PuzzleStats, PuzzleError> {
+        let path = path.as_ref();
+        match fs::read_to_string(path) {
+            Ok(content) => PuzzleStats::from_str(&content).map_err(|source| PuzzleError::Invalid {
+                path: path.to_path_buf(),
+                source,
+            }),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                Ok(PuzzleStats::default())
+            }
+            Err(source) => Err(PuzzleError::Io {
+                path: path.to_path_buf(),
+                source,
+            }),
+        }
+    }
+
+    /// Save the statistics in a file, the parent directories are created if needed.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), PuzzleError> {
+        let path = path.as_ref();
+        let io_error = |source| PuzzleError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        fs::write(path, format!("{self}\n")).map_err(io_error)
+    }
+}
+
+impl Default for PuzzleStats {
+    fn default() -> Self {
+        PuzzleStats {
+            rating: PUZZLE_START_RATING,
+            solved: 0,
+            failed: 0,
+        }
+    }
+}
+
+impl fmt::Display for PuzzleStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {}", self.rating, self.solved, self.failed)
+    }
+}
+
+impl FromStr for PuzzleStats {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || Error::InvalidPuzzle {
+            reason: format!("invalid statistics ({})", s.trim()),
+        };
+        let values = s
+            .split_whitespace()
+            .map(u32::from_str)
+            .collect::<Result<Vec<u32>, _>>()
+            .map_err(|_| error())?;
+        match values.as_slice() {
+            &[rating, solved, failed] => Ok(PuzzleStats {
+                rating,
+                solved,
+                failed,
+            }),
+            _ => Err(error()),
+        }
+    }
+}
+
+/// A series of puzzles, with the statistics of the user.
+///
+/// Only the first try of a puzzle is recorded in the [`PuzzleStats`]: solved without a wrong
+/// move, or failed. The retries are not recorded.
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+/// use chess::{ChessMove, Puzzle, PuzzleMove, PuzzleStats, PuzzleTrainer, Square};
+///
+/// let puzzle = Puzzle::from_str("mate1,7k/p5pp/8/8/8/8/6PP/3R2K1 b - - 0 1,a7a6 d1d8,600").unwrap();
+/// let mut trainer = PuzzleTrainer::new(vec![puzzle], PuzzleStats::default()).unwrap();
+/// trainer.show_solution();
+/// assert_eq!(trainer.stats().failed, 1);
+///
+/// // The retry is not recorded
+/// trainer.retry();
+/// assert_eq!(trainer.play(ChessMove::new(Square::D1, Square::D8)), PuzzleMove::Solved);
+/// assert_eq!(trainer.stats().solved, 0);
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct PuzzleTrainer {
+    puzzles: Vec<Puzzle>,
+    index: usize,
+    attempt: PuzzleAttempt,
+    stats: PuzzleStats,
+    /// Whether the first try of the current puzzle is recorded.
+    recorded: bool,
+}
+
+impl PuzzleTrainer {
+    /// Start with the first puzzle ([`None`] if there is no puzzle).
+    pub fn new(puzzles: Vec<Puzzle>, stats: PuzzleStats) -> Option<Self> {
+        let attempt = PuzzleAttempt::new(puzzles.first()?.clone());
+        Some(PuzzleTrainer {
+            puzzles,
+            index: 0,
+            attempt,
+            stats,
+            recorded: false,
+        })
+    }
+
+    /// Get the current puzzle.
+    pub fn puzzle(&self) -> &Puzzle {
+        self.attempt.puzzle()
+    }
+
+    /// Get the index of the current puzzle.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Get the number of puzzles.
+    pub fn len(&self) -> usize {
+        self.puzzles.len()
+    }
+
+    /// Verify if there is no puzzle (never true, a trainer has at least one puzzle).
+    pub fn is_empty(&self) -> bool {
+        self.puzzles.is_empty()
+    }
+
+    /// Get the attempt of the current puzzle.
+    pub fn attempt(&self) -> &PuzzleAttempt {
+        &self.attempt
+    }
+
+    /// Get the statistics of the user.
+    pub fn stats(&self) -> PuzzleStats {
+        self.stats
+    }
+
+    /// Play a move of the user in the current puzzle.
+    pub fn play(&mut self, m: ChessMove) -> PuzzleMove {
+        let result = self.attempt.play(m);
+        match result {
+            PuzzleMove::Wrong => self.record(false),
+            PuzzleMove::Solved => self.record(true),
+            PuzzleMove::Illegal | PuzzleMove::Correct => {}
+        }
+        result
+    }
+
+    /// Show the solution of the current puzzle, it is failed.
+    pub fn show_solution(&mut self) {
+        if !self.attempt.is_over() {
+            self.record(false);
+            self.attempt.show_solution();
+        }
+    }
+
+    /// Try the current puzzle again.
+    pub fn retry(&mut self) {
+        self.attempt = PuzzleAttempt::new(self.puzzle().clone());
+    }
+
+    /// Go to the next puzzle, the first one follows the last one.
+    pub fn next(&mut self) {
+        self.index = (self.index + 1) % self.puzzles.len();
+        self.attempt = PuzzleAttempt::new(self.puzzles[self.index].clone());
+        self.recorded = false;
+    }
+
+    /// Record the first try of the current puzzle.
+    fn record(&mut self, success: bool) {
+        if !self.recorded {
+            self.recorded = true;
+            self.stats.record(self.puzzle().rating, success);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Square;
+
+    /// Black plays a6, white mates in 2: Rxd8+ Qxd8 then Qxd8#.
+    const MATE_IN_2: &str =
+        "mate2,3r2k1/p1q2ppp/8/8/8/8/3R1PPP/3Q2K1 b - - 0 1,a7a6 d2d8 c7d8 d1d8,1200,0,0,0,mateIn2 short";
+
+    #[test]
+    fn parse() {
+        let puzzle = Puzzle::from_str(MATE_IN_2).unwrap();
+        assert_eq!(puzzle.moves.len(), 4);
+        assert_eq!(puzzle.themes, ["mateIn2", "short"]);
+        let puzzle = Puzzle::from_str("mate1,7k/p5pp/8/8/8/8/6PP/3R2K1 b - - 0 1,a7a6 d1d8,600");
+        assert!(puzzle.unwrap().themes.is_empty());
+
+        for line in [
+            "",
+            "id,8/8/8/8/8/8/8/8 w - - 0 1",
+            "id,4k3/8/8/8/8/8/8/4K3 w - - 0 1,e1e2,1500",
+            "id,4k3/8/8/8/8/8/8/4K3 w - - 0 1,e1e2 e8e7 e2e3,1500",
+            "id,4k3/8/8/8/8/8/8/4K3 w - - 0 1,e1e3 e8e7,1500",
+            "id,4k3/8/8/8/8/8/8/4K3 w - - 0 1,e1e2 e8e7,high",
+            "id,4k3/4P3/8/8/8/8/8/4K3 w - - 0 1,e7e8n e8e7,1500",
+        ] {
+            assert!(Puzzle::from_str(line).is_err(), "{line}");
+        }
+    }
+
+    #[test]
+    fn attempt() {
+        let puzzle = Puzzle::from_str(MATE_IN_2).unwrap();
+        assert_eq!(puzzle.color(), Color::White);
+        let mut attempt = PuzzleAttempt::new(puzzle.clone());
+        assert_eq!(
+            attempt.play(ChessMove::new(Square::E1, Square::E2)),
+            PuzzleMove::Illegal
+        );
+        assert_eq!(
+            attempt.play(ChessMove::new(Square::H2, Square::H3)),
+            PuzzleMove::Wrong
+        );
+        assert_eq!(attempt.last_move(), Some(PuzzleMove::Wrong));
+        assert_eq!(
+            attempt.play(ChessMove::new(Square::D2, Square::D8)),
+            PuzzleMove::Correct
+        );
+        // The opponent replied
+        assert_eq!(attempt.played().len(), 3);
+        assert_eq!(attempt.board().side_to_move(), Color::White);
+        assert_eq!(
+            attempt.play(ChessMove::new(Square::D1, Square::D8)),
+            PuzzleMove::Solved
+        );
+        assert!(attempt.is_over());
+        assert_eq!(
+            attempt.play(ChessMove::new(Square::H2, Square::H3)),
+            PuzzleMove::Illegal
+        );
+
+        let mut attempt = PuzzleAttempt::new(puzzle);
+        attempt.show_solution();
+        assert_eq!(attempt.played().len(), 4);
+        assert!(attempt.is_solution_shown());
+        assert!(!attempt.is_solved());
+    }
+
+    #[test]
+    fn alternative_mate() {
+        // Black plays a6, both Rc8# and Rd8# mate (the solution is Rd8#)
+        let puzzle = Puzzle::from_str("alt,7k/p5pp/8/8/8/8/6PP/2RR2K1 b - - 0 1,a7a6 d1d8,900");
+        let mut attempt = PuzzleAttempt::new(puzzle.unwrap());
+        assert_eq!(
+            attempt.play(ChessMove::new(Square::C1, Square::C8)),
+            PuzzleMove::Solved
+        );
+        assert_eq!(attempt.mistakes(), 0);
+    }
+
+    #[test]
+    fn stats() {
+        let mut stats = PuzzleStats::default();
+        stats.record(2000, true);
+        assert!(stats.rating > PUZZLE_START_RATING + 25);
+        stats.record(1000, false);
+        assert_eq!(stats.attempts(), 2);
+        assert_eq!(PuzzleStats::from_str(&stats.to_string()).unwrap(), stats);
+        for stats in ["", "1500 1", "1500 1 x", "1500 1 2 3"] {
+            assert!(PuzzleStats::from_str(stats).is_err(), "{stats}");
+        }
+
+        let dir = std::env::temp_dir().join(format!("rg-chess-puzzles-{}", std::process::id()));
+        let path = dir.join("nested").join("stats.txt");
+        assert_eq!(PuzzleStats::load(&path).unwrap(), PuzzleStats::default());
+        stats.save(&path).unwrap();
+        assert_eq!(PuzzleStats::load(&path).unwrap(), stats);
+
+        let csv = dir.join("puzzles.csv");
+        fs::write(
+            &csv,
+            format!("PuzzleId,FEN,Moves,Rating\n\n{MATE_IN_2}\nid,invalid,e2e4,1\n"),
+        )
+        .unwrap();
+        assert_eq!(Puzzle::load_csv(&csv).unwrap().len(), 1);
+        fs::write(&csv, "id,invalid,e2e4,1\n").unwrap();
+        assert!(matches!(
+            Puzzle::load_csv(&csv),
+            Err(PuzzleError::Invalid { .. })
+        ));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn trainer() {
+        let puzzles = vec![
+            Puzzle::from_str(MATE_IN_2).unwrap(),
+            Puzzle::from_str(MATE_IN_2).unwrap(),
+        ];
+        let mut trainer = PuzzleTrainer::new(puzzles, PuzzleStats::default()).unwrap();
+        assert_eq!(trainer.len(), 2);
+        assert_eq!(
+            trainer.play(ChessMove::new(Square::H2, Square::H3)),
+            PuzzleMove::Wrong
+        );
+        trainer.play(ChessMove::new(Square::D2, Square::D8));
+        trainer.play(ChessMove::new(Square::D1, Square::D8));
+        assert_eq!((trainer.stats().solved, trainer.stats().failed), (0, 1));
+
+        trainer.next();
+        assert_eq!(trainer.index(), 1);
+        trainer.play(ChessMove::new(Square::D2, Square::D8));
+        trainer.play(ChessMove::new(Square::D1, Square::D8));
+        assert_eq!((trainer.stats().solved, trainer.stats().failed), (1, 1));
+        trainer.next();
+        assert_eq!(trainer.index(), 0);
+        assert!(PuzzleTrainer::new(vec![], PuzzleStats::default()).is_none());
+    }
+}
@@ -1,4 +1,5 @@
-use std::str::FromStr;
+use core::fmt;
+use core::str::FromStr;
 
 use crate::{Error, BOARD_SIZE};
 
@@ -81,6 +82,12 @@ impl Rank {
     }
 }
 
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", (b'1' + *self as u8) as char)
+    }
+}
+
 impl FromStr for Rank {
     type Err = Error;
 
@@ -179,4 +186,11 @@ mod tests {
         assert_eq!(Rank::from_str(" 1"), Err(Error::InvalidRank));
         assert_eq!(Rank::from_str("second"), Err(Error::InvalidRank));
     }
+
+    #[test]
+    fn display() {
+        for rank in ALL_RANKS {
+            assert_eq!(Rank::from_str(&rank.to_string()), Ok(rank));
+        }
+    }
 }
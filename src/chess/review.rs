@@ -0,0 +1,322 @@
+//! Review of a game: the loss of every move compared to the best move of an engine.
+
+use std::fmt;
+
+use crate::{Analysis, Board, Chess, ChessMove, Color, GameState, Pgn};
+
+/// The maximum score (in centipawns) of a position in a [`GameReview`], a mate is worth
+/// this score.
+pub const REVIEW_MAX_SCORE: i32 = 1000;
+
+/// Number of classes of moves.
+pub const NUM_MOVE_CLASSES: usize = 5;
+
+/// The class of a move according to its loss (in centipawns).
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum MoveClass {
+    /// The best move of the engine, or a move as good.
+    Best,
+    /// Less than 50 centipawns lost.
+    Good,
+    /// Less than 100 centipawns lost, the NAG is `$6` (`?!`).
+    Inaccuracy,
+    /// Less than 300 centipawns lost, the NAG is `$2` (`?`).
+    Mistake,
+    /// At least 300 centipawns lost, the NAG is `$4` (`??`).
+    Blunder,
+}
+
+/// List of all the classes of moves, from the best to the worst.
+pub const ALL_MOVE_CLASSES: [MoveClass; NUM_MOVE_CLASSES] = [
+    MoveClass::Best,
+    MoveClass::Good,
+    MoveClass::Inaccuracy,
+    MoveClass::Mistake,
+    MoveClass::Blunder,
+];
+
+impl MoveClass {
+    /// Get the class of a move that loses the given number of centipawns.
+    pub fn from_loss(loss: u32) -> Self {
+        match loss {
+            0 => MoveClass::Best,
+            1..50 => MoveClass::Good,
+            50..100 => MoveClass::Inaccuracy,
+            100..300 => MoveClass::Mistake,
+            _ => MoveClass::Blunder,
+        }
+    }
+
+    /// Get the NAG (Numeric Annotation Glyph) written after the move in PGN (if exist).
+    pub fn nag(self) -> Option<u8> {
+        match self {
+            MoveClass::Best | MoveClass::Good => None,
+            MoveClass::Inaccuracy => Some(6),
+            MoveClass::Mistake => Some(2),
+            MoveClass::Blunder => Some(4),
+        }
+    }
+}
+
+impl fmt::Display for MoveClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            MoveClass::Best => "Best move",
+            MoveClass::Good => "Good move",
+            MoveClass::Inaccuracy => "Inaccuracy",
+            MoveClass::Mistake => "Mistake",
+            MoveClass::Blunder => "Blunder",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The review of a move.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct MoveReview {
+    /// The position before the move.
+    pub board: Board,
+    /// The move played.
+    pub m: ChessMove,
+    /// The best move of the engine (if the engine found one).
+    pub best_move: Option<ChessMove>,
+    /// The loss of the move compared to the best move (in centipawns).
+    pub loss: u32,
+    /// The class of the move according to its loss.
+    pub class: MoveClass,
+    /// The accuracy of the move (from 0 to 100), according to the winning chances lost.
+    pub accuracy: f32,
+}
+
+/// The review of the moves of a game by an engine.
+///
+/// Every position of the game is searched, the loss of a move is the difference between the
+/// score of the position before the move and the score after the move, for the player who
+/// moves (0 for the best move). The scores are bounded by [`REVIEW_MAX_SCORE`].
+///
+/// The accuracy of a move decreases with the winning chances lost, as on lichess.org: 100
+/// for the best move, 0 for the loss of a won position.
+///
+/// # Examples
+///
+/// ```
+/// use chess::{Chess, Color, Engine, GameReview, MoveClass, Square};
+///
+/// let mut chess = Chess::default();
+/// chess.play(Square::E2, Square::E4);
+/// chess.play(Square::E7, Square::E5);
+/// chess.play(Square::D1, Square::H5);
+/// // Qxe5 is checkmate
+/// chess.play(Square::E8, Square::E7);
+///
+/// let engine = Engine::new(2);
+/// let review = GameReview::new(&chess, |board| engine.analyse(board));
+/// assert_eq!(review.moves()[3].class, MoveClass::Blunder);
+/// assert_eq!(review.count(Color::Black, MoveClass::Blunder), 1);
+/// assert!(review.accuracy(Color::White) > review.accuracy(Color::Black));
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct GameReview {
+    scores: Vec<i32>,
+    moves: Vec<MoveReview>,
+}
+
+impl GameReview {
+    /// Review the moves of a game: `analyse` searches a position ([`None`] if the game is
+    /// over).
+    pub fn new(chess: &Chess, mut analyse: impl FnMut(&Board) -> Option<Analysis>) -> Self {
+        let boards: Vec<Board> = (0..=chess.moves.len())
+            .map(|ply| chess.board_at(ply).expect("position of the game"))
+            .collect();
+        let analyses: Vec<Option<Analysis>> = boards.iter().map(&mut analyse).collect();
+        let scores: Vec<i32> = boards
+            .iter()
+            .zip(&analyses)
+            .map(|(board, analysis)| white_score(board, analysis.as_ref()))
+            .collect();
+        let moves = chess
+            .moves
+            .iter()
+            .enumerate()
+            .map(|(ply, &m)| {
+                let board = boards[ply];
+                let sign = match board.side_to_move() {
+                    Color::White => 1,
+                    Color::Black => -1,
+                };
+                let best_move = analyses[ply].as_ref().map(|analysis| analysis.best_move);
+                let before = sign * scores[ply];
+                let loss = match best_move == Some(m) {
+                    true => 0,
+                    false => (before - sign * scores[ply + 1]).max(0) as u32,
+                };
+                MoveReview {
+                    board,
+                    m,
+                    best_move,
+                    loss,
+                    class: MoveClass::from_loss(loss),
+                    accuracy: move_accuracy(before, before - loss as i32),
+                }
+            })
+            .collect();
+        GameReview { scores, moves }
+    }
+
+    /// Get the score of every position (in centipawns, from the point of view of white),
+    /// from the start position to the last one.
+    pub fn scores(&self) -> &[i32] {
+        &self.scores
+    }
+
+    /// Get the review of every move.
+    pub fn moves(&self) -> &[MoveReview] {
+        &self.moves
+    }
+
+    /// Get the moves of a player.
+    fn moves_of(&self, color: Color) -> impl Iterator<Item = &MoveReview> {
+        self.moves
+            .iter()
+            .filter(move |review| review.board.side_to_move() == color)
+    }
+
+    /// Get the accuracy of a player (from 0 to 100): the average accuracy of his moves,
+    /// 100 if he didn't move.
+    pub fn accuracy(&self, color: Color) -> f32 {
+        let accuracies: Vec<f32> = self.moves_of(color).map(|review| review.accuracy).collect();
+        match accuracies.len() {
+            0 => 100.0,
+            len => accuracies.iter().sum::<f32>() / len as f32,
+        }
+    }
+
+    /// Get the average loss of the moves of a player (in centipawns).
+    pub fn average_loss(&self, color: Color) -> f32 {
+        let losses: Vec<u32> = self.moves_of(color).map(|review| review.loss).collect();
+        match losses.len() {
+            0 => 0.0,
+            len => losses.iter().sum::<u32>() as f32 / len as f32,
+        }
+    }
+
+    /// Count the moves of a player in a class.
+    pub fn count(&self, color: Color, class: MoveClass) -> usize {
+        self.moves_of(color)
+            .filter(|review| review.class == class)
+            .count()
+    }
+
+    /// Write the review in the PGN of the game: the NAG of the inaccuracies, the mistakes and
+    /// the blunders, a comment with the best move and the score (`[%eval]`) after every move,
+    /// and the accuracies before the first move.
+    ///
+    /// The PGN must contain the moves of the reviewed game, the text of its comments is kept.
+    pub fn annotate(&self, pgn: &mut Pgn) {
+        let add_comment = |pgn: &mut Pgn, ply: usize, text: String| {
+            let text = match pgn.comment(ply) {
+                Some(comment) => format!("{comment} {text}"),
+                None => text,
+            };
+            pgn.set_comment(ply, &text);
+        };
+        add_comment(
+            pgn,
+            0,
+            format!(
+                "Accuracy: white {:.1}%, black {:.1}%",
+                self.accuracy(Color::White),
+                self.accuracy(Color::Black)
+            ),
+        );
+        for (index, review) in self.moves.iter().enumerate() {
+            let ply = index + 1;
+            pgn.set_nag(ply, review.class.nag());
+            let eval = format!("[%eval {:.2}]", self.scores[ply] as f32 / 100.0);
+            let text = match (review.class.nag(), review.best_move) {
+                (Some(_), Some(best_move)) => format!(
+                    "{}, {} was best. {eval}",
+                    review.class,
+                    best_move.to_san(&review.board)
+                ),
+                _ => eval,
+            };
+            add_comment(pgn, ply, text);
+        }
+    }
+}
+
+/// Get the score of a position from the point of view of white, bounded by
+/// [`REVIEW_MAX_SCORE`].
+fn white_score(board: &Board, analysis: Option<&Analysis>) -> i32 {
+    let score = match (analysis, board.side_to_move()) {
+        (Some(analysis), Color::White) => analysis.score,
+        (Some(analysis), Color::Black) => -analysis.score,
+        (None, _) => match board.state() {
+            GameState::Checkmates(Color::White) => -REVIEW_MAX_SCORE,
+            GameState::Checkmates(Color::Black) => REVIEW_MAX_SCORE,
+            _ => 0,
+        },
+    };
+    score.clamp(-REVIEW_MAX_SCORE, REVIEW_MAX_SCORE)
+}
+
+/// Get the winning chances (from 0 to 100) of a score in centipawns.
+fn win_percent(score: i32) -> f32 {
+    50.0 + 50.0 * (2.0 / (1.0 + (-0.003_682_08 * score as f32).exp()) - 1.0)
+}
+
+/// Get the accuracy of a move (from 0 to 100) from the score before and after the move,
+/// for the player who moves.
+fn move_accuracy(before: i32, after: i32) -> f32 {
+    let lost = (win_percent(before) - win_percent(after)).max(0.0);
+    (103.166_8 * (-0.043_54 * lost).exp() - 3.166_9).clamp(0.0, 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Engine, Square};
+
+    #[test]
+    fn classes() {
+        assert_eq!(MoveClass::from_loss(0), MoveClass::Best);
+        assert_eq!(MoveClass::from_loss(49), MoveClass::Good);
+        assert_eq!(MoveClass::from_loss(50), MoveClass::Inaccuracy);
+        assert_eq!(MoveClass::from_loss(299), MoveClass::Mistake);
+        assert_eq!(MoveClass::from_loss(300), MoveClass::Blunder);
+        assert!(move_accuracy(30, 30) > 99.9);
+        assert!(move_accuracy(0, -REVIEW_MAX_SCORE) < 10.0);
+    }
+
+    #[test]
+    fn review_and_annotate() {
+        // Fool's mate: black mates, white blunders
+        let mut chess = Chess::default();
+        for (from, to) in [
+            (Square::F2, Square::F3),
+            (Square::E7, Square::E5),
+            (Square::G2, Square::G4),
+            (Square::D8, Square::H4),
+        ] {
+            chess.play(from, to);
+        }
+        let engine = Engine::new(2);
+        let review = GameReview::new(&chess, |board| engine.analyse(board));
+        assert_eq!(review.scores().len(), 5);
+        assert_eq!(review.scores()[4], -REVIEW_MAX_SCORE);
+        assert_eq!(review.moves()[2].class, MoveClass::Blunder);
+        assert_eq!(review.moves()[3].class, MoveClass::Best);
+        assert_eq!(review.count(Color::White, MoveClass::Blunder), 1);
+        assert!(review.average_loss(Color::White) > 300.0);
+        assert!(review.accuracy(Color::Black) > 90.0);
+
+        let mut pgn = Pgn::from_chess(&chess);
+        review.annotate(&mut pgn);
+        assert_eq!(pgn.nag(3), Some(4));
+        assert_eq!(pgn.nag(4), None);
+        assert!(pgn.comment(3).unwrap().starts_with("Blunder, "));
+        assert_eq!(pgn.comment(4), Some("[%eval -10.00]"));
+        assert!(pgn.comment(0).unwrap().starts_with("Accuracy: white "));
+    }
+}
@@ -0,0 +1,138 @@
+//! Save a game in a PGN file and load it to resume the game.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::{Chess, Error, GameReview, Pgn};
+#[cfg(feature = "gui")]
+use crate::{AUTOSAVE_FILE_NAME, REVIEW_FILE_NAME, SAVED_GAME_FILE_NAME};
+
+/// Error that can happen when saving or loading a game.
+#[derive(Error, Debug)]
+pub enum SaveError {
+    /// The game file can't be read or written.
+    #[error("Can't access the game file {}: {}", path.display(), source)]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    /// The game file is not a valid PGN game.
+    #[error("Invalid game file {}: {}", path.display(), source)]
+    Pgn { path: PathBuf, source: Error },
+}
+
+impl Chess {
+    /// Get the path of the file where the game in progress is saved after every move
+    /// (in the user data directory).
+    #[cfg(feature = "gui")]
+    pub fn autosave_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join(env!("CARGO_PKG_NAME")).join(AUTOSAVE_FILE_NAME))
+    }
+
+    /// Get the path of the file used by the Save and Load buttons (in the user data directory).
+    #[cfg(feature = "gui")]
+    pub fn saved_game_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join(env!("CARGO_PKG_NAME")).join(SAVED_GAME_FILE_NAME))
+    }
+
+    /// Get the path of the file where the game report exports the annotated game (in the
+    /// user data directory).
+    #[cfg(feature = "gui")]
+    pub fn review_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join(env!("CARGO_PKG_NAME")).join(REVIEW_FILE_NAME))
+    }
+
+    /// Save the game in a PGN file, the parent directories are created if needed.
+    ///
+    /// The board, the history, the clock, the draw offer and the state are kept
+    /// (see [`Pgn::from_chess`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::{Chess, Square};
+    ///
+    /// let path = std::env::temp_dir().join("rg-chess-doc-save.pgn");
+    /// let mut chess = Chess::default();
+    /// chess.play(Square::E2, Square::E4);
+    /// chess.save(&path).unwrap();
+    ///
+    /// assert_eq!(Chess::load(&path).unwrap(), chess);
+    /// # std::fs::remove_file(path).unwrap();
+    /// ```
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SaveError> {
+        write_pgn(path.as_ref(), &Pgn::from_chess(self))
+    }
+
+    /// Save the game in a PGN file annotated with its review (see [`GameReview::annotate`]).
+    pub fn save_review<P: AsRef<Path>>(
+        &self,
+        review: &GameReview,
+        path: P,
+    ) -> Result<(), SaveError> {
+        let mut pgn = Pgn::from_chess(self);
+        review.annotate(&mut pgn);
+        write_pgn(path.as_ref(), &pgn)
+    }
+
+    /// Load a game saved in a PGN file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Chess, SaveError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|source| SaveError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Pgn::from_str(&content)
+            .and_then(|pgn| pgn.to_chess())
+            .map_err(|source| SaveError::Pgn {
+                path: path.to_path_buf(),
+                source,
+            })
+    }
+}
+
+/// Write a PGN file, the parent directories are created if needed.
+fn write_pgn(path: &Path, pgn: &Pgn) -> Result<(), SaveError> {
+    let io_error = |source| SaveError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(io_error)?;
+    }
+    fs::write(path, pgn.to_string()).map_err(io_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{Board, Color, GameState, Square, TimeControl};
+
+    #[test]
+    fn save_and_load() {
+        let dir = std::env::temp_dir().join(format!("rg-chess-save-{}", std::process::id()));
+        let path = dir.join("nested").join("game.pgn");
+        assert!(matches!(Chess::load(&path), Err(SaveError::Io { .. })));
+
+        let time_control = TimeControl::from_str("3+2").unwrap();
+        let mut chess = Chess::with_time_control(Board::default(), time_control);
+        chess.play(Square::G1, Square::F3);
+        chess.tick(Duration::from_millis(1500));
+        chess.play(Square::G8, Square::F6);
+        chess.resign(Color::White);
+        chess.save(&path).unwrap();
+        let loaded = Chess::load(&path).unwrap();
+        assert_eq!(loaded, chess);
+        assert_eq!(loaded.state, GameState::Resigns(Color::White));
+
+        fs::write(&path, "1. e4 e4 *").unwrap();
+        assert!(matches!(Chess::load(&path), Err(SaveError::Pgn { .. })));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
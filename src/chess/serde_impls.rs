@@ -0,0 +1,141 @@
+//! Implement [`Serialize`] and [`Deserialize`] for the core types (feature `serde`).
+//!
+//! The types with a standard notation are written as strings: a [`Square`] as `"e4"`,
+//! a [`Board`] in FEN, a [`ChessMove`] in UCI (`"e2e4"`) and a [`Player`] as `"engine:3"`.
+//! A [`Chess`] game is written in PGN, its moves are replayed when it is read.
+//! The other types derive their implementation.
+
+use alloc::string::String;
+use core::str::FromStr;
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(feature = "std")]
+use crate::Player;
+use crate::{Board, Chess, ChessMove, File, Pgn, Rank, Square};
+
+/// Implement [`Serialize`] with [`Display`][core::fmt::Display] and [`Deserialize`]
+/// with [`FromStr`].
+macro_rules! serde_as_string {
+    ($($type:ty),* $(,)?) => {
+        $(
+            impl Serialize for $type {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.collect_str(self)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $type {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let text = String::deserialize(deserializer)?;
+                    <$type>::from_str(&text).map_err(de::Error::custom)
+                }
+            }
+        )*
+    };
+}
+
+serde_as_string!(File, Rank, Square, ChessMove, Board);
+#[cfg(feature = "std")]
+serde_as_string!(Player);
+
+impl Serialize for Chess {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&Pgn::from_chess(self))
+    }
+}
+
+impl<'de> Deserialize<'de> for Chess {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        let pgn = Pgn::from_str(&text).map_err(de::Error::custom)?;
+        pgn.to_chess().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{
+        Analysis, CastleRights, Chess, Color, GameState, Pgn, Piece, TimeControl, ALL_SQUARES,
+    };
+
+    #[test]
+    fn as_string() {
+        assert_eq!(serde_json::to_string(&Square::E4).unwrap(), r#""e4""#);
+        assert_eq!(serde_json::to_string(&File::E).unwrap(), r#""e""#);
+        assert_eq!(serde_json::to_string(&Rank::Fourth).unwrap(), r#""4""#);
+        assert_eq!(
+            serde_json::to_string(&ChessMove::new(Square::E2, Square::E4)).unwrap(),
+            r#""e2e4""#
+        );
+        assert_eq!(
+            serde_json::to_string(&Board::default()).unwrap(),
+            r#""rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1""#
+        );
+        #[cfg(feature = "std")]
+        assert_eq!(
+            serde_json::to_string(&Player::Engine(3)).unwrap(),
+            r#""engine:3""#
+        );
+
+        for square in ALL_SQUARES {
+            let json = serde_json::to_string(&square).unwrap();
+            assert_eq!(serde_json::from_str::<Square>(&json).unwrap(), square);
+        }
+        assert!(serde_json::from_str::<Square>(r#""i9""#).is_err());
+        assert!(serde_json::from_str::<Board>(r#""8/8 w""#).is_err());
+        assert!(serde_json::from_str::<ChessMove>("42").is_err());
+    }
+
+    #[test]
+    fn derived() {
+        assert_eq!(
+            serde_json::to_string(&Piece::Knight).unwrap(),
+            r#""Knight""#
+        );
+        assert_eq!(serde_json::to_string(&Color::Black).unwrap(), r#""Black""#);
+        assert_eq!(
+            serde_json::to_string(&GameState::Checkmates(Color::White)).unwrap(),
+            r#"{"Checkmates":"White"}"#
+        );
+        let rights: CastleRights = serde_json::from_str(r#""KingSide""#).unwrap();
+        assert_eq!(rights, CastleRights::KingSide);
+        let analysis = Analysis {
+            best_move: ChessMove::new(Square::G1, Square::F3),
+            score: 25,
+            pv: vec![ChessMove::new(Square::G1, Square::F3)],
+            depth: 3,
+        };
+        let json = serde_json::to_string(&analysis).unwrap();
+        assert_eq!(
+            json,
+            r#"{"best_move":"g1f3","score":25,"pv":["g1f3"],"depth":3}"#
+        );
+        assert_eq!(serde_json::from_str::<Analysis>(&json).unwrap(), analysis);
+    }
+
+    #[test]
+    fn game() {
+        let time_control = TimeControl::from_str("3+2").unwrap();
+        let mut chess = Chess::with_time_control(Board::default(), time_control);
+        chess.play(Square::E2, Square::E4);
+        chess.tick(Duration::from_millis(2500));
+        chess.offer_draw();
+
+        let json = serde_json::to_string(&chess).unwrap();
+        assert!(json.starts_with(r#""[Event "#));
+        assert_eq!(serde_json::from_str::<Chess>(&json).unwrap(), chess);
+
+        // The moves are replayed, a corrupted game is refused
+        let corrupted = json.replace("1. e4", "1. e5");
+        assert!(serde_json::from_str::<Chess>(&corrupted).is_err());
+        assert!(serde_json::from_str::<Chess>(r#"{"history":["8/8 w"],"moves":[]}"#).is_err());
+
+        let pgn = Pgn::from_chess(&chess);
+        let json = serde_json::to_string(&pgn).unwrap();
+        assert_eq!(serde_json::from_str::<Pgn>(&json).unwrap(), pgn);
+    }
+}
@@ -0,0 +1,1018 @@
+//! Host many games over WebSocket: the clients (browsers or bots) talk to the server in JSON.
+//!
+//! The [`GameServer`] holds the games and answers the [`ClientRequest`]s with
+//! [`ServerEvent`]s, without any I/O. [`serve`] runs it behind a WebSocket listener.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::net::{TcpListener, TcpStream};
+use std::str::FromStr;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tungstenite::{Message as WsMessage, WebSocket};
+
+use crate::{
+    Board, Chess, ChessMove, Color, Error, GameState, TimeControl, SERVER_CLOCK_SYNC_INTERVAL,
+    SERVER_TICK,
+};
+
+/// The identifier of a connected client.
+pub type ClientId = u64;
+
+/// The identifier of a game hosted by the [`GameServer`].
+pub type GameId = u64;
+
+/// Error sent back to a client when its [`ClientRequest`] is refused.
+#[derive(Error, Clone, PartialEq, Eq, Debug)]
+pub enum ServerError {
+    /// The request is not valid JSON or not a known request.
+    #[error("Invalid request: {}", reason)]
+    InvalidRequest { reason: String },
+
+    /// The time control of the new game is invalid.
+    #[error(transparent)]
+    InvalidTimeControl(#[from] Error),
+
+    /// No game has this identifier.
+    #[error("The game {} doesn't exist", game)]
+    UnknownGame { game: GameId },
+
+    /// Both players of the game are already connected.
+    #[error("The game {} is full", game)]
+    GameFull { game: GameId },
+
+    /// The client already plays the game (e.g. its creator joins it).
+    #[error("You already play the game {}", game)]
+    AlreadyPlaying { game: GameId },
+
+    /// The client doesn't play the game.
+    #[error("You don't play the game {}", game)]
+    NotAPlayer { game: GameId },
+
+    /// The other player hasn't joined the game yet.
+    #[error("The game {} waits for the opponent", game)]
+    WaitingForOpponent { game: GameId },
+
+    /// The game is over, nothing can be played anymore.
+    #[error("The game {} is over", game)]
+    GameOver { game: GameId },
+
+    /// The client played when it is the turn of the opponent.
+    #[error("It is not your turn")]
+    NotYourTurn,
+
+    /// The move is not legal on the board of the game.
+    #[error("Illegal move: {}", uci)]
+    IllegalMove { uci: ChessMove },
+
+    /// The client accepted a draw that the opponent didn't offer.
+    #[error("The opponent didn't offer a draw")]
+    NoDrawOffer,
+}
+
+/// A request of a client, sent as a JSON object with a `type` field.
+///
+/// | Request                                              | Meaning                               |
+/// |------------------------------------------------------|---------------------------------------|
+/// | `{"type":"create","time_control":"5+3","color":"White"}` | Create a game (both fields are optional) |
+/// | `{"type":"join","game":1}`                           | Play the free color of a game         |
+/// | `{"type":"watch","game":1}`                          | Follow a game as a spectator          |
+/// | `{"type":"list"}`                                    | List the games                        |
+/// | `{"type":"move","game":1,"uci":"e2e4"}`              | Play a move in UCI notation           |
+/// | `{"type":"offer_draw","game":1}`                     | Offer a draw to the opponent          |
+/// | `{"type":"accept_draw","game":1}`                    | Accept the draw offer of the opponent |
+/// | `{"type":"resign","game":1}`                         | Resign the game                       |
+///
+/// # Examples
+///
+/// ```
+/// use chess::{ChessMove, ClientRequest, Square};
+///
+/// let request: ClientRequest = serde_json::from_str(r#"{"type":"move","game":1,"uci":"e2e4"}"#).unwrap();
+/// assert_eq!(request, ClientRequest::Move { game: 1, uci: ChessMove::new(Square::E2, Square::E4) });
+/// ```
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientRequest {
+    Create {
+        /// `<minutes>+<seconds>`, no clock by default.
+        #[serde(default)]
+        time_control: Option<String>,
+        /// The color of the creator, white by default.
+        #[serde(default)]
+        color: Option<Color>,
+    },
+    Join {
+        game: GameId,
+    },
+    Watch {
+        game: GameId,
+    },
+    List,
+    Move {
+        game: GameId,
+        uci: ChessMove,
+    },
+    OfferDraw {
+        game: GameId,
+    },
+    AcceptDraw {
+        game: GameId,
+    },
+    Resign {
+        game: GameId,
+    },
+}
+
+/// The remaining time of the players, in milliseconds.
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Debug)]
+pub struct ClockTimes {
+    pub white: u64,
+    pub black: u64,
+}
+
+/// A game in the answer to [`ClientRequest::List`].
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct GameSummary {
+    pub game: GameId,
+    /// The colors without a player.
+    pub free: Vec<Color>,
+    pub state: GameState,
+}
+
+/// An event sent by the server, as a JSON object with a `type` field.
+///
+/// The players and the spectators of a game receive its `player_joined`, `player_left`,
+/// `move`, `clock`, `draw_offered` and `game_over` events. The `clock` event is sent every
+/// [`SERVER_CLOCK_SYNC_INTERVAL`] while a game with a clock is played.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerEvent {
+    /// The game is created, the client plays `color`.
+    Created { game: GameId, color: Color },
+    /// The client plays `color` in the game (answer to `join`).
+    Joined { game: GameId, color: Color },
+    /// The whole game, sent to a client that joins or watches it.
+    State {
+        game: GameId,
+        fen: Board,
+        moves: Vec<ChessMove>,
+        state: GameState,
+        clock: Option<ClockTimes>,
+        draw_offer: Option<Color>,
+    },
+    /// The games of the server (answer to `list`).
+    Games { games: Vec<GameSummary> },
+    /// A player is connected to `color`.
+    PlayerJoined { game: GameId, color: Color },
+    /// The player of `color` is disconnected, another client can join the game.
+    PlayerLeft { game: GameId, color: Color },
+    /// A move is played, `fen` is the new position.
+    Move {
+        game: GameId,
+        uci: ChessMove,
+        fen: Board,
+        clock: Option<ClockTimes>,
+    },
+    /// The remaining time of the players.
+    Clock { game: GameId, clock: ClockTimes },
+    /// The player of `by` offers a draw.
+    DrawOffered { game: GameId, by: Color },
+    /// The game is over.
+    GameOver { game: GameId, state: GameState },
+    /// The last request is refused.
+    Error { message: String },
+}
+
+impl From<ServerError> for ServerEvent {
+    fn from(error: ServerError) -> Self {
+        ServerEvent::Error {
+            message: error.to_string(),
+        }
+    }
+}
+
+/// A game hosted by the [`GameServer`].
+#[derive(Clone, Debug)]
+struct Game {
+    chess: Chess,
+    /// The client playing each color (indexed by [`Color::to_index`]).
+    players: [Option<ClientId>; 2],
+    spectators: Vec<ClientId>,
+    draw_offer: Option<Color>,
+}
+
+impl Game {
+    /// The players and the spectators.
+    fn audience(&self) -> impl Iterator<Item = ClientId> + '_ {
+        self.players
+            .iter()
+            .flatten()
+            .chain(&self.spectators)
+            .copied()
+    }
+
+    fn color_of(&self, client: ClientId) -> Option<Color> {
+        [Color::White, Color::Black]
+            .into_iter()
+            .find(|color| self.players[color.to_index()] == Some(client))
+    }
+
+    fn is_full(&self) -> bool {
+        self.players.iter().all(Option::is_some)
+    }
+
+    fn clock(&self) -> Option<ClockTimes> {
+        self.chess.clock.map(|clock| ClockTimes {
+            white: clock.remaining(Color::White).as_millis() as u64,
+            black: clock.remaining(Color::Black).as_millis() as u64,
+        })
+    }
+}
+
+/// The games of the server, updated by the requests of the clients.
+///
+/// It doesn't do any I/O: every method returns the events to send, with their recipient.
+/// The server is authoritative, a move is played only if it is legal
+/// (see [`Board::is_legal`]) and it is the turn of the client.
+///
+/// # Examples
+///
+/// ```
+/// use chess::{ChessMove, ClientRequest, Color, GameServer, ServerEvent, Square};
+///
+/// let mut server = GameServer::default();
+/// let create = ClientRequest::Create { time_control: None, color: None };
+/// assert_eq!(server.handle(1, create)[0], (1, ServerEvent::Created { game: 1, color: Color::White }));
+/// server.handle(2, ClientRequest::Join { game: 1 });
+///
+/// let e4 = ChessMove::new(Square::E2, Square::E4);
+/// let events = server.handle(1, ClientRequest::Move { game: 1, uci: e4 });
+/// assert_eq!(events.len(), 2); // Sent to both players
+/// ```
+#[derive(Clone, Debug)]
+pub struct GameServer {
+    games: BTreeMap<GameId, Game>,
+    next_game: GameId,
+}
+
+impl Default for GameServer {
+    fn default() -> Self {
+        GameServer {
+            games: BTreeMap::new(),
+            next_game: 1,
+        }
+    }
+}
+
+impl GameServer {
+    /// Get the game hosted with this identifier (if exist).
+    pub fn game(&self, game: GameId) -> Option<&Chess> {
+        self.games.get(&game).map(|game| &game.chess)
+    }
+
+    /// Get the number of hosted games.
+    pub fn len(&self) -> usize {
+        self.games.len()
+    }
+
+    /// Verify if no game is hosted.
+    pub fn is_empty(&self) -> bool {
+        self.games.is_empty()
+    }
+
+    /// Answer a request of a client, a refused request is answered by an
+    /// [`Error`][ServerEvent::Error] event.
+    pub fn handle(
+        &mut self,
+        client: ClientId,
+        request: ClientRequest,
+    ) -> Vec<(ClientId, ServerEvent)> {
+        let mut events = vec![];
+        if let Err(error) = self.apply(client, request, &mut events) {
+            events.push((client, error.into()));
+        }
+        events
+    }
+
+    /// Remove a client from its games, its colors can be joined by another client.
+    ///
+    /// The games without any client are removed.
+    pub fn disconnect(&mut self, client: ClientId) -> Vec<(ClientId, ServerEvent)> {
+        let mut events = vec![];
+        self.games.retain(|&id, game| {
+            game.spectators.retain(|&spectator| spectator != client);
+            if let Some(color) = game.color_of(client) {
+                game.players[color.to_index()] = None;
+                for recipient in game.audience() {
+                    events.push((recipient, ServerEvent::PlayerLeft { game: id, color }));
+                }
+            }
+            game.audience().next().is_some()
+        });
+        events
+    }
+
+    /// Let the time pass in the games where both players are connected.
+    pub fn tick(&mut self, delta: Duration) -> Vec<(ClientId, ServerEvent)> {
+        let mut events = vec![];
+        for (&id, game) in self.games.iter_mut() {
+            if !game.is_full() || game.chess.state.is_finish() {
+                continue;
+            }
+            game.chess.tick(delta);
+            let state = game.chess.state;
+            if state.is_finish() {
+                Self::broadcast(id, game, &mut events, |game| ServerEvent::GameOver {
+                    game,
+                    state,
+                });
+            }
+        }
+        events
+    }
+
+    /// The remaining time of every game with a clock in progress, sent to its clients.
+    pub fn clocks(&self) -> Vec<(ClientId, ServerEvent)> {
+        let mut events = vec![];
+        for (&id, game) in &self.games {
+            if !game.is_full() || game.chess.state.is_finish() {
+                continue;
+            }
+            if let Some(clock) = game.clock() {
+                Self::broadcast(id, game, &mut events, |game| ServerEvent::Clock {
+                    game,
+                    clock,
+                });
+            }
+        }
+        events
+    }
+
+    /// Send an event to the players and the spectators of a game.
+    fn broadcast(
+        id: GameId,
+        game: &Game,
+        events: &mut Vec<(ClientId, ServerEvent)>,
+        event: impl Fn(GameId) -> ServerEvent,
+    ) {
+        for recipient in game.audience() {
+            events.push((recipient, event(id)));
+        }
+    }
+
+    fn apply(
+        &mut self,
+        client: ClientId,
+        request: ClientRequest,
+        events: &mut Vec<(ClientId, ServerEvent)>,
+    ) -> Result<(), ServerError> {
+        match request {
+            ClientRequest::Create {
+                time_control,
+                color,
+            } => {
+                let chess = match time_control {
+                    Some(time_control) => Chess::with_time_control(
+                        Board::default(),
+                        TimeControl::from_str(&time_control)?,
+                    ),
+                    None => Chess::default(),
+                };
+                let color = color.unwrap_or(Color::White);
+                let mut players = [None; 2];
+                players[color.to_index()] = Some(client);
+                let id = self.next_game;
+                self.next_game += 1;
+                self.games.insert(
+                    id,
+                    Game {
+                        chess,
+                        players,
+                        spectators: vec![],
+                        draw_offer: None,
+                    },
+                );
+                events.push((client, ServerEvent::Created { game: id, color }));
+            }
+            ClientRequest::Join { game: id } => {
+                let game = self.get_mut(id)?;
+                if game.color_of(client).is_some() {
+                    return Err(ServerError::AlreadyPlaying { game: id });
+                }
+                let color = [Color::White, Color::Black]
+                    .into_iter()
+                    .find(|color| game.players[color.to_index()].is_none())
+                    .ok_or(ServerError::GameFull { game: id })?;
+                game.players[color.to_index()] = Some(client);
+                events.push((client, ServerEvent::Joined { game: id, color }));
+                events.push((client, Self::state(id, game)));
+                for recipient in game.audience().filter(|&recipient| recipient != client) {
+                    events.push((recipient, ServerEvent::PlayerJoined { game: id, color }));
+                }
+            }
+            ClientRequest::Watch { game: id } => {
+                let game = self.get_mut(id)?;
+                if !game.spectators.contains(&client) {
+                    game.spectators.push(client);
+                }
+                events.push((client, Self::state(id, game)));
+            }
+            ClientRequest::List => {
+                let games = self
+                    .games
+                    .iter()
+                    .map(|(&id, game)| GameSummary {
+                        game: id,
+                        free: [Color::White, Color::Black]
+                            .into_iter()
+                            .filter(|color| game.players[color.to_index()].is_none())
+                            .collect(),
+                        state: game.chess.state,
+                    })
+                    .collect();
+                events.push((client, ServerEvent::Games { games }));
+            }
+            ClientRequest::Move { game: id, uci } => {
+                let (game, color) = self.get_playing(id, client)?;
+                if color != game.chess.board.side_to_move() {
+                    return Err(ServerError::NotYourTurn);
+                }
+                if !game.chess.board.is_legal(uci) {
+                    return Err(ServerError::IllegalMove { uci });
+                }
+                game.chess.play(uci.from, uci.to);
+                game.draw_offer = None;
+                let (fen, clock) = (game.chess.board, game.clock());
+                Self::broadcast(id, game, events, |game| ServerEvent::Move {
+                    game,
+                    uci,
+                    fen,
+                    clock,
+                });
+                if game.chess.state.is_finish() {
+                    let state = game.chess.state;
+                    Self::broadcast(id, game, events, |game| ServerEvent::GameOver {
+                        game,
+                        state,
+                    });
+                }
+            }
+            ClientRequest::OfferDraw { game: id } => {
+                let (game, color) = self.get_playing(id, client)?;
+                game.draw_offer = Some(color);
+                Self::broadcast(id, game, events, |game| ServerEvent::DrawOffered {
+                    game,
+                    by: color,
+                });
+            }
+            ClientRequest::AcceptDraw { game: id } => {
+                let (game, color) = self.get_playing(id, client)?;
+                if game.draw_offer != Some(!color) {
+                    return Err(ServerError::NoDrawOffer);
+                }
+                game.chess.accept_draw();
+                Self::broadcast(id, game, events, |game| ServerEvent::GameOver {
+                    game,
+                    state: GameState::DrawAccepted,
+                });
+            }
+            ClientRequest::Resign { game: id } => {
+                let (game, color) = self.get_playing(id, client)?;
+                game.chess.resign(color);
+                Self::broadcast(id, game, events, |game| ServerEvent::GameOver {
+                    game,
+                    state: GameState::Resigns(color),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn get_mut(&mut self, id: GameId) -> Result<&mut Game, ServerError> {
+        self.games
+            .get_mut(&id)
+            .ok_or(ServerError::UnknownGame { game: id })
+    }
+
+    /// Get a game in progress played by the client, with the color of the client.
+    fn get_playing(
+        &mut self,
+        id: GameId,
+        client: ClientId,
+    ) -> Result<(&mut Game, Color), ServerError> {
+        let game = self.get_mut(id)?;
+        let color = game
+            .color_of(client)
+            .ok_or(ServerError::NotAPlayer { game: id })?;
+        if game.chess.state.is_finish() {
+            return Err(ServerError::GameOver { game: id });
+        }
+        if !game.is_full() {
+            return Err(ServerError::WaitingForOpponent { game: id });
+        }
+        Ok((game, color))
+    }
+
+    fn state(id: GameId, game: &Game) -> ServerEvent {
+        ServerEvent::State {
+            game: id,
+            fen: game.chess.board,
+            moves: game.chess.moves.clone(),
+            state: game.chess.state,
+            clock: game.clock(),
+            draw_offer: game.draw_offer,
+        }
+    }
+}
+
+/// What a connection thread tells the thread of the [`GameServer`].
+enum Input {
+    Connected(ClientId, mpsc::Sender<ServerEvent>),
+    Request(ClientId, ClientRequest),
+    Disconnected(ClientId),
+}
+
+/// Run a [`GameServer`] on a listener, each client is a WebSocket connection.
+///
+/// The requests and the events are JSON text messages. A connection that can't be accepted
+/// is only logged, the server goes on with the other ones.
+///
+/// ```no_run
+/// use std::net::TcpListener;
+///
+/// let listener = TcpListener::bind("0.0.0.0:8080").unwrap();
+/// chess::serve(listener).unwrap();
+/// ```
+pub fn serve(listener: TcpListener) -> io::Result<()> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || run_server(receiver));
+    for (client, stream) in (1..).zip(listener.incoming()) {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(error) => {
+                log::warn!("Can't accept a connection: {error}");
+                // Give the system time to free resources (e.g. too many open files)
+                thread::sleep(SERVER_TICK);
+                continue;
+            }
+        };
+        let sender = sender.clone();
+        thread::spawn(move || {
+            if let Err(error) = run_connection(client, stream, &sender) {
+                log::info!("Client {client}: {error}");
+            }
+            let _ = sender.send(Input::Disconnected(client));
+        });
+    }
+    Ok(())
+}
+
+/// Update the [`GameServer`] with the inputs of the connections, and send them the events.
+fn run_server(receiver: mpsc::Receiver<Input>) {
+    let mut server = GameServer::default();
+    let mut clients: HashMap<ClientId, mpsc::Sender<ServerEvent>> = HashMap::new();
+    let mut last_tick = Instant::now();
+    let mut last_sync = Instant::now();
+    loop {
+        let events = match receiver.recv_timeout(SERVER_TICK) {
+            Ok(Input::Connected(client, sender)) => {
+                log::info!("Client {client} connected");
+                clients.insert(client, sender);
+                vec![]
+            }
+            Ok(Input::Request(client, request)) => {
+                log::debug!("Client {client}: {request:?}");
+                server.handle(client, request)
+            }
+            Ok(Input::Disconnected(client)) => {
+                log::info!("Client {client} disconnected");
+                clients.remove(&client);
+                server.disconnect(client)
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => vec![],
+            Err(mpsc::RecvTimeoutError::Disconnected) => return,
+        };
+        let mut events = events;
+        events.extend(server.tick(last_tick.elapsed()));
+        last_tick = Instant::now();
+        if last_sync.elapsed() >= SERVER_CLOCK_SYNC_INTERVAL {
+            events.extend(server.clocks());
+            last_sync = Instant::now();
+        }
+        for (client, event) in events {
+            if let Some(sender) = clients.get(&client) {
+                let _ = sender.send(event);
+            }
+        }
+    }
+}
+
+/// Forward the requests of a client to the server thread, and the events to the client.
+fn run_connection(
+    client: ClientId,
+    stream: TcpStream,
+    server: &mpsc::Sender<Input>,
+) -> Result<(), Box<tungstenite::Error>> {
+    let mut socket = tungstenite::accept(stream).map_err(|error| match error {
+        tungstenite::HandshakeError::Failure(error) => error,
+        tungstenite::HandshakeError::Interrupted(_) => {
+            tungstenite::Error::Io(io::ErrorKind::WouldBlock.into())
+        }
+    })?;
+    // The reads return regularly to send the events
+    socket
+        .get_ref()
+        .set_read_timeout(Some(SERVER_TICK))
+        .map_err(tungstenite::Error::Io)?;
+    let (sender, events) = mpsc::channel();
+    if server.send(Input::Connected(client, sender)).is_err() {
+        return Ok(());
+    }
+    loop {
+        match socket.read() {
+            Ok(WsMessage::Text(text)) => match serde_json::from_str(&text) {
+                Ok(request) => {
+                    if server.send(Input::Request(client, request)).is_err() {
+                        return Ok(());
+                    }
+                }
+                Err(error) => {
+                    let error = ServerError::InvalidRequest {
+                        reason: error.to_string(),
+                    };
+                    send(&mut socket, &error.into())?;
+                }
+            },
+            Ok(WsMessage::Close(_)) => return Ok(()),
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(error))
+                if matches!(
+                    error.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) => {}
+            Err(tungstenite::Error::ConnectionClosed) => return Ok(()),
+            Err(error) => return Err(error.into()),
+        }
+        while let Ok(event) = events.try_recv() {
+            send(&mut socket, &event)?;
+        }
+    }
+}
+
+fn send(
+    socket: &mut WebSocket<TcpStream>,
+    event: &ServerEvent,
+) -> Result<(), Box<tungstenite::Error>> {
+    let json = serde_json::to_string(event).expect("an event can be serialized");
+    Ok(socket.send(WsMessage::Text(json))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Square;
+
+    /// Clients talking to a [`GameServer`] in-process, they keep the events they receive.
+    #[derive(Default)]
+    struct Harness {
+        server: GameServer,
+        inboxes: HashMap<ClientId, Vec<ServerEvent>>,
+    }
+
+    impl Harness {
+        fn send(&mut self, client: ClientId, request: ClientRequest) {
+            let events = self.server.handle(client, request);
+            self.deliver(events);
+        }
+
+        fn disconnect(&mut self, client: ClientId) {
+            let events = self.server.disconnect(client);
+            self.deliver(events);
+        }
+
+        fn tick(&mut self, delta: Duration) {
+            let events = self.server.tick(delta);
+            self.deliver(events);
+        }
+
+        fn deliver(&mut self, events: Vec<(ClientId, ServerEvent)>) {
+            for (client, event) in events {
+                self.inboxes.entry(client).or_default().push(event);
+            }
+        }
+
+        /// Take the events received by a client.
+        fn received(&mut self, client: ClientId) -> Vec<ServerEvent> {
+            self.inboxes.remove(&client).unwrap_or_default()
+        }
+
+        fn play(&mut self, client: ClientId, game: GameId, uci: &str) {
+            let uci = ChessMove::from_str(uci).unwrap();
+            self.send(client, ClientRequest::Move { game, uci });
+        }
+
+        fn create(&mut self, client: ClientId, time_control: Option<&str>) -> GameId {
+            self.send(
+                client,
+                ClientRequest::Create {
+                    time_control: time_control.map(String::from),
+                    color: None,
+                },
+            );
+            match self.received(client)[..] {
+                [ServerEvent::Created { game, .. }] => game,
+                ref events => panic!("unexpected events: {events:?}"),
+            }
+        }
+    }
+
+    fn is_error(events: &[ServerEvent]) -> bool {
+        matches!(events, [ServerEvent::Error { .. }])
+    }
+
+    #[test]
+    fn requests() {
+        let json = r#"{"type":"create","time_control":"5+3","color":"Black"}"#;
+        assert_eq!(
+            serde_json::from_str::<ClientRequest>(json).unwrap(),
+            ClientRequest::Create {
+                time_control: Some("5+3".to_string()),
+                color: Some(Color::Black)
+            }
+        );
+        assert_eq!(
+            serde_json::from_str::<ClientRequest>(r#"{"type":"create"}"#).unwrap(),
+            ClientRequest::Create {
+                time_control: None,
+                color: None
+            }
+        );
+        assert_eq!(
+            serde_json::to_string(&ClientRequest::AcceptDraw { game: 2 }).unwrap(),
+            r#"{"type":"accept_draw","game":2}"#
+        );
+        assert!(serde_json::from_str::<ClientRequest>(r#"{"type":"castle"}"#).is_err());
+        let event = ServerEvent::Clock {
+            game: 1,
+            clock: ClockTimes {
+                white: 1000,
+                black: 2500,
+            },
+        };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"type":"clock","game":1,"clock":{"white":1000,"black":2500}}"#
+        );
+    }
+
+    #[test]
+    fn play_a_game() {
+        let mut harness = Harness::default();
+        let game = harness.create(1, None);
+        harness.play(1, game, "e2e4");
+        assert!(matches!(
+            harness.received(1)[..],
+            [ServerEvent::Error { ref message }] if message.contains("waits for the opponent")
+        ));
+
+        harness.send(2, ClientRequest::Join { game });
+        let events = harness.received(2);
+        assert_eq!(
+            events[0],
+            ServerEvent::Joined {
+                game,
+                color: Color::Black
+            }
+        );
+        assert!(matches!(events[1], ServerEvent::State { ref moves, .. } if moves.is_empty()));
+        assert_eq!(
+            harness.received(1),
+            [ServerEvent::PlayerJoined {
+                game,
+                color: Color::Black
+            }]
+        );
+        harness.send(3, ClientRequest::Join { game });
+        assert!(is_error(&harness.received(3)));
+
+        // The fool's mate, with refused moves
+        harness.play(2, game, "e7e5");
+        assert!(is_error(&harness.received(2)));
+        harness.play(1, game, "e2e5");
+        assert!(is_error(&harness.received(1)));
+        for (client, uci) in [(1, "f2f3"), (2, "e7e5"), (1, "g2g4"), (2, "d8h4")] {
+            harness.play(client, game, uci);
+        }
+        let events = harness.received(1);
+        assert_eq!(events.len(), 5);
+        assert_eq!(
+            events[4],
+            ServerEvent::GameOver {
+                game,
+                state: GameState::Checkmates(Color::White)
+            }
+        );
+        assert_eq!(harness.received(2), events);
+        harness.play(1, game, "e2e4");
+        assert!(is_error(&harness.received(1)));
+    }
+
+    #[test]
+    fn join_own_game() {
+        let mut harness = Harness::default();
+        let game = harness.create(1, None);
+        harness.send(1, ClientRequest::Join { game });
+        assert!(matches!(
+            harness.received(1)[..],
+            [ServerEvent::Error { ref message }] if message.contains("already play")
+        ));
+
+        // The seat of the opponent is still free
+        harness.send(2, ClientRequest::Join { game });
+        assert_eq!(
+            harness.received(2)[0],
+            ServerEvent::Joined {
+                game,
+                color: Color::Black
+            }
+        );
+        harness.play(1, game, "e2e4");
+        harness.play(2, game, "e7e5");
+        let events = harness.received(2);
+        assert!(events.len() > 1);
+        assert!(!events
+            .iter()
+            .any(|event| matches!(event, ServerEvent::Error { .. })));
+    }
+
+    #[test]
+    fn draw_and_resign() {
+        let mut harness = Harness::default();
+        let game = harness.create(1, None);
+        harness.send(2, ClientRequest::Join { game });
+        harness.received(2);
+        harness.send(2, ClientRequest::AcceptDraw { game });
+        assert!(is_error(&harness.received(2)));
+
+        harness.send(1, ClientRequest::OfferDraw { game });
+        harness.send(1, ClientRequest::AcceptDraw { game });
+        assert!(matches!(
+            harness.received(1).last(),
+            Some(ServerEvent::Error { .. })
+        ));
+        harness.send(2, ClientRequest::AcceptDraw { game });
+        assert_eq!(
+            harness.server.game(game).unwrap().state,
+            GameState::DrawAccepted
+        );
+
+        let game = harness.create(3, None);
+        harness.send(4, ClientRequest::Join { game });
+        harness.send(4, ClientRequest::Resign { game });
+        assert!(harness.received(3).contains(&ServerEvent::GameOver {
+            game,
+            state: GameState::Resigns(Color::Black)
+        }));
+    }
+
+    #[test]
+    fn spectators_and_many_games() {
+        let mut harness = Harness::default();
+        let first = harness.create(1, None);
+        let second = harness.create(2, Some("1+0"));
+        assert_ne!(first, second);
+        harness.send(3, ClientRequest::Join { game: second });
+        harness.send(4, ClientRequest::Watch { game: first });
+        harness.received(3);
+        harness.received(4);
+
+        harness.play(3, second, "e2e4");
+        assert!(is_error(&harness.received(3)));
+        harness.play(2, second, "e2e4");
+        assert!(harness.received(4).is_empty());
+        assert_eq!(harness.server.game(first).unwrap().moves, []);
+
+        harness.send(5, ClientRequest::Join { game: first });
+        harness.play(1, first, "d2d4");
+        let uci = ChessMove::new(Square::D2, Square::D4);
+        assert!(matches!(
+            harness.received(4)[..],
+            [ServerEvent::PlayerJoined { .. }, ServerEvent::Move { uci: m, .. }] if m == uci
+        ));
+
+        harness.send(6, ClientRequest::List);
+        match &harness.received(6)[..] {
+            [ServerEvent::Games { games }] => {
+                assert_eq!(games.len(), 2);
+                assert!(games.iter().all(|game| game.free.is_empty()));
+            }
+            events => panic!("unexpected events: {events:?}"),
+        }
+    }
+
+    #[test]
+    fn clock_and_disconnection() {
+        let mut harness = Harness::default();
+        let game = harness.create(1, Some("1+0"));
+        harness.send(2, ClientRequest::Join { game });
+        harness.play(1, game, "e2e4");
+        harness.received(1);
+
+        // A player leaves, the clock waits for a new one
+        harness.disconnect(2);
+        assert_eq!(
+            harness.received(1),
+            [ServerEvent::PlayerLeft {
+                game,
+                color: Color::Black
+            }]
+        );
+        harness.tick(Duration::from_secs(120));
+        assert_eq!(harness.server.game(game).unwrap().state, GameState::Ongoing);
+        harness.send(3, ClientRequest::Join { game });
+        let clock = ClockTimes {
+            white: 60_000,
+            black: 60_000,
+        };
+        assert!(matches!(
+            harness.received(3)[1],
+            ServerEvent::State { clock: Some(c), ref moves, .. } if c == clock && moves.len() == 1
+        ));
+        assert_eq!(harness.server.clocks().len(), 2);
+
+        harness.tick(Duration::from_secs(61));
+        assert!(harness.received(1).contains(&ServerEvent::GameOver {
+            game,
+            state: GameState::Timeout(Color::Black)
+        }));
+
+        // The games without clients are removed
+        harness.disconnect(1);
+        harness.disconnect(3);
+        assert!(harness.server.is_empty());
+    }
+
+    #[test]
+    fn websocket() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        thread::spawn(move || serve(listener));
+
+        let connect = || {
+            let (mut socket, _) = tungstenite::connect(url.as_str()).unwrap();
+            if let tungstenite::stream::MaybeTlsStream::Plain(stream) = socket.get_mut() {
+                stream
+                    .set_read_timeout(Some(Duration::from_secs(5)))
+                    .unwrap();
+            }
+            socket
+        };
+        let request = |socket: &mut WebSocket<_>, json: &str| {
+            socket.send(WsMessage::Text(json.to_string())).unwrap();
+        };
+        // The clock events can be received at any time
+        let event = |socket: &mut WebSocket<_>| loop {
+            if let WsMessage::Text(text) = socket.read().unwrap() {
+                match serde_json::from_str::<ServerEvent>(&text).unwrap() {
+                    ServerEvent::Clock { .. } => {}
+                    event => return event,
+                }
+            }
+        };
+
+        let mut white = connect();
+        let mut black = connect();
+        request(&mut white, r#"{"type":"create","time_control":"3+2"}"#);
+        let ServerEvent::Created { game, .. } = event(&mut white) else {
+            panic!("the game is not created");
+        };
+        request(&mut black, &format!(r#"{{"type":"join","game":{game}}}"#));
+        assert_eq!(
+            event(&mut black),
+            ServerEvent::Joined {
+                game,
+                color: Color::Black
+            }
+        );
+        assert!(matches!(
+            event(&mut white),
+            ServerEvent::PlayerJoined { .. }
+        ));
+        request(
+            &mut white,
+            &format!(r#"{{"type":"move","game":{game},"uci":"e2e4"}}"#),
+        );
+        assert!(matches!(event(&mut black), ServerEvent::State { .. }));
+        assert!(matches!(
+            event(&mut black),
+            ServerEvent::Move { clock: Some(_), .. }
+        ));
+        request(&mut black, "not json");
+        assert!(matches!(event(&mut black), ServerEvent::Error { .. }));
+    }
+}
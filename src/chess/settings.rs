@@ -0,0 +1,483 @@
+//! Describe the user settings of the game (GUI), persisted between runs.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    Level, Player, TimeControl, ANALYSIS_DEPTH, ANALYSIS_LINES, HINTS_PER_GAME, SETTINGS_FILE_NAME,
+};
+
+/// The maximum number of lines shown in the analysis mode.
+pub const MAX_ANALYSIS_LINES: usize = 5;
+
+/// The time controls proposed in the settings screen.
+pub const TIME_CONTROL_PRESETS: [&str; 8] =
+    ["none", "1+0", "3+0", "3+2", "5+0", "5+3", "10+0", "15+10"];
+
+/// The numbers of hints per game proposed in the settings screen.
+pub const HINTS_PRESETS: [usize; 5] = [0, 1, 3, 5, 10];
+
+/// Error that can happen when loading or saving the [`Settings`].
+#[derive(Error, Debug)]
+pub enum SettingsError {
+    /// The settings file can't be read or written.
+    #[error("Can't access the settings file {}: {}", path.display(), source)]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    /// The settings file is not a valid TOML file.
+    #[error("Invalid settings file {}: {}", path.display(), source)]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+
+    /// The settings can't be written in TOML.
+    #[error("Can't serialize the settings: {}", source)]
+    Serialize { source: toml::ser::Error },
+}
+
+/// An entry of the settings screen.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum SettingsEntry {
+    Theme,
+    BoardFlipped,
+    TimeControl,
+    LegalMoves,
+    LastMove,
+    Check,
+    Selected,
+    Hovered,
+    Pinned,
+    AnalysisLines,
+    Hints,
+    EngineLevel,
+}
+
+/// Number of entries in the settings screen.
+pub const NUM_SETTINGS_ENTRIES: usize = 12;
+
+/// All the entries of the settings screen, in the order they are displayed.
+pub const ALL_SETTINGS_ENTRIES: [SettingsEntry; NUM_SETTINGS_ENTRIES] = [
+    SettingsEntry::Theme,
+    SettingsEntry::BoardFlipped,
+    SettingsEntry::TimeControl,
+    SettingsEntry::LegalMoves,
+    SettingsEntry::LastMove,
+    SettingsEntry::Check,
+    SettingsEntry::Selected,
+    SettingsEntry::Hovered,
+    SettingsEntry::Pinned,
+    SettingsEntry::AnalysisLines,
+    SettingsEntry::Hints,
+    SettingsEntry::EngineLevel,
+];
+
+impl SettingsEntry {
+    /// Get the label of the entry.
+    pub fn label(&self) -> &'static str {
+        match self {
+            SettingsEntry::Theme => "Theme",
+            SettingsEntry::BoardFlipped => "Black at the bottom",
+            SettingsEntry::TimeControl => "Time control",
+            SettingsEntry::LegalMoves => "Show legal moves",
+            SettingsEntry::LastMove => "Show last move",
+            SettingsEntry::Check => "Show check",
+            SettingsEntry::Selected => "Show selected piece",
+            SettingsEntry::Hovered => "Show hovered move",
+            SettingsEntry::Pinned => "Show pinned pieces",
+            SettingsEntry::AnalysisLines => "Analysis lines",
+            SettingsEntry::Hints => "Hints per game",
+            SettingsEntry::EngineLevel => "Engine level",
+        }
+    }
+}
+
+/// Which squares are highlighted on the board.
+///
+/// The colors are defined by the [`Theme`][crate::Theme], a theme without a color for
+/// a highlight never draws it.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Highlights {
+    /// The legal destinations of the selected piece.
+    pub legal_moves: bool,
+    /// The departure and arrival of the last move.
+    pub last_move: bool,
+    /// The king in check.
+    pub check: bool,
+    /// The selected piece.
+    pub selected: bool,
+    /// The legal destination under the mouse.
+    pub hovered: bool,
+    /// The pinned pieces.
+    pub pinned: bool,
+}
+
+impl Default for Highlights {
+    fn default() -> Self {
+        Highlights {
+            legal_moves: true,
+            last_move: true,
+            check: true,
+            selected: true,
+            hovered: true,
+            pinned: true,
+        }
+    }
+}
+
+/// The settings chosen by the user.
+///
+/// They are saved in a TOML file, every missing key takes its default value
+/// and unknown keys are ignored, so a settings file stays valid across versions.
+///
+/// # Examples
+///
+/// ```
+/// use chess::Settings;
+///
+/// let settings = Settings::from_toml("theme = \"Coral\"").unwrap();
+/// assert_eq!(settings.theme, "Coral");
+/// assert!(!settings.board_flipped);
+/// assert_eq!(settings.time_control(), None);
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// The name of the [`Theme`][crate::Theme] in use.
+    pub theme: String,
+    /// Show the board from the black side.
+    pub board_flipped: bool,
+    /// The [`TimeControl`] of the new games (`"none"` for no clock).
+    pub time_control: String,
+    /// The highlights drawn on the board.
+    pub highlights: Highlights,
+    /// The engine of the analysis mode, written as a [`Player`] (e.g. `"engine:5"` or the
+    /// path of an UCI engine).
+    pub analysis_engine: String,
+    /// The number of lines shown in the analysis mode (1 to [`MAX_ANALYSIS_LINES`]).
+    pub analysis_lines: usize,
+    /// The number of hints of the local player in a game.
+    pub hints_per_game: usize,
+    /// The [`Level`] of the built-in engine, it replaces the level of the players written
+    /// `level:<level>` when it changes.
+    pub engine_level: String,
+}
+
+impl Settings {
+    /// Get the default path of the settings file (in the user config directory).
+    ///
+    /// Return [`None`] if the platform has no config directory.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join(env!("CARGO_PKG_NAME")).join(SETTINGS_FILE_NAME))
+    }
+
+    /// Read the settings from a TOML string.
+    pub fn from_toml(content: &str) -> Result<Settings, toml::de::Error> {
+        toml::from_str(content)
+    }
+
+    /// Write the settings in a TOML string.
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string(self)
+    }
+
+    /// Load the settings from a file.
+    ///
+    /// A missing file is not an error, the default settings are returned.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Settings, SettingsError> {
+        let path = path.as_ref();
+        match fs::read_to_string(path) {
+            Ok(content) => Settings::from_toml(&content).map_err(|source| SettingsError::Parse {
+                path: path.to_path_buf(),
+                source,
+            }),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Settings::default()),
+            Err(source) => Err(SettingsError::Io {
+                path: path.to_path_buf(),
+                source,
+            }),
+        }
+    }
+
+    /// Save the settings in a file, the parent directories are created if needed.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SettingsError> {
+        let path = path.as_ref();
+        let io_error = |source| SettingsError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        let content = self
+            .to_toml()
+            .map_err(|source| SettingsError::Serialize { source })?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        fs::write(path, content).map_err(io_error)
+    }
+
+    /// Get the [`TimeControl`] of the new games.
+    ///
+    /// An invalid time control is logged and ignored (i.e. no clock).
+    pub fn time_control(&self) -> Option<TimeControl> {
+        if self.time_control == "none" {
+            return None;
+        }
+        TimeControl::from_str(&self.time_control)
+            .map_err(|error| log::warn!("{error}"))
+            .ok()
+    }
+
+    /// Get the engine of the analysis mode.
+    ///
+    /// An invalid engine is logged and replaced by the built-in engine.
+    pub fn analysis_engine(&self) -> Player {
+        Player::from_str(&self.analysis_engine)
+            .map_err(|error| log::warn!("{error}"))
+            .unwrap_or(Player::Engine(ANALYSIS_DEPTH))
+    }
+
+    /// Get the [`Level`] of the built-in engine.
+    ///
+    /// An invalid level is logged and replaced by the default level.
+    pub fn engine_level(&self) -> Level {
+        Level::from_str(&self.engine_level)
+            .map_err(|error| log::warn!("{error}"))
+            .unwrap_or_default()
+    }
+
+    /// Get the value of an entry of the settings screen.
+    pub fn value(&self, entry: SettingsEntry) -> String {
+        let on_off = |value: bool| if value { "On" } else { "Off" }.to_string();
+        match entry {
+            SettingsEntry::Theme => self.theme.clone(),
+            SettingsEntry::BoardFlipped => on_off(self.board_flipped),
+            SettingsEntry::TimeControl => self.time_control.clone(),
+            SettingsEntry::LegalMoves => on_off(self.highlights.legal_moves),
+            SettingsEntry::LastMove => on_off(self.highlights.last_move),
+            SettingsEntry::Check => on_off(self.highlights.check),
+            SettingsEntry::Selected => on_off(self.highlights.selected),
+            SettingsEntry::Hovered => on_off(self.highlights.hovered),
+            SettingsEntry::Pinned => on_off(self.highlights.pinned),
+            SettingsEntry::AnalysisLines => self.analysis_lines.to_string(),
+            SettingsEntry::Hints => self.hints_per_game.to_string(),
+            SettingsEntry::EngineLevel => self.engine_level.clone(),
+        }
+    }
+
+    /// Change the value of an entry of the settings screen (toggle or next value).
+    ///
+    /// The [`Theme`][SettingsEntry::Theme] depends on the themes available,
+    /// it is not changed here.
+    pub fn change(&mut self, entry: SettingsEntry) {
+        let highlights = &mut self.highlights;
+        match entry {
+            SettingsEntry::Theme => {}
+            SettingsEntry::BoardFlipped => self.board_flipped = !self.board_flipped,
+            SettingsEntry::TimeControl => self.next_time_control(),
+            SettingsEntry::LegalMoves => highlights.legal_moves = !highlights.legal_moves,
+            SettingsEntry::LastMove => highlights.last_move = !highlights.last_move,
+            SettingsEntry::Check => highlights.check = !highlights.check,
+            SettingsEntry::Selected => highlights.selected = !highlights.selected,
+            SettingsEntry::Hovered => highlights.hovered = !highlights.hovered,
+            SettingsEntry::Pinned => highlights.pinned = !highlights.pinned,
+            SettingsEntry::AnalysisLines => {
+                self.analysis_lines = self.analysis_lines % MAX_ANALYSIS_LINES + 1
+            }
+            SettingsEntry::Hints => self.next_hints(),
+            SettingsEntry::EngineLevel => {
+                self.engine_level = self.engine_level().next().to_string()
+            }
+        }
+    }
+
+    /// Use the next time control of the [`TIME_CONTROL_PRESETS`].
+    pub fn next_time_control(&mut self) {
+        let index = TIME_CONTROL_PRESETS
+            .iter()
+            .position(|&preset| preset == self.time_control)
+            .map_or(0, |index| (index + 1) % TIME_CONTROL_PRESETS.len());
+        self.time_control = TIME_CONTROL_PRESETS[index].to_string();
+    }
+
+    /// Use the next number of hints of the [`HINTS_PRESETS`].
+    pub fn next_hints(&mut self) {
+        let index = HINTS_PRESETS
+            .iter()
+            .position(|&preset| preset == self.hints_per_game)
+            .map_or(0, |index| (index + 1) % HINTS_PRESETS.len());
+        self.hints_per_game = HINTS_PRESETS[index];
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            theme: "Dust".to_string(),
+            board_flipped: false,
+            time_control: "none".to_string(),
+            highlights: Highlights::default(),
+            analysis_engine: format!("engine:{ANALYSIS_DEPTH}"),
+            analysis_lines: ANALYSIS_LINES,
+            hints_per_game: HINTS_PER_GAME,
+            engine_level: Level::default().to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn missing_keys() {
+        let settings = Settings::from_toml(
+            r#"
+            board_flipped = true
+
+            [highlights]
+            pinned = false
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            settings,
+            Settings {
+                board_flipped: true,
+                highlights: Highlights {
+                    pinned: false,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn unknown_keys() {
+        let settings = Settings::from_toml("volume = 11\ntime_control = \"3+2\"").unwrap();
+        assert_eq!(
+            settings.time_control(),
+            Some(TimeControl::new(
+                Duration::from_secs(180),
+                Duration::from_secs(2)
+            ))
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let settings = Settings {
+            theme: "Marine".to_string(),
+            board_flipped: true,
+            time_control: "5+3".to_string(),
+            highlights: Highlights {
+                hovered: false,
+                ..Default::default()
+            },
+            analysis_engine: "/usr/bin/stockfish".to_string(),
+            analysis_lines: 1,
+            hints_per_game: 0,
+            engine_level: "novice".to_string(),
+        };
+        let content = settings.to_toml().unwrap();
+        assert_eq!(Settings::from_toml(&content).unwrap(), settings);
+    }
+
+    #[test]
+    fn load_and_save() {
+        let dir = std::env::temp_dir().join(format!("rg-chess-settings-{}", std::process::id()));
+        let path = dir.join("nested").join(SETTINGS_FILE_NAME);
+        assert_eq!(Settings::load(&path).unwrap(), Settings::default());
+
+        let settings = Settings {
+            theme: "Coral".to_string(),
+            ..Default::default()
+        };
+        settings.save(&path).unwrap();
+        assert_eq!(Settings::load(&path).unwrap(), settings);
+
+        fs::write(&path, "theme = ").unwrap();
+        assert!(matches!(
+            Settings::load(&path),
+            Err(SettingsError::Parse { .. })
+        ));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn next_time_control() {
+        let mut settings = Settings::default();
+        settings.next_time_control();
+        assert_eq!(settings.time_control, "1+0");
+
+        settings.time_control = "15+10".to_string();
+        settings.next_time_control();
+        assert_eq!(settings.time_control, "none");
+
+        // A custom time control restarts the presets
+        settings.time_control = "7+7".to_string();
+        settings.next_time_control();
+        assert_eq!(settings.time_control, "none");
+    }
+
+    #[test]
+    fn change() {
+        let mut settings = Settings::default();
+        for entry in ALL_SETTINGS_ENTRIES {
+            settings.change(entry);
+        }
+        assert_eq!(settings.value(SettingsEntry::Theme), "Dust");
+        assert_eq!(settings.value(SettingsEntry::BoardFlipped), "On");
+        assert_eq!(settings.value(SettingsEntry::TimeControl), "1+0");
+        assert_eq!(settings.value(SettingsEntry::Pinned), "Off");
+        assert_eq!(settings.value(SettingsEntry::AnalysisLines), "4");
+        assert_eq!(settings.value(SettingsEntry::Hints), "5");
+        assert_eq!(settings.value(SettingsEntry::EngineLevel), "advanced");
+        assert!(!settings.highlights.legal_moves);
+
+        settings.analysis_lines = MAX_ANALYSIS_LINES;
+        settings.change(SettingsEntry::AnalysisLines);
+        assert_eq!(settings.analysis_lines, 1);
+
+        settings.hints_per_game = 10;
+        settings.change(SettingsEntry::Hints);
+        assert_eq!(settings.hints_per_game, 0);
+
+        settings.engine_level = "master".to_string();
+        assert_eq!(settings.engine_level(), Level::Intermediate);
+        settings.change(SettingsEntry::EngineLevel);
+        assert_eq!(settings.engine_level, "advanced");
+    }
+
+    #[test]
+    fn analysis_engine() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.analysis_engine(), Player::Engine(ANALYSIS_DEPTH));
+        settings.analysis_engine = "engine:0".to_string();
+        assert_eq!(settings.analysis_engine(), Player::Engine(ANALYSIS_DEPTH));
+        settings.analysis_engine = "./stockfish".to_string();
+        assert_eq!(
+            settings.analysis_engine(),
+            Player::Uci("./stockfish".into())
+        );
+    }
+
+    #[test]
+    fn invalid_time_control() {
+        let settings = Settings {
+            time_control: "fast".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(settings.time_control(), None);
+    }
+}
@@ -0,0 +1,198 @@
+//! Follow a game without playing it: a game hosted on the network or a PGN file being written.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::network::is_same_game;
+use crate::{Chess, Session, SPECTATOR_POLL_INTERVAL};
+
+/// Where the [`Spectator`] reads the game.
+#[derive(Debug)]
+enum Source {
+    /// A game hosted on the network (see [`Session::watch`]).
+    Network { session: Box<Session> },
+    /// A PGN file, read again when it is modified.
+    Pgn {
+        path: PathBuf,
+        modified: Option<SystemTime>,
+        last_poll: Option<Instant>,
+    },
+}
+
+/// A read-only view of a live game, optionally delayed (e.g. to prevent cheating when the
+/// game is shown to the public).
+///
+/// The game is [watched][Spectator::watch] on the network or [tailed][Spectator::tail] from
+/// a PGN file being written (e.g. the autosave of another `rg-chess`).
+/// Every change of the game is shown after the [delay][Spectator::set_delay].
+///
+/// # Examples
+///
+/// ```
+/// use chess::{Chess, Spectator, Square};
+///
+/// let path = std::env::temp_dir().join("rg-chess-doc-spectator.pgn");
+/// let mut chess = Chess::default();
+/// chess.play(Square::E2, Square::E4);
+/// chess.save(&path).unwrap();
+///
+/// let mut spectator = Spectator::tail(&path);
+/// assert_eq!(spectator.update(), Some(chess));
+/// assert_eq!(spectator.update(), None); // No change
+/// # std::fs::remove_file(path).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct Spectator {
+    source: Source,
+    delay: Duration,
+    /// The game updated by the host (for a network game).
+    received: Chess,
+    /// The last version of the game read from the source.
+    live: Option<Chess>,
+    /// The versions of the game waiting for the delay, with the time they were read.
+    delayed: VecDeque<(Instant, Chess)>,
+}
+
+impl Spectator {
+    /// Watch the game hosted on the given address (e.g. `192.168.1.10:7878`).
+    pub fn watch<S: Into<String>>(address: S) -> Self {
+        Spectator::new(Source::Network {
+            session: Box::new(Session::watch(address)),
+        })
+    }
+
+    /// Follow the game written in a PGN file, the file is read again when it is modified.
+    pub fn tail<P: AsRef<Path>>(path: P) -> Self {
+        Spectator::new(Source::Pgn {
+            path: path.as_ref().to_path_buf(),
+            modified: None,
+            last_poll: None,
+        })
+    }
+
+    fn new(source: Source) -> Self {
+        Spectator {
+            source,
+            delay: Duration::ZERO,
+            received: Chess::default(),
+            live: None,
+            delayed: VecDeque::new(),
+        }
+    }
+
+    /// Get the delay before a change of the game is shown.
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// Set the delay before a change of the game is shown (none by default).
+    pub fn set_delay(&mut self, delay: Duration) {
+        self.delay = delay;
+    }
+
+    /// Verify if the game is received: the host is connected or the file has been read.
+    pub fn is_connected(&self) -> bool {
+        match &self.source {
+            Source::Network { session, .. } => session.is_connected(),
+            Source::Pgn { .. } => self.live.is_some(),
+        }
+    }
+
+    /// Read the changes of the game, and get the game to show (if it changed and the delay
+    /// is elapsed).
+    pub fn update(&mut self) -> Option<Chess> {
+        if let Some(chess) = self.read() {
+            let changed = self
+                .live
+                .as_ref()
+                .is_none_or(|live| !is_same_game(live, &chess));
+            if changed {
+                self.delayed.push_back((Instant::now(), chess.clone()));
+            }
+            self.live = Some(chess);
+        }
+        let mut shown = None;
+        while let Some((read, _)) = self.delayed.front() {
+            if read.elapsed() < self.delay {
+                break;
+            }
+            shown = self.delayed.pop_front().map(|(_, chess)| chess);
+        }
+        shown
+    }
+
+    /// Get the game from the source (if it can be read now).
+    fn read(&mut self) -> Option<Chess> {
+        match &mut self.source {
+            Source::Network { session } => {
+                session.update(&mut self.received);
+                session.is_connected().then(|| self.received.clone())
+            }
+            Source::Pgn {
+                path,
+                modified,
+                last_poll,
+            } => {
+                if last_poll.is_some_and(|last| last.elapsed() < SPECTATOR_POLL_INTERVAL) {
+                    return None;
+                }
+                *last_poll = Some(Instant::now());
+                let time = fs::metadata(&path).and_then(|metadata| metadata.modified());
+                match time {
+                    Ok(time) if *modified != Some(time) => {
+                        // A file being written may be incomplete, it is read again later
+                        let chess = Chess::load(&path).ok()?;
+                        *modified = Some(time);
+                        Some(chess)
+                    }
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::{Color, GameState, Square};
+
+    #[test]
+    fn delay() {
+        let path = std::env::temp_dir().join("rg-chess-test-spectator-delay.pgn");
+        let mut chess = Chess::default();
+        chess.save(&path).unwrap();
+        let mut spectator = Spectator::tail(&path);
+        spectator.set_delay(Duration::from_millis(300));
+        assert_eq!(spectator.update(), None);
+        assert!(spectator.is_connected());
+
+        // The file is written again during the delay
+        chess.play(Square::E2, Square::E4);
+        chess.resign(Color::Black);
+        thread::sleep(SPECTATOR_POLL_INTERVAL);
+        chess.save(&path).unwrap();
+        let start = Instant::now();
+        let mut shown = vec![];
+        while shown.len() < 2 {
+            assert!(start.elapsed() < Duration::from_secs(5), "timeout");
+            shown.extend(spectator.update());
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(start.elapsed() >= spectator.delay());
+        assert_eq!(shown[0], Chess::default());
+        assert_eq!(shown[1].state, GameState::Resigns(Color::Black));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn invalid_file() {
+        let mut spectator = Spectator::tail("/nonexistent/rg-chess.pgn");
+        assert_eq!(spectator.update(), None);
+        assert!(!spectator.is_connected());
+    }
+}
@@ -1,10 +1,8 @@
-use std::cmp::max;
-use std::fmt;
-use std::str::FromStr;
+use core::cmp::max;
+use core::fmt;
+use core::str::FromStr;
 
-use crate::{
-    Color, Direction, Error, File, Rank, BOARD_CELL_PX_SIZE, BOARD_SIZE, NUM_FILES, NUM_RANKS,
-};
+use crate::{Color, Direction, Error, File, Rank, BOARD_SIZE, NUM_FILES, NUM_RANKS};
 
 /// Represent a square on the chess board.
 #[rustfmt::skip]
@@ -78,43 +76,6 @@ impl Square {
         Square::new(file.to_index() + rank.to_index() * BOARD_SIZE.0 as usize)
     }
 
-    /// Transform a screen coordinate into a [`Square`].
-    ///
-    /// > **Reciprocal**: see [`Square::to_screen`].
-    ///
-    /// The result depend of:
-    /// - [`BOARD_SIZE`]
-    /// - [`BOARD_CELL_PX_SIZE`]
-    #[inline]
-    pub fn from_screen(x: f32, y: f32) -> Square {
-        // Transpose to grid space
-        let x = x / BOARD_CELL_PX_SIZE.0;
-        let y = y / BOARD_CELL_PX_SIZE.1;
-
-        // transpose to Square (return the y-axis)
-        let y = BOARD_SIZE.1 - y as i16 - 1;
-        Square::make_square(File::new(x as usize), Rank::new(y as usize))
-    }
-
-    /// Transform a [`Square`] into a screen coordinate.
-    ///
-    /// > **Reciprocal**: see [`Square::from_screen`].
-    ///
-    /// The result depend of:
-    /// - [`BOARD_SIZE`]
-    /// - [`BOARD_CELL_PX_SIZE`]
-    #[inline]
-    pub fn to_screen(&self) -> (f32, f32) {
-        // transpose to grid space (return the y-axis)
-        let x = self.file().to_index() as f32;
-        let y = (BOARD_SIZE.1 as usize - self.rank().to_index() - 1) as f32;
-
-        // Transpose to screen space
-        let x = x * BOARD_CELL_PX_SIZE.0;
-        let y = y * BOARD_CELL_PX_SIZE.1;
-        (x, y)
-    }
-
     /// Return the [`File`] of this square.
     ///
     /// # Examples
@@ -521,10 +482,11 @@ impl FromStr for Square {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.len() < 2 {
-            return Err(Error::InvalidSquare);
-        }
-        let ch: Vec<char> = s.chars().collect();
+        let mut chars = s.chars();
+        let ch = match (chars.next(), chars.next()) {
+            (Some(file), Some(rank)) => [file, rank],
+            _ => return Err(Error::InvalidSquare),
+        };
         match ch[0] {
             'a' | 'b' | 'c' | 'd' | 'e' | 'f' | 'g' | 'h' => {}
             _ => return Err(Error::InvalidSquare),
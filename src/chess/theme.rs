@@ -1,132 +1,641 @@
 //! Describe theme available in the game
 
+use std::fs;
+use std::path::{Path, PathBuf};
+
 use ggez::graphics::Color;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{ALL_COLORS, ALL_PIECES, NUM_COLORS, NUM_PIECES, RESOURCES_DIR};
+
+/// Error that can happen when loading a [`Theme`].
+#[derive(Error, Debug)]
+pub enum ThemeError {
+    /// The theme file can't be read.
+    #[error("Can't read the theme file {}: {}", path.display(), source)]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    /// The theme file is not a valid TOML file.
+    #[error("Invalid theme file {}: {}", path.display(), source)]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+
+    /// A value of the theme is invalid.
+    #[error("Invalid value for `{}` in the theme \"{}\": {}", field, theme, reason)]
+    InvalidValue {
+        theme: String,
+        field: String,
+        reason: String,
+    },
 
-use crate::{NUM_COLORS, NUM_PIECES};
-
-/// Dust theme.
-pub const THEME_DUST: Theme = Theme {
-    background_color: Color::new(0.09, 0.09, 0.11, 1.0),
-    board_color: [
-        Color::new(0.7969, 0.7148, 0.6797, 1.0),
-        Color::new(0.4375, 0.3984, 0.4648, 1.0),
-    ],
-    piece_path: [
-        [
-            "/images/pieces/white_pawn.png",
-            "/images/pieces/white_knight.png",
-            "/images/pieces/white_bishop.png",
-            "/images/pieces/white_rook.png",
-            "/images/pieces/white_queen.png",
-            "/images/pieces/white_king.png",
-        ],
-        [
-            "/images/pieces/black_pawn.png",
-            "/images/pieces/black_knight.png",
-            "/images/pieces/black_bishop.png",
-            "/images/pieces/black_rook.png",
-            "/images/pieces/black_queen.png",
-            "/images/pieces/black_king.png",
-        ],
-    ],
-    valid_moves_color: Some(Color::new(0.25, 0.75, 0.25, 0.5)),
-    piece_pinned_color: Some(Color::new(0.75, 0.25, 0.25, 0.5)),
-    piece_pinned_path: Some("/images/pin.png"),
-    theme_icon_path: Some("/images/theme_icon_white.png"),
-    font_path: "/fonts/LiberationMono-Regular.ttf",
-    font_scale: 20.0,
-};
-
-/// Coral theme.
-pub const THEME_CORAL: Theme = Theme {
-    board_color: [
-        Color::new(177.0 / 256.0, 228.0 / 256.0, 185.0 / 256.0, 1.0),
-        Color::new(112.0 / 256.0, 162.0 / 256.0, 163.0 / 256.0, 1.0),
-    ],
-    ..THEME_DUST
-};
-
-/// Marine theme.
-pub const THEME_MARINE: Theme = Theme {
-    board_color: [
-        Color::new(157.0 / 256.0, 172.0 / 256.0, 255.0 / 256.0, 1.0),
-        Color::new(111.0 / 256.0, 115.0 / 256.0, 210.0 / 256.0, 1.0),
-    ],
-    ..THEME_DUST
-};
-
-/// Wheat theme.
-pub const THEME_WHEAT: Theme = Theme {
-    board_color: [
-        Color::new(234.0 / 256.0, 240.0 / 256.0, 206.0 / 256.0, 1.0),
-        Color::new(187.0 / 256.0, 190.0 / 256.0, 100.0 / 256.0, 1.0),
-    ],
-    ..THEME_DUST
-};
-
-/// Emerald theme.
-pub const THEME_EMERALD: Theme = Theme {
-    board_color: [
-        Color::new(173.0 / 256.0, 189.0 / 256.0, 143.0 / 256.0, 1.0),
-        Color::new(111.0 / 256.0, 143.0 / 256.0, 114.0 / 256.0, 1.0),
-    ],
-    ..THEME_DUST
-};
-
-/// Sandcastle theme.
-pub const THEME_SANDCASTLE: Theme = Theme {
-    board_color: [
-        Color::new(227.0 / 256.0, 193.0 / 256.0, 111.0 / 256.0, 1.0),
-        Color::new(184.0 / 256.0, 139.0 / 256.0, 74.0 / 256.0, 1.0),
-    ],
-    ..THEME_DUST
-};
-
-/// Index of the current theme if using roll theme.
-pub(crate) static mut INDEX_THEME: usize = 0;
-
-/// Numbers of [`Theme`].
-pub const NUM_THEMES: usize = 6;
-
-/// Enumerate all [`Theme`].
-pub const THEMES: [Theme; NUM_THEMES] = [
-    THEME_DUST,
-    THEME_CORAL,
-    THEME_MARINE,
-    THEME_WHEAT,
-    THEME_EMERALD,
-    THEME_SANDCASTLE,
-];
+    /// A required value of the theme is missing (and the theme doesn't extend another one).
+    #[error("Missing value for `{}` in the theme \"{}\"", field, theme)]
+    MissingValue { theme: String, field: String },
+
+    /// No theme with this name exist.
+    #[error("Unknown theme \"{}\"", name)]
+    Unknown { name: String },
+}
 
 /// Describe the theme of the chess game (GUI).
 ///
 /// RootPath is `resources/` (changed by [`ggez::ContextBuilder::add_resource_path`]).
 ///
+/// Themes are usually defined in TOML files (see [`Theme::from_toml`]) and discovered at
+/// startup by a [`ThemeRegistry`].
+///
 /// # Examples
 ///
 /// ```
-/// use chess::{Theme, THEME_DUST};
+/// use chess::Theme;
 ///
-/// const THEME:Theme = Theme {
-///     font_path: "/fonts/font.ttf", // located in resources/fonts/font.ttf
-///     ..THEME_DUST
+/// let theme = Theme {
+///     font_path: "/fonts/font.ttf".to_string(), // located in resources/fonts/font.ttf
+///     ..Default::default()
 /// };
 /// ```
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct Theme {
+    pub name: String,
     pub background_color: Color,
     pub board_color: [Color; NUM_COLORS],
-    pub piece_path: [[&'static str; NUM_PIECES]; NUM_COLORS],
+    pub piece_path: [[String; NUM_PIECES]; NUM_COLORS],
     pub valid_moves_color: Option<Color>,
+    pub last_move_color: Option<Color>,
+    pub check_color: Option<Color>,
+    pub selected_color: Option<Color>,
+    pub hovered_color: Option<Color>,
+    pub premove_color: Option<Color>,
+    pub hint_color: Option<Color>,
     pub piece_pinned_color: Option<Color>,
-    pub piece_pinned_path: Option<&'static str>,
-    pub theme_icon_path: Option<&'static str>,
-    pub font_path: &'static str,
+    pub piece_pinned_path: Option<String>,
+    pub theme_icon_path: Option<String>,
+    pub font_path: String,
     pub font_scale: f32,
 }
 
+impl Theme {
+    /// Parse a theme from the content of a TOML file.
+    ///
+    /// If the theme `extends` another one, the `parent` is used for the missing values.
+    ///
+    /// | Key                  | Value                                                 |
+    /// |----------------------|-------------------------------------------------------|
+    /// | `name`               | Name of the theme (required)                          |
+    /// | `extends`            | Name of the theme used for the missing values         |
+    /// | `background`         | Color of the background                               |
+    /// | `board`              | Colors of the white and black cells                   |
+    /// | `piece_set`          | Directory of the `<color>_<piece>.png` images         |
+    /// | `valid_moves`        | Color of the legal destinations, or `"none"`          |
+    /// | `last_move`          | Color of the last move, or `"none"`                   |
+    /// | `check`              | Color of the king in check, or `"none"`               |
+    /// | `selected`           | Color of the selected square, or `"none"`             |
+    /// | `hovered`            | Color of the hovered destination, or `"none"`         |
+    /// | `premove`            | Color of the queued premoves, or `"none"`             |
+    /// | `hint`               | Color of the squares of the hint, or `"none"`         |
+    /// | `piece_pinned`       | Color of the pinned pieces, or `"none"`               |
+    /// | `piece_pinned_image` | Image drawn on pinned pieces, or `"none"`             |
+    /// | `theme_icon`         | Image of the theme button, or `"none"`                |
+    /// | `font`               | Path of the font                                      |
+    /// | `font_scale`         | Scale of the font                                     |
+    ///
+    /// Colors are written `"#RRGGBB"` or `"#RRGGBBAA"`, paths are relative to the resources
+    /// and the files must exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::Theme;
+    ///
+    /// let content = r##"
+    ///     name = "Night"
+    ///     extends = "Dust"
+    ///     board = ["#8899AA", "#223344"]
+    ///     check = "none"
+    /// "##;
+    /// let theme = Theme::from_toml(content, Some(&Theme::default())).unwrap();
+    ///
+    /// assert_eq!(theme.name, "Night");
+    /// assert_eq!(theme.check_color, None);
+    /// assert_eq!(theme.font_path, Theme::default().font_path);
+    /// ```
+    pub fn from_toml(content: &str, parent: Option<&Theme>) -> Result<Theme, ThemeError> {
+        let file: ThemeFile = toml::from_str(content).map_err(|source| ThemeError::Parse {
+            path: PathBuf::from("<string>"),
+            source,
+        })?;
+        file.into_theme(parent)
+    }
+}
+
 impl Default for Theme {
+    /// The Dust theme, used when no theme file is found.
+    fn default() -> Self {
+        Theme {
+            name: "Dust".to_string(),
+            background_color: Color::new(0.09, 0.09, 0.11, 1.0),
+            board_color: [
+                Color::new(0.7969, 0.7148, 0.6797, 1.0),
+                Color::new(0.4375, 0.3984, 0.4648, 1.0),
+            ],
+            piece_path: piece_set_paths("/images/pieces"),
+            valid_moves_color: Some(Color::new(0.25, 0.75, 0.25, 0.5)),
+            last_move_color: Some(Color::new(0.95, 0.85, 0.25, 0.4)),
+            check_color: Some(Color::new(0.9, 0.1, 0.1, 0.6)),
+            selected_color: Some(Color::new(0.25, 0.5, 0.9, 0.5)),
+            hovered_color: Some(Color::new(0.25, 0.75, 0.25, 0.8)),
+            premove_color: Some(Color::new(0.6, 0.25, 0.75, 0.5)),
+            hint_color: Some(Color::new(0.1, 0.7, 0.7, 0.6)),
+            piece_pinned_color: Some(Color::new(0.75, 0.25, 0.25, 0.5)),
+            piece_pinned_path: Some("/images/pin.png".to_string()),
+            theme_icon_path: Some("/images/theme_icon_white.png".to_string()),
+            font_path: "/fonts/LiberationMono-Regular.ttf".to_string(),
+            font_scale: 20.0,
+        }
+    }
+}
+
+/// Verify if a path of the resources (starting with '/') exists.
+fn resource_exists(path: &str) -> bool {
+    Path::new(RESOURCES_DIR)
+        .join(path.trim_start_matches('/'))
+        .exists()
+}
+
+/// Get the path of every piece image in a piece set directory.
+fn piece_set_paths(dir: &str) -> [[String; NUM_PIECES]; NUM_COLORS] {
+    const COLOR_NAMES: [&str; NUM_COLORS] = ["white", "black"];
+    const PIECE_NAMES: [&str; NUM_PIECES] = ["pawn", "knight", "bishop", "rook", "queen", "king"];
+    let dir = dir.trim_end_matches('/');
+    ALL_COLORS.map(|color| {
+        ALL_PIECES.map(|piece| {
+            format!(
+                "{dir}/{}_{}.png",
+                COLOR_NAMES[color.to_index()],
+                PIECE_NAMES[piece.to_index()]
+            )
+        })
+    })
+}
+
+/// The content of a theme file, every value is optional if the theme extends another one.
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    name: String,
+    extends: Option<String>,
+    background: Option<String>,
+    board: Option<[String; NUM_COLORS]>,
+    piece_set: Option<String>,
+    valid_moves: Option<String>,
+    last_move: Option<String>,
+    check: Option<String>,
+    selected: Option<String>,
+    hovered: Option<String>,
+    premove: Option<String>,
+    hint: Option<String>,
+    piece_pinned: Option<String>,
+    piece_pinned_image: Option<String>,
+    theme_icon: Option<String>,
+    font: Option<String>,
+    font_scale: Option<f32>,
+}
+
+impl ThemeFile {
+    /// Validate the values and build the [`Theme`].
+    fn into_theme(self, parent: Option<&Theme>) -> Result<Theme, ThemeError> {
+        let name = self.name.trim().to_string();
+        if name.is_empty() {
+            return Err(ThemeError::InvalidValue {
+                theme: name,
+                field: "name".to_string(),
+                reason: "the name can't be empty".to_string(),
+            });
+        }
+        let parent = match (&self.extends, parent) {
+            (Some(_), Some(parent)) => Some(parent),
+            (Some(extends), None) => {
+                return Err(ThemeError::Unknown {
+                    name: extends.clone(),
+                })
+            }
+            (None, _) => None,
+        };
+
+        let missing = |field: &str| ThemeError::MissingValue {
+            theme: name.clone(),
+            field: field.to_string(),
+        };
+        let invalid = |field: &str, reason: String| ThemeError::InvalidValue {
+            theme: name.clone(),
+            field: field.to_string(),
+            reason,
+        };
+        let color = |field: &str, value: &str| {
+            parse_color(value).ok_or_else(|| {
+                invalid(
+                    field,
+                    format!("\"{value}\" is not a color (#RRGGBB or #RRGGBBAA)"),
+                )
+            })
+        };
+        let optional_color =
+            |field: &str, value: Option<String>, default: Option<Color>| match value.as_deref() {
+                None => Ok(default),
+                Some("none") => Ok(None),
+                Some(value) => color(field, value).map(Some),
+            };
+        let path = |field: &str, value: String| {
+            if !value.starts_with('/') {
+                Err(invalid(
+                    field,
+                    format!("\"{value}\" must be a path in the resources (starting with '/')"),
+                ))
+            } else if !resource_exists(&value) {
+                Err(invalid(
+                    field,
+                    format!("\"{value}\" doesn't exist in the resources"),
+                ))
+            } else {
+                Ok(value)
+            }
+        };
+        let optional_path =
+            |field: &str, value: Option<String>, default: Option<String>| match value {
+                None => Ok(default),
+                Some(value) if value == "none" => Ok(None),
+                Some(value) => path(field, value).map(Some),
+            };
+
+        let background_color = match self.background {
+            Some(value) => color("background", &value)?,
+            None => {
+                parent
+                    .ok_or_else(|| missing("background"))?
+                    .background_color
+            }
+        };
+        let board_color = match self.board {
+            Some([white, black]) => [color("board", &white)?, color("board", &black)?],
+            None => parent.ok_or_else(|| missing("board"))?.board_color,
+        };
+        let piece_path = match self.piece_set {
+            Some(dir) => {
+                let paths = piece_set_paths(&path("piece_set", dir)?);
+                // The images would fail to load when drawn
+                if let Some(image) = paths.iter().flatten().find(|image| !resource_exists(image)) {
+                    return Err(invalid(
+                        "piece_set",
+                        format!("\"{image}\" doesn't exist in the resources"),
+                    ));
+                }
+                paths
+            }
+            None => parent
+                .ok_or_else(|| missing("piece_set"))?
+                .piece_path
+                .clone(),
+        };
+        let font_path = match self.font {
+            Some(font) => path("font", font)?,
+            None => parent.ok_or_else(|| missing("font"))?.font_path.clone(),
+        };
+        let font_scale = match self.font_scale {
+            Some(scale) if scale > 0.0 && scale.is_finite() => scale,
+            Some(scale) => return Err(invalid("font_scale", format!("{scale} is not positive"))),
+            None => parent.ok_or_else(|| missing("font_scale"))?.font_scale,
+        };
+
+        Ok(Theme {
+            background_color,
+            board_color,
+            piece_path,
+            valid_moves_color: optional_color(
+                "valid_moves",
+                self.valid_moves,
+                parent.and_then(|theme| theme.valid_moves_color),
+            )?,
+            last_move_color: optional_color(
+                "last_move",
+                self.last_move,
+                parent.and_then(|theme| theme.last_move_color),
+            )?,
+            check_color: optional_color(
+                "check",
+                self.check,
+                parent.and_then(|theme| theme.check_color),
+            )?,
+            selected_color: optional_color(
+                "selected",
+                self.selected,
+                parent.and_then(|theme| theme.selected_color),
+            )?,
+            hovered_color: optional_color(
+                "hovered",
+                self.hovered,
+                parent.and_then(|theme| theme.hovered_color),
+            )?,
+            premove_color: optional_color(
+                "premove",
+                self.premove,
+                parent.and_then(|theme| theme.premove_color),
+            )?,
+            hint_color: optional_color(
+                "hint",
+                self.hint,
+                parent.and_then(|theme| theme.hint_color),
+            )?,
+            piece_pinned_color: optional_color(
+                "piece_pinned",
+                self.piece_pinned,
+                parent.and_then(|theme| theme.piece_pinned_color),
+            )?,
+            piece_pinned_path: optional_path(
+                "piece_pinned_image",
+                self.piece_pinned_image,
+                parent.and_then(|theme| theme.piece_pinned_path.clone()),
+            )?,
+            theme_icon_path: optional_path(
+                "theme_icon",
+                self.theme_icon,
+                parent.and_then(|theme| theme.theme_icon_path.clone()),
+            )?,
+            font_path,
+            font_scale,
+            name,
+        })
+    }
+}
+
+/// Parse a `#RRGGBB` or `#RRGGBBAA` color.
+fn parse_color(value: &str) -> Option<Color> {
+    let hex = value.strip_prefix('#')?;
+    if !(hex.len() == 6 || hex.len() == 8) || !hex.is_ascii() {
+        return None;
+    }
+    let mut components = [255u8; 4];
+    for (i, component) in components.iter_mut().enumerate().take(hex.len() / 2) {
+        *component = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).ok()?;
+    }
+    Some(Color::from_rgba(
+        components[0],
+        components[1],
+        components[2],
+        components[3],
+    ))
+}
+
+/// All the [`Theme`] available in the game, and the one in use.
+///
+/// It always contains at least the [`default`][Theme::default] theme.
+///
+/// # Examples
+///
+/// ```
+/// use chess::ThemeRegistry;
+///
+/// let mut themes = ThemeRegistry::default();
+/// assert_eq!(themes.current().name, "Dust");
+/// assert!(themes.select("Unknown").is_err());
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct ThemeRegistry {
+    themes: Vec<Theme>,
+    current: usize,
+}
+
+impl ThemeRegistry {
+    /// Create a registry with the themes found in the [`THEMES_DIR`][crate::THEMES_DIR].
+    ///
+    /// Invalid theme files are skipped and logged.
+    pub fn discover() -> Self {
+        let mut registry = ThemeRegistry::default();
+        for error in registry.load_dir(crate::THEMES_DIR) {
+            log::warn!("{error}");
+        }
+        registry
+    }
+
+    /// Load every `*.toml` theme file of a directory.
+    ///
+    /// A theme with the same name as a known theme replaces it.
+    /// Return the errors of the files that can't be loaded.
+    pub fn load_dir<P: AsRef<Path>>(&mut self, dir: P) -> Vec<ThemeError> {
+        let dir = dir.as_ref();
+        let mut errors = Vec::new();
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(source) => {
+                errors.push(ThemeError::Io {
+                    path: dir.to_path_buf(),
+                    source,
+                });
+                return errors;
+            }
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .collect();
+        paths.sort();
+
+        let mut files = Vec::new();
+        for path in paths {
+            let file = fs::read_to_string(&path)
+                .map_err(|source| ThemeError::Io {
+                    path: path.clone(),
+                    source,
+                })
+                .and_then(|content| {
+                    toml::from_str::<ThemeFile>(&content).map_err(|source| ThemeError::Parse {
+                        path: path.clone(),
+                        source,
+                    })
+                });
+            match file {
+                Ok(file) => files.push(file),
+                Err(error) => errors.push(error),
+            }
+        }
+
+        // A theme can extend a theme defined in another file, so resolve them until
+        // nothing changes.
+        loop {
+            let (mut ready, waiting): (Vec<ThemeFile>, Vec<ThemeFile>) =
+                files.into_iter().partition(|file| match &file.extends {
+                    Some(parent) => self.get(parent).is_some(),
+                    None => true,
+                });
+            // Themes that don't extend another one first, they may replace a parent
+            ready.sort_by_key(|file| file.extends.is_some());
+            if ready.is_empty() {
+                files = waiting;
+                break;
+            }
+            for file in ready {
+                let parent = file
+                    .extends
+                    .as_ref()
+                    .and_then(|name| self.get(name))
+                    .cloned();
+                match file.into_theme(parent.as_ref()) {
+                    Ok(theme) => self.add(theme),
+                    Err(error) => errors.push(error),
+                }
+            }
+            files = waiting;
+        }
+        for file in files {
+            errors.push(ThemeError::InvalidValue {
+                theme: file.name,
+                field: "extends".to_string(),
+                reason: format!("unknown theme \"{}\"", file.extends.unwrap_or_default()),
+            });
+        }
+        errors
+    }
+
+    /// Add a theme, it replaces the theme with the same name (if exist).
+    pub fn add(&mut self, theme: Theme) {
+        match self
+            .themes
+            .iter()
+            .position(|known| known.name == theme.name)
+        {
+            Some(index) => self.themes[index] = theme,
+            None => self.themes.push(theme),
+        }
+    }
+
+    /// Get a theme by its name.
+    pub fn get(&self, name: &str) -> Option<&Theme> {
+        self.themes.iter().find(|theme| theme.name == name)
+    }
+
+    /// Get the names of all the themes.
+    pub fn names(&self) -> Vec<&str> {
+        self.themes
+            .iter()
+            .map(|theme| theme.name.as_str())
+            .collect()
+    }
+
+    /// Get the theme in use.
+    pub fn current(&self) -> &Theme {
+        &self.themes[self.current]
+    }
+
+    /// Use the theme with the given name.
+    pub fn select(&mut self, name: &str) -> Result<&Theme, ThemeError> {
+        let index = self
+            .themes
+            .iter()
+            .position(|theme| theme.name == name)
+            .ok_or_else(|| ThemeError::Unknown {
+                name: name.to_string(),
+            })?;
+        self.current = index;
+        Ok(self.current())
+    }
+
+    /// Use the next theme (cycle through all the themes).
+    pub fn select_next(&mut self) -> &Theme {
+        self.current = (self.current + 1) % self.themes.len();
+        self.current()
+    }
+}
+
+impl Default for ThemeRegistry {
+    /// A registry with only the [`default`][Theme::default] theme.
     fn default() -> Self {
-        THEME_DUST
+        ThemeRegistry {
+            themes: vec![Theme::default()],
+            current: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_color() {
+        assert_eq!(
+            super::parse_color("#FF0000"),
+            Some(Color::from_rgba(255, 0, 0, 255))
+        );
+        assert_eq!(
+            super::parse_color("#00ff0080"),
+            Some(Color::from_rgba(0, 255, 0, 128))
+        );
+        assert_eq!(super::parse_color("FF0000"), None);
+        assert_eq!(super::parse_color("#FF00"), None);
+        assert_eq!(super::parse_color("#GG0000"), None);
+    }
+
+    #[test]
+    fn from_toml_errors() {
+        let missing = Theme::from_toml(r#"name = "Alone""#, None);
+        assert!(matches!(missing, Err(ThemeError::MissingValue { .. })));
+
+        let unknown_parent = Theme::from_toml("name = \"Child\"\nextends = \"Dust\"", None);
+        assert!(matches!(unknown_parent, Err(ThemeError::Unknown { .. })));
+
+        let content = "name = \"Bad\"\nextends = \"Dust\"\nboard = [\"#FFFFFF\", \"black\"]";
+        let error = Theme::from_toml(content, Some(&Theme::default())).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid value for `board` in the theme \"Bad\": \
+             \"black\" is not a color (#RRGGBB or #RRGGBBAA)"
+        );
+
+        let content = "name = \"Bad\"\nextends = \"Dust\"\nfont = \"fonts/font.ttf\"";
+        let error = Theme::from_toml(content, Some(&Theme::default()));
+        assert!(matches!(error, Err(ThemeError::InvalidValue { .. })));
+
+        let content = "name = \"Bad\"\nextends = \"Dust\"\nfont = \"/fonts/missing.ttf\"";
+        let error = Theme::from_toml(content, Some(&Theme::default())).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid value for `font` in the theme \"Bad\": \
+             \"/fonts/missing.ttf\" doesn't exist in the resources"
+        );
+
+        // The directory exists but not the images
+        let content = "name = \"Bad\"\nextends = \"Dust\"\npiece_set = \"/fonts\"";
+        let error = Theme::from_toml(content, Some(&Theme::default()));
+        assert!(matches!(error, Err(ThemeError::InvalidValue { .. })));
+
+        let content = "name = \"Bad\"\nextends = \"Dust\"\nunknown_key = 1";
+        let error = Theme::from_toml(content, Some(&Theme::default()));
+        assert!(matches!(error, Err(ThemeError::Parse { .. })));
+    }
+
+    #[test]
+    fn registry() {
+        let mut registry = ThemeRegistry::default();
+        let errors = registry.load_dir(crate::THEMES_DIR);
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(
+            registry.names(),
+            vec!["Dust", "Coral", "Emerald", "Marine", "Sandcastle", "Wheat"]
+        );
+
+        assert_eq!(registry.select("Marine").unwrap().name, "Marine");
+        assert_eq!(registry.select_next().name, "Sandcastle");
+        assert_eq!(registry.select_next().name, "Wheat");
+        assert_eq!(registry.select_next().name, "Dust");
+        assert!(matches!(
+            registry.select("Unknown"),
+            Err(ThemeError::Unknown { .. })
+        ));
+        assert_eq!(registry.current().name, "Dust");
     }
 }
@@ -0,0 +1,530 @@
+//! Play in a terminal, e.g. over SSH without a display.
+
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{self, Stylize};
+use crossterm::{cursor, execute, queue, terminal};
+
+use crate::{
+    Chess, ChessMove, Color, GameState, Pgn, Piece, Player, SharedBot, Square, UciError,
+    NUM_COLORS, TUI_TICK,
+};
+
+/// The colours of the board in the terminal.
+const LIGHT_SQUARE: style::Color = style::Color::Rgb {
+    r: 240,
+    g: 217,
+    b: 181,
+};
+const DARK_SQUARE: style::Color = style::Color::Rgb {
+    r: 181,
+    g: 136,
+    b: 99,
+};
+const LAST_MOVE_SQUARE: style::Color = style::Color::Rgb {
+    r: 205,
+    g: 210,
+    b: 106,
+};
+const CURSOR_SQUARE: style::Color = style::Color::Rgb {
+    r: 100,
+    g: 150,
+    b: 230,
+};
+const TARGET_SQUARE: style::Color = style::Color::Rgb {
+    r: 130,
+    g: 190,
+    b: 130,
+};
+
+/// The number of moves shown in the move list (the last ones).
+const MOVE_LIST_LINES: usize = 12;
+
+/// The column of the side panel (right of the board).
+const PANEL_COLUMN: u16 = 30;
+
+/// A game of chess in the terminal.
+///
+/// It renders the [`Board`][crate::Board] with Unicode pieces and plays on a [`Chess`], so the
+/// rules are the same as in the GUI. A move is entered with the cursor (the arrows, then
+/// `Space` or `Enter` on the piece and on its destination) or typed in SAN or UCI
+/// (e.g. `Nf3` or `g1f3`) followed by `Enter`.
+///
+/// The typed commands are `undo`, `new`, `flip`, `draw` (offer, accept or declare a draw),
+/// `resign` and `quit` (or `Ctrl+C`).
+///
+/// # Examples
+///
+/// ```no_run
+/// use chess::{Chess, ChessTui};
+///
+/// ChessTui::new(Chess::default()).run().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct ChessTui {
+    chess: Chess,
+    cursor: Square,
+    selected: Option<Square>,
+    flipped: bool,
+    /// The move or the command being typed.
+    input: String,
+    /// The answer to the last input (e.g. an illegal move).
+    message: String,
+    bots: [Option<SharedBot>; NUM_COLORS],
+    /// The move of the bot thinking in another thread.
+    thinking: Option<mpsc::Receiver<Option<ChessMove>>>,
+    quit: bool,
+}
+
+impl ChessTui {
+    /// Create a game in the terminal, played by two humans.
+    pub fn new(chess: Chess) -> Self {
+        ChessTui {
+            chess,
+            cursor: Square::E2,
+            selected: None,
+            flipped: false,
+            input: String::new(),
+            message: String::new(),
+            bots: [None, None],
+            thinking: None,
+            quit: false,
+        }
+    }
+
+    /// Get the game.
+    pub fn chess(&self) -> &Chess {
+        &self.chess
+    }
+
+    /// Set who plays a side, an UCI engine is started immediately.
+    pub fn set_player(&mut self, color: Color, player: &Player) -> Result<(), UciError> {
+        self.bots[color.to_index()] = player.bot()?.map(|bot| Arc::new(Mutex::new(bot)));
+        self.thinking = None;
+        Ok(())
+    }
+
+    /// Show the board upside down (black at the bottom).
+    pub fn set_flipped(&mut self, flipped: bool) {
+        self.flipped = flipped;
+    }
+
+    /// Play in the terminal until the user quits.
+    ///
+    /// The terminal is in raw mode on an alternate screen during the game, it is restored
+    /// at the end.
+    pub fn run(&mut self) -> io::Result<()> {
+        let mut out = io::stdout();
+        terminal::enable_raw_mode()?;
+        execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+        let result = self.event_loop(&mut out);
+        execute!(out, cursor::Show, terminal::LeaveAlternateScreen)?;
+        terminal::disable_raw_mode()?;
+        result
+    }
+
+    fn event_loop<W: Write>(&mut self, out: &mut W) -> io::Result<()> {
+        let mut last = Instant::now();
+        while !self.quit {
+            self.chess.tick(last.elapsed());
+            last = Instant::now();
+            self.update_bots();
+            self.draw(out)?;
+            if event::poll(TUI_TICK)? {
+                match event::read()? {
+                    Event::Key(key) if key.kind == KeyEventKind::Press => self.handle_key(key),
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Ask the bot to play if it is its turn, and play its move once found.
+    ///
+    /// The bot thinks in another thread to keep the clocks and the keys responsive.
+    fn update_bots(&mut self) {
+        let side = self.chess.board.side_to_move();
+        if let Some(receiver) = &self.thinking {
+            match receiver.try_recv() {
+                Ok(Some(m)) => {
+                    self.thinking = None;
+                    if self.chess.state.is_ongoing() {
+                        self.play(m);
+                    }
+                }
+                Ok(None) | Err(mpsc::TryRecvError::Disconnected) => {
+                    // Don't ask again, the side is given back to the user
+                    self.message = format!("The bot playing {side:?} can't find a move");
+                    self.thinking = None;
+                    self.bots[side.to_index()] = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => {}
+            }
+        } else if self.chess.state.is_ongoing() {
+            if let Some(bot) = &self.bots[side.to_index()] {
+                let bot = Arc::clone(bot);
+                let board = self.chess.board;
+                let (sender, receiver) = mpsc::channel();
+                thread::spawn(move || {
+                    let mut bot = bot.lock().expect("bot poisoned");
+                    // The game may have stopped waiting for this move
+                    let _ = sender.send(bot.choose_move(&board));
+                });
+                self.thinking = Some(receiver);
+            }
+        }
+    }
+
+    /// Verify if the player who has to play is a [`Bot`][crate::Bot].
+    fn is_bot_turn(&self) -> bool {
+        self.bots[self.chess.board.side_to_move().to_index()].is_some()
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            if matches!(key.code, KeyCode::Char('c') | KeyCode::Char('q')) {
+                self.quit = true;
+            }
+            return;
+        }
+        // The arrows go up and right from the point of view of the player at the bottom
+        let (up, down) = match self.flipped {
+            false => (KeyCode::Up, KeyCode::Down),
+            true => (KeyCode::Down, KeyCode::Up),
+        };
+        let (right, left) = match self.flipped {
+            false => (KeyCode::Right, KeyCode::Left),
+            true => (KeyCode::Left, KeyCode::Right),
+        };
+        match key.code {
+            code if code == up => self.cursor = self.cursor.up(),
+            code if code == down => self.cursor = self.cursor.down(),
+            code if code == right => self.cursor = self.cursor.right(),
+            code if code == left => self.cursor = self.cursor.left(),
+            KeyCode::Enter if !self.input.is_empty() => {
+                let input = std::mem::take(&mut self.input);
+                self.submit(input.trim());
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => self.select(self.cursor),
+            KeyCode::Esc => {
+                self.selected = None;
+                self.input.clear();
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Char(c) if c.is_ascii_graphic() => self.input.push(c),
+            _ => {}
+        }
+    }
+
+    /// Select a piece of the side to move, or play the selected piece on the square.
+    fn select(&mut self, square: Square) {
+        self.message.clear();
+        if self.chess.state.is_finish() || self.is_bot_turn() {
+            return;
+        }
+        let side = self.chess.board.side_to_move();
+        match self.selected {
+            Some(from) if self.chess.board.is_legal(ChessMove::new(from, square)) => {
+                self.play(ChessMove::new(from, square));
+            }
+            _ if self.chess.board.color_on_is(square, side) => self.selected = Some(square),
+            _ => self.selected = None,
+        }
+    }
+
+    /// Execute a typed command or play a typed move.
+    fn submit(&mut self, input: &str) {
+        self.message.clear();
+        let side = self.chess.board.side_to_move();
+        match input {
+            "quit" => self.quit = true,
+            "flip" => self.flipped = !self.flipped,
+            "undo" => {
+                self.thinking = None;
+                self.chess.undo();
+            }
+            "new" => {
+                self.thinking = None;
+                self.chess.reset();
+            }
+            _ if self.chess.state.is_finish() => self.message = "The game is over".to_string(),
+            "resign" => self.chess.resign(side),
+            "draw" if self.chess.offer_draw => self.chess.accept_draw(),
+            "draw" if self.chess.can_declare_draw() => self.chess.declare_draw(),
+            "draw" => {
+                self.chess.offer_draw();
+                self.message = format!("{side:?} offers a draw");
+            }
+            _ if self.is_bot_turn() => self.message = "Wait for the bot".to_string(),
+            _ => {
+                let board = self.chess.board;
+                let m = ChessMove::from_str(input)
+                    .or_else(|_| ChessMove::from_san(&board, input))
+                    .ok()
+                    .filter(|&m| board.is_legal(m));
+                match m {
+                    Some(m) => self.play(m),
+                    None => self.message = format!("Illegal move: {input}"),
+                }
+            }
+        }
+        self.selected = None;
+    }
+
+    fn play(&mut self, m: ChessMove) {
+        self.chess.play(m.from, m.to);
+        self.selected = None;
+        self.cursor = m.to;
+    }
+
+    /// Get the text of the state of the game.
+    fn status(&self) -> String {
+        match self.chess.state {
+            GameState::Ongoing if self.chess.offer_draw => {
+                format!("{:?} turn, draw offered", self.chess.board.side_to_move())
+            }
+            GameState::Ongoing => format!("{:?} turn", self.chess.board.side_to_move()),
+            GameState::Checkmates(color) => format!("{color:?} is checkmate, {:?} win !", !color),
+            GameState::Stalemate => "Draw: Stalemate".to_string(),
+            GameState::DrawAccepted => "Draw: Accepted".to_string(),
+            GameState::DrawDeclared => "Draw: Declared".to_string(),
+            GameState::Resigns(color) => format!("{color:?} resigns, {:?} win !", !color),
+            GameState::Timeout(color) => format!("{color:?} timeout, {:?} win !", !color),
+        }
+    }
+
+    /// Get the text of the clock of a side.
+    fn clock_text(&self, color: Color) -> String {
+        let time = match self.chess.clock() {
+            Some(clock) => {
+                let seconds = clock.remaining(color).as_secs_f32().ceil() as u64;
+                format!("{:02}:{:02}", seconds / 60, seconds % 60)
+            }
+            None => "--:--".to_string(),
+        };
+        let turn = match self.chess.board.side_to_move() == color {
+            true => "*",
+            false => " ",
+        };
+        format!("{turn} {color:?} {time}")
+    }
+
+    /// Get the last lines of the move list (e.g. `1. e4 e5`).
+    fn move_list(&self) -> Vec<String> {
+        let pgn = Pgn::from_chess(&self.chess);
+        let lines: Vec<String> = pgn
+            .moves()
+            .chunks(2)
+            .enumerate()
+            .map(|(index, pair)| format!("{}. {}", index + 1, pair.join(" ")))
+            .collect();
+        lines[lines.len().saturating_sub(MOVE_LIST_LINES)..].to_vec()
+    }
+
+    /// Draw the board and the side panel.
+    fn draw<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        queue!(
+            out,
+            terminal::Clear(terminal::ClearType::All),
+            cursor::MoveTo(0, 0)
+        )?;
+        let board = self.chess.board;
+        let last_move = self.chess.last_move();
+        let targets = match self.selected {
+            Some(from) => board.get_legal_moves(from),
+            None => vec![],
+        };
+        let (top, bottom) = match self.flipped {
+            false => (Color::Black, Color::White),
+            true => (Color::White, Color::Black),
+        };
+        let mut panel = vec![self.clock_text(top), String::new()];
+        panel.extend(self.move_list());
+        panel.resize(MOVE_LIST_LINES + 2, String::new());
+        panel.extend([String::new(), self.status(), self.clock_text(bottom)]);
+
+        for row in 0..8 {
+            let rank = match self.flipped {
+                false => 7 - row,
+                true => row,
+            };
+            queue!(out, style::Print(format!(" {} ", rank + 1)))?;
+            for column in 0..8 {
+                let file = match self.flipped {
+                    false => column,
+                    true => 7 - column,
+                };
+                let square = Square::new(rank * 8 + file);
+                let background = if square == self.cursor || Some(square) == self.selected {
+                    CURSOR_SQUARE
+                } else if targets.contains(&square) {
+                    TARGET_SQUARE
+                } else if last_move.is_some_and(|m| m.from == square || m.to == square) {
+                    LAST_MOVE_SQUARE
+                } else if (rank + file) % 2 == 0 {
+                    DARK_SQUARE
+                } else {
+                    LIGHT_SQUARE
+                };
+                let cell = match board.on(square) {
+                    Some((piece, color)) => {
+                        let foreground = match color {
+                            Color::White => style::Color::White,
+                            Color::Black => style::Color::Black,
+                        };
+                        format!(" {} ", piece_symbol(piece))
+                            .with(foreground)
+                            .on(background)
+                            .bold()
+                    }
+                    None => "   ".to_string().on(background),
+                };
+                queue!(out, style::PrintStyledContent(cell))?;
+            }
+            queue!(out, cursor::MoveToNextLine(1))?;
+        }
+        let files = match self.flipped {
+            false => "a  b  c  d  e  f  g  h",
+            true => "h  g  f  e  d  c  b  a",
+        };
+        queue!(out, style::Print(format!("    {files}")))?;
+        for (row, line) in panel.iter().enumerate() {
+            queue!(
+                out,
+                cursor::MoveTo(PANEL_COLUMN, row as u16),
+                style::Print(line)
+            )?;
+        }
+        let row = panel.len() as u16 + 1;
+        queue!(
+            out,
+            cursor::MoveTo(0, row),
+            style::Print(format!("Move: {}", self.input)),
+            cursor::MoveTo(0, row + 1),
+            style::Print(&self.message),
+            cursor::MoveTo(0, row + 3),
+            style::Print("Arrows + Space to move, or type a move (Nf3, g1f3) + Enter".dark_grey()),
+            cursor::MoveTo(0, row + 4),
+            style::Print("Commands: undo, new, flip, draw, resign, quit".dark_grey()),
+        )?;
+        out.flush()
+    }
+}
+
+/// Get the Unicode symbol of a piece (the filled one, coloured when drawn).
+fn piece_symbol(piece: Piece) -> char {
+    match piece {
+        Piece::Pawn => '♟',
+        Piece::Knight => '♞',
+        Piece::Bishop => '♝',
+        Piece::Rook => '♜',
+        Piece::Queen => '♛',
+        Piece::King => '♚',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(tui: &mut ChessTui, code: KeyCode) {
+        tui.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    fn type_text(tui: &mut ChessTui, text: &str) {
+        for c in text.chars() {
+            press(tui, KeyCode::Char(c));
+        }
+        press(tui, KeyCode::Enter);
+    }
+
+    fn screen(tui: &ChessTui) -> String {
+        let mut out = Vec::new();
+        tui.draw(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn cursor() {
+        let mut tui = ChessTui::new(Chess::default());
+        // The cursor starts on e2, e2e4 with the arrows
+        press(&mut tui, KeyCode::Char(' '));
+        assert_eq!(tui.selected, Some(Square::E2));
+        press(&mut tui, KeyCode::Up);
+        press(&mut tui, KeyCode::Up);
+        press(&mut tui, KeyCode::Enter);
+        assert_eq!(
+            tui.chess().last_move(),
+            Some(ChessMove::new(Square::E2, Square::E4))
+        );
+
+        // A white piece can't be selected by black
+        press(&mut tui, KeyCode::Left);
+        press(&mut tui, KeyCode::Down);
+        press(&mut tui, KeyCode::Down);
+        press(&mut tui, KeyCode::Enter);
+        assert_eq!(tui.selected, None);
+
+        // Flipped, up goes toward the first rank
+        tui.set_flipped(true);
+        tui.cursor = Square::D7;
+        press(&mut tui, KeyCode::Enter);
+        press(&mut tui, KeyCode::Up);
+        press(&mut tui, KeyCode::Enter);
+        assert_eq!(
+            tui.chess().last_move(),
+            Some(ChessMove::new(Square::D7, Square::D6))
+        );
+    }
+
+    #[test]
+    fn typed_moves_and_commands() {
+        let mut tui = ChessTui::new(Chess::default());
+        type_text(&mut tui, "e4");
+        type_text(&mut tui, "c7c5");
+        type_text(&mut tui, "Ke3");
+        assert_eq!(tui.message, "Illegal move: Ke3");
+        type_text(&mut tui, "Nf3");
+        assert_eq!(tui.chess().moves().len(), 3);
+
+        type_text(&mut tui, "undo");
+        assert_eq!(tui.chess().moves().len(), 2);
+        press(&mut tui, KeyCode::Char('x'));
+        press(&mut tui, KeyCode::Esc);
+        assert!(tui.input.is_empty());
+
+        type_text(&mut tui, "draw");
+        assert!(tui.chess().offer_draw);
+        type_text(&mut tui, "draw");
+        assert_eq!(tui.chess().state, GameState::DrawAccepted);
+        type_text(&mut tui, "Nf3");
+        assert_eq!(tui.message, "The game is over");
+
+        type_text(&mut tui, "new");
+        type_text(&mut tui, "resign");
+        assert_eq!(tui.chess().state, GameState::Resigns(Color::White));
+
+        tui.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        assert!(tui.quit);
+    }
+
+    #[test]
+    fn draw() {
+        let mut tui = ChessTui::new(Chess::default());
+        type_text(&mut tui, "e4");
+        type_text(&mut tui, "e5");
+        let screen = screen(&tui);
+        assert!(screen.contains('♚'));
+        assert!(screen.contains("1. e4 e5"));
+        assert!(screen.contains("White turn"));
+        assert!(screen.contains("* White --:--"));
+    }
+}
@@ -0,0 +1,340 @@
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use thiserror::Error;
+
+use crate::{Analysis, Board, ChessMove, MATE_SCORE, UCI_MOVETIME};
+
+/// Error that can happen when talking to an UCI engine.
+#[derive(Error, Debug)]
+pub enum UciError {
+    /// The engine can't be started.
+    #[error("Can't start the engine {}: {}", path.display(), source)]
+    Spawn {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    /// The engine can't be read or written.
+    #[error("Can't communicate with the engine: {}", source)]
+    Io {
+        #[from]
+        source: std::io::Error,
+    },
+
+    /// The engine closed its output.
+    #[error("The engine has quit")]
+    Closed,
+
+    /// The engine answered something unexpected.
+    #[error("Unexpected answer of the engine: {}", line)]
+    Protocol { line: String },
+}
+
+/// An external chess engine that talks the UCI (Universal Chess Interface) protocol.
+///
+/// The engine process is stopped when the [`UciEngine`] is dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use chess::{Board, UciEngine};
+///
+/// let mut engine = UciEngine::spawn("stockfish").expect("stockfish is installed");
+/// let analysis = engine.analyse(&Board::default()).unwrap();
+/// println!("{}: {}", engine.name(), analysis.best_move);
+/// ```
+#[derive(Debug)]
+pub struct UciEngine {
+    name: String,
+    child: Child,
+    /// Shared with the [stoppers][UciEngine::stopper].
+    stdin: Arc<Mutex<ChildStdin>>,
+    stdout: BufReader<ChildStdout>,
+    movetime: Duration,
+}
+
+impl UciEngine {
+    /// Start the engine and wait until it is ready.
+    pub fn spawn<P: AsRef<Path>>(path: P) -> Result<Self, UciError> {
+        let path = path.as_ref();
+        let mut child = Command::new(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|source| UciError::Spawn {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        let stdin = child.stdin.take().expect("piped stdin");
+        let stdout = BufReader::new(child.stdout.take().expect("piped stdout"));
+        let mut engine = UciEngine {
+            name: path.display().to_string(),
+            child,
+            stdin: Arc::new(Mutex::new(stdin)),
+            stdout,
+            movetime: UCI_MOVETIME,
+        };
+
+        engine.send("uci")?;
+        loop {
+            let line = engine.read_line()?;
+            if let Some(name) = line.strip_prefix("id name ") {
+                engine.name = name.trim().to_string();
+            } else if line.trim() == "uciok" {
+                break;
+            }
+        }
+        engine.send("isready")?;
+        while engine.read_line()?.trim() != "readyok" {}
+        Ok(engine)
+    }
+
+    /// Get the name of the engine.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Set the time the engine can think for each move.
+    pub fn set_movetime(&mut self, movetime: Duration) {
+        self.movetime = movetime;
+    }
+
+    /// Get a function that stops the current search (e.g. from another thread), the
+    /// engine then answers its best move.
+    pub fn stopper(&self) -> impl Fn() + Send + Sync + 'static {
+        let stdin = Arc::clone(&self.stdin);
+        move || {
+            let _ = write_command(&stdin, "stop");
+        }
+    }
+
+    /// Send a command to the engine.
+    fn send(&mut self, command: &str) -> Result<(), UciError> {
+        write_command(&self.stdin, command)
+    }
+
+    /// Read a line of the engine.
+    fn read_line(&mut self) -> Result<String, UciError> {
+        let mut line = String::new();
+        if self.stdout.read_line(&mut line)? == 0 {
+            return Err(UciError::Closed);
+        }
+        #[cfg(feature = "log")]
+        log::debug!("uci < {}", line.trim_end());
+        Ok(line)
+    }
+
+    /// Search the best move of the position during the [movetime][UciEngine::set_movetime].
+    pub fn analyse(&mut self, board: &Board) -> Result<Analysis, UciError> {
+        self.send(&format!("position fen {board}"))?;
+        self.send(&format!("go movetime {}", self.movetime.as_millis()))?;
+        let mut info = None;
+        loop {
+            let line = self.read_line()?;
+            let line = line.trim();
+            if line.starts_with("info ") {
+                info = parse_info(line).or(info);
+            } else if let Some(best_move) = line.strip_prefix("bestmove ") {
+                let best_move = best_move.split_whitespace().next().unwrap_or_default();
+                let best_move = parse_move(best_move).ok_or_else(|| UciError::Protocol {
+                    line: line.to_string(),
+                })?;
+                let (score, depth, mut pv) = info.unwrap_or((0, 0, vec![]));
+                if pv.first() != Some(&best_move) {
+                    pv = vec![best_move];
+                }
+                return Ok(Analysis {
+                    best_move,
+                    score,
+                    pv,
+                    depth,
+                });
+            }
+        }
+    }
+
+    /// Search the best lines of the position (MultiPV) until the search is stopped.
+    ///
+    /// The lines (from the best to the worst) and the number of positions searched are
+    /// given to `report` every time the engine sends them, the search is stopped when
+    /// `report` returns false or when a [stopper][UciEngine::stopper] is called.
+    pub fn analyse_infinite(
+        &mut self,
+        board: &Board,
+        count: usize,
+        mut report: impl FnMut(&[Analysis], u64) -> bool,
+    ) -> Result<(), UciError> {
+        let count = count.max(1);
+        self.send(&format!("setoption name MultiPV value {count}"))?;
+        self.send(&format!("position fen {board}"))?;
+        self.send("go infinite")?;
+        let mut lines: Vec<Analysis> = vec![];
+        let mut stopped = false;
+        loop {
+            let line = self.read_line()?;
+            let line = line.trim();
+            if line.starts_with("bestmove") {
+                return Ok(());
+            }
+            if stopped || !line.starts_with("info ") {
+                continue;
+            }
+            let (Some((score, depth, pv)), (index, nodes)) = (parse_info(line), parse_stats(line))
+            else {
+                continue;
+            };
+            let Some(&best_move) = pv.first() else {
+                continue;
+            };
+            let index = index.clamp(1, count) - 1;
+            let analysis = Analysis {
+                best_move,
+                score,
+                pv,
+                depth,
+            };
+            match lines.get_mut(index) {
+                Some(line) => *line = analysis,
+                None => lines.push(analysis),
+            }
+            if !report(&lines, nodes) {
+                self.send("stop")?;
+                stopped = true;
+            }
+        }
+    }
+}
+
+/// Write a command to the engine.
+fn write_command(stdin: &Mutex<ChildStdin>, command: &str) -> Result<(), UciError> {
+    #[cfg(feature = "log")]
+    log::debug!("uci > {command}");
+    let mut stdin = stdin.lock().expect("engine input not poisoned");
+    writeln!(stdin, "{command}")?;
+    stdin.flush()?;
+    Ok(())
+}
+
+impl Drop for UciEngine {
+    fn drop(&mut self) {
+        if self.send("quit").is_err() || self.child.wait().is_err() {
+            let _ = self.child.kill();
+        }
+    }
+}
+
+/// Parse a move in UCI notation.
+///
+/// A pawn is always promoted to a queen, so an under-promotion is played as a queen promotion.
+fn parse_move(text: &str) -> Option<ChessMove> {
+    ChessMove::from_str(text.get(..4)?).ok()
+}
+
+/// Parse the score, the depth and the principal variation of an `info` line.
+///
+/// Return [`None`] if the line doesn't contain a score.
+fn parse_info(line: &str) -> Option<(i32, u32, Vec<ChessMove>)> {
+    let mut tokens = line.split_whitespace();
+    let mut score = None;
+    let mut depth = 0;
+    let mut pv = vec![];
+    while let Some(token) = tokens.next() {
+        match token {
+            "depth" => depth = tokens.next()?.parse().ok()?,
+            "score" => {
+                let kind = tokens.next()?;
+                let value: i32 = tokens.next()?.parse().ok()?;
+                score = match kind {
+                    "cp" => Some(value),
+                    "mate" if value > 0 => Some(MATE_SCORE - (2 * value - 1)),
+                    "mate" => Some(-MATE_SCORE - 2 * value),
+                    _ => return None,
+                };
+            }
+            "pv" => {
+                pv = tokens.by_ref().map_while(parse_move).collect();
+            }
+            _ => {}
+        }
+    }
+    score.map(|score| (score, depth, pv))
+}
+
+/// Parse the index of the line (`multipv`, 1 if missing) and the number of positions
+/// searched (`nodes`, 0 if missing) of an `info` line.
+fn parse_stats(line: &str) -> (usize, u64) {
+    let mut tokens = line.split_whitespace();
+    let (mut index, mut nodes) = (1, 0);
+    while let Some(token) = tokens.next() {
+        match token {
+            "multipv" => index = tokens.next().and_then(|n| n.parse().ok()).unwrap_or(1),
+            "nodes" => nodes = tokens.next().and_then(|n| n.parse().ok()).unwrap_or(0),
+            // The moves of the line can't be confused with the keywords
+            "pv" => break,
+            _ => {}
+        }
+    }
+    (index, nodes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Square;
+
+    #[test]
+    fn info() {
+        assert_eq!(
+            parse_info("info depth 12 seldepth 15 score cp -35 nodes 1000 pv e7e5 g1f3"),
+            Some((
+                -35,
+                12,
+                vec![
+                    ChessMove::new(Square::E7, Square::E5),
+                    ChessMove::new(Square::G1, Square::F3)
+                ]
+            ))
+        );
+        assert_eq!(parse_info("info string hello"), None);
+        assert_eq!(parse_info("info depth 1 score lowerbound"), None);
+    }
+
+    #[test]
+    fn info_mate() {
+        let (score, _, pv) = parse_info("info depth 5 score mate 2 pv a7a8n").unwrap();
+        let analysis = Analysis {
+            best_move: pv[0],
+            score,
+            pv,
+            depth: 5,
+        };
+        assert_eq!(analysis.mate_in(), Some(2));
+        assert_eq!(analysis.best_move, ChessMove::new(Square::A7, Square::A8));
+
+        let (score, _, _) = parse_info("info score mate -3").unwrap();
+        assert_eq!(score, -MATE_SCORE + 6);
+    }
+
+    #[test]
+    fn stats() {
+        assert_eq!(
+            parse_stats("info depth 20 multipv 3 score cp 12 nodes 123456 nps 900 pv e2e4"),
+            (3, 123456)
+        );
+        assert_eq!(parse_stats("info depth 1 score cp 12 pv e2e4"), (1, 0));
+    }
+
+    #[test]
+    fn spawn_error() {
+        assert!(matches!(
+            UciEngine::spawn("/nonexistent/engine"),
+            Err(UciError::Spawn { .. })
+        ));
+    }
+}
@@ -0,0 +1,527 @@
+//! Command-line interface of the chess executable
+
+use std::error::Error;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use chess::{
+    run, Analysis, Board, Chess, ChessGui, ChessMove, Color, Correspondence, Engine, Pgn, Player,
+    Puzzle, Session, Settings, Spectator, TimeControl, UciEngine, ENGINE_DEPTH,
+};
+use clap::{ArgGroup, Parser, Subcommand};
+
+/// Chess game with graphical interface.
+///
+/// Without subcommand, the game window is opened.
+#[derive(Parser, Debug)]
+#[command(version, about)]
+#[command(group(ArgGroup::new("spectator").args(["watch", "watch_pgn"])))]
+pub struct Cli {
+    /// Start from a position in FEN (Forsyth-Edwards Notation)
+    #[arg(long, conflicts_with = "pgn")]
+    fen: Option<String>,
+
+    /// Start from the end of the game of a PGN file
+    #[arg(long)]
+    pgn: Option<PathBuf>,
+
+    /// Name of the theme
+    #[arg(long)]
+    theme: Option<String>,
+
+    /// Who plays white: human, engine, engine:<depth>, level:<level>, elo:<rating> or the path
+    /// of an UCI engine
+    #[arg(long, default_value = "human")]
+    white: Player,
+
+    /// Who plays black: human, engine, engine:<depth>, level:<level>, elo:<rating> or the path
+    /// of an UCI engine
+    #[arg(long, default_value = "human")]
+    black: Player,
+
+    /// Time control of the game: <minutes>+<seconds> or none [default: from the settings]
+    #[arg(long)]
+    time_control: Option<String>,
+
+    /// Open the board editor to set up the position before playing
+    #[arg(long, conflicts_with_all = ["join", "watch", "watch_pgn"])]
+    edit: bool,
+
+    /// Analyse the position with the engine of the settings
+    #[arg(long, conflicts_with_all = ["host", "join"])]
+    analyse: bool,
+
+    /// Settings file [default: settings.toml in the user config directory]
+    #[arg(long)]
+    settings: Option<PathBuf>,
+
+    /// Host a network game on an address (e.g. 0.0.0.0:7878)
+    #[arg(long, conflicts_with = "join")]
+    host: Option<String>,
+
+    /// Join the network game hosted on an address (e.g. 192.168.1.10:7878)
+    #[arg(long, conflicts_with_all = ["fen", "pgn", "time_control"])]
+    join: Option<String>,
+
+    /// Color of the host of a network game: white or black
+    #[arg(long, default_value = "white", requires = "host")]
+    color: Color,
+
+    /// Watch the network game hosted on an address, without playing
+    #[arg(long, conflicts_with_all = ["host", "join", "fen", "pgn"])]
+    watch: Option<String>,
+
+    /// Watch the game written in a PGN file (e.g. the autosave of another rg-chess)
+    #[arg(long, conflicts_with_all = ["host", "join", "fen", "pgn"])]
+    watch_pgn: Option<PathBuf>,
+
+    /// Delay in seconds before a spectator sees the moves
+    #[arg(long, requires = "spectator")]
+    delay: Option<u64>,
+
+    /// Solve the puzzles of a CSV file (e.g. the puzzle database of lichess.org)
+    #[arg(long, conflicts_with_all = ["host", "join", "watch", "watch_pgn", "edit"])]
+    puzzles: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// Headless commands, they don't open a window.
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Count the positions reachable at a given depth
+    Perft {
+        /// Depth in half-moves
+        depth: u32,
+
+        /// Position in FEN [default: initial position]
+        #[arg(long)]
+        fen: Option<String>,
+
+        /// Show the count for each move
+        #[arg(long)]
+        divide: bool,
+    },
+
+    /// Verify a position in FEN, the exit code is 1 if it is invalid
+    ValidateFen {
+        /// Position in FEN
+        fen: String,
+    },
+
+    /// Print the final position of a PGN game in FEN
+    Pgn2fen {
+        /// The PGN file
+        file: PathBuf,
+
+        /// Print every position of the game
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Search the best move of a position
+    Analyse {
+        /// Position in FEN [default: initial position]
+        #[arg(long, conflicts_with = "pgn")]
+        fen: Option<String>,
+
+        /// Analyse the final position of a PGN game
+        #[arg(long)]
+        pgn: Option<PathBuf>,
+
+        /// Depth of the built-in engine (in half-moves)
+        #[arg(long, default_value_t = ENGINE_DEPTH)]
+        depth: u32,
+
+        /// Use an UCI engine instead of the built-in one
+        #[arg(long)]
+        uci: Option<PathBuf>,
+
+        /// Thinking time of the UCI engine (in milliseconds)
+        #[arg(long, default_value_t = 1000)]
+        movetime: u64,
+    },
+
+    /// Play a correspondence game by exchanging move files
+    #[command(subcommand)]
+    Correspondence(CorrespondenceCommand),
+}
+
+#[derive(Subcommand, Debug)]
+enum CorrespondenceCommand {
+    /// Play a move in the game, and write the move file to send to the opponent
+    Play {
+        /// The PGN file of the game (created if it doesn't exist)
+        game: PathBuf,
+
+        /// The move in SAN (e.g. `Nf3`) or UCI (e.g. `g1f3`)
+        #[arg(value_name = "MOVE")]
+        move_text: String,
+
+        /// The move file [default: `<GAME>-<PLY>.pgn`]
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+
+    /// Verify the move file received from the opponent, and play its move in the game
+    Import {
+        /// The PGN file of the game (created if it doesn't exist)
+        game: PathBuf,
+
+        /// The move file
+        file: PathBuf,
+    },
+}
+
+impl Cli {
+    /// Execute the command (or open the game window).
+    pub fn execute(self) -> Result<(), Box<dyn Error>> {
+        match self.command {
+            Some(command) => command.execute(&mut io::stdout().lock()),
+            None => self.open_window(),
+        }
+    }
+
+    /// Open the game window with the options.
+    fn open_window(self) -> Result<(), Box<dyn Error>> {
+        let mut game = ChessGui::default();
+        // Loaded first, the options have the priority
+        if let Some(path) = self.settings.or_else(Settings::default_path) {
+            game.load_settings(path);
+        }
+        if let Some(theme) = &self.theme {
+            game.select_theme(theme)?;
+        }
+
+        // The last game is proposed only when no position is given, not in a network game
+        let network = self.host.is_some() || self.join.is_some();
+        let spectator = match (self.watch, &self.watch_pgn) {
+            (Some(address), _) => Some(Spectator::watch(address)),
+            (None, Some(path)) => Some(Spectator::tail(path)),
+            (None, None) => None,
+        };
+        let resume = self.fen.is_none()
+            && self.pgn.is_none()
+            && self.puzzles.is_none()
+            && !self.edit
+            && !network
+            && spectator.is_none();
+        let mut chess = load_chess(self.fen, self.pgn)?;
+        let time_control = match self.time_control.as_deref() {
+            Some("none") => None,
+            Some(time_control) => Some(TimeControl::from_str(time_control)?),
+            None => game.settings().time_control(),
+        };
+        chess.set_time_control(time_control);
+        game.set_chess(chess);
+        game.set_player(Color::White, &self.white)?;
+        game.set_player(Color::Black, &self.black)?;
+        if self.edit {
+            game.open_editor();
+        }
+        if let Some(path) = &self.puzzles {
+            game.open_puzzles(Puzzle::load_csv(path)?);
+        }
+        if self.analyse {
+            game.toggle_analysis();
+        }
+        if let Some(address) = &self.host {
+            let session = Session::host(address, self.color)?;
+            if let Some(address) = session.local_addr() {
+                println!("Waiting for the opponent on {address}");
+            }
+            game.set_network(session);
+        }
+        if let Some(address) = &self.join {
+            game.set_network(Session::join(address));
+        }
+        if let Some(mut spectator) = spectator {
+            spectator.set_delay(Duration::from_secs(self.delay.unwrap_or(0)));
+            game.set_spectator(spectator);
+        }
+        if let Some(path) = Chess::autosave_path() {
+            if resume {
+                game.propose_resume(&path);
+            }
+            game.enable_autosave(path);
+        }
+
+        run(game);
+        Ok(())
+    }
+}
+
+impl Command {
+    /// Execute the headless command, its output is written in `out`.
+    fn execute(self, out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+        match self {
+            Command::Perft { depth, fen, divide } => {
+                perft(&load_chess(fen, None)?, depth, divide, out)
+            }
+            Command::ValidateFen { fen } => {
+                let board = load_fen(&fen)?;
+                writeln!(out, "{board}")?;
+                Ok(())
+            }
+            Command::Pgn2fen { file, all } => {
+                let chess = load_chess(None, Some(file))?;
+                if all {
+                    for fen in chess.history() {
+                        writeln!(out, "{fen}")?;
+                    }
+                }
+                writeln!(out, "{}", chess.board())?;
+                Ok(())
+            }
+            Command::Analyse {
+                fen,
+                pgn,
+                depth,
+                uci,
+                movetime,
+            } => {
+                let board = load_chess(fen, pgn)?.board();
+                let (name, analysis) = match uci {
+                    Some(path) => {
+                        let mut engine = UciEngine::spawn(path)?;
+                        engine.set_movetime(Duration::from_millis(movetime));
+                        (engine.name().to_string(), Some(engine.analyse(&board)?))
+                    }
+                    None => {
+                        let engine = Engine::new(depth);
+                        ("Built-in engine".to_string(), engine.analyse(&board))
+                    }
+                };
+                writeln!(out, "Position: {board}")?;
+                writeln!(out, "Engine:   {name}")?;
+                match analysis {
+                    Some(analysis) => print_analysis(&board, &analysis, out)?,
+                    None => writeln!(out, "Game over: {:?}", board.state())?,
+                }
+                Ok(())
+            }
+            Command::Correspondence(command) => correspondence(command, out),
+        }
+    }
+}
+
+/// Parse a position in FEN, it must be playable (e.g. a king of each side).
+fn load_fen(fen: &str) -> Result<Board, Box<dyn Error>> {
+    let board = Board::from_str(fen)?;
+    board.validate()?;
+    Ok(board)
+}
+
+/// Create the game from a FEN string or a PGN file (the initial position by default).
+fn load_chess(fen: Option<String>, pgn: Option<PathBuf>) -> Result<Chess, Box<dyn Error>> {
+    if let Some(fen) = fen {
+        return Ok(Chess::new(load_fen(&fen)?));
+    }
+    if let Some(path) = pgn {
+        let content = fs::read_to_string(&path)
+            .map_err(|error| format!("Can't read {}: {}", path.display(), error))?;
+        return Ok(Pgn::from_str(&content)?.to_chess()?);
+    }
+    Ok(Chess::default())
+}
+
+/// Play or import a move of a correspondence game.
+fn correspondence(
+    command: CorrespondenceCommand,
+    output: &mut impl Write,
+) -> Result<(), Box<dyn Error>> {
+    let load = |game: &Path| -> Result<Chess, Box<dyn Error>> {
+        match game.exists() {
+            true => Ok(Chess::load(game)?),
+            false => Ok(Chess::default()),
+        }
+    };
+    match command {
+        CorrespondenceCommand::Play {
+            game,
+            move_text,
+            out,
+        } => {
+            let mut chess = load(&game)?;
+            let board = chess.board();
+            let m = ChessMove::from_str(&move_text)
+                .or_else(|_| ChessMove::from_san(&board, &move_text))?;
+            if !chess.state().is_ongoing() || !board.is_legal(m) {
+                return Err(format!("Illegal move {move_text}").into());
+            }
+            chess.play(m.from, m.to);
+            let correspondence = Correspondence::from_chess(&chess)?;
+            let out = out.unwrap_or_else(|| {
+                let stem = game.file_stem().unwrap_or_default().to_string_lossy();
+                game.with_file_name(format!("{stem}-{}.pgn", correspondence.ply()))
+            });
+            correspondence.save(&out)?;
+            chess.save(&game)?;
+            writeln!(
+                output,
+                "Played {}, send {}",
+                m.to_san(&board),
+                out.display()
+            )?;
+        }
+        CorrespondenceCommand::Import { game, file } => {
+            let mut chess = load(&game)?;
+            let board = chess.board();
+            let m = Correspondence::load(&file)?.apply(&mut chess)?;
+            chess.save(&game)?;
+            writeln!(output, "The opponent played {}", m.to_san(&board))?;
+            if chess.state().is_finish() {
+                writeln!(output, "Game over: {:?}", chess.state())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Count and print the positions reachable at a given depth.
+fn perft(
+    chess: &Chess,
+    depth: u32,
+    divide: bool,
+    out: &mut impl Write,
+) -> Result<(), Box<dyn Error>> {
+    let board = chess.board();
+    let start = Instant::now();
+    let nodes = if divide && depth > 0 {
+        let mut nodes = 0;
+        for m in board.get_all_legal_moves() {
+            let mut next = board;
+            next.update(m);
+            let count = next.perft(depth - 1);
+            writeln!(out, "{m}: {count}")?;
+            nodes += count;
+        }
+        writeln!(out)?;
+        nodes
+    } else {
+        board.perft(depth)
+    };
+    writeln!(out, "Nodes: {nodes}")?;
+    writeln!(out, "Time:  {:.3}s", start.elapsed().as_secs_f64())?;
+    Ok(())
+}
+
+/// Print the score, the best move and the principal variation.
+fn print_analysis(board: &Board, analysis: &Analysis, out: &mut impl Write) -> io::Result<()> {
+    let score = match analysis.mate_in() {
+        Some(moves) => format!("mate in {moves}"),
+        None => format!("{:+.2}", analysis.score as f32 / 100.0),
+    };
+    writeln!(out, "Depth:    {}", analysis.depth)?;
+    writeln!(out, "Score:    {score} (for {:?})", board.side_to_move())?;
+    writeln!(out, "Best:     {}", analysis.best_move.to_san(board))?;
+    writeln!(out, "Line:     {}", analysis.pv_san(board))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parse the arguments of a headless command and execute it, return its output.
+    fn execute(args: &[&str]) -> Result<String, Box<dyn Error>> {
+        let cli = Cli::try_parse_from(["rg-chess"].iter().chain(args))?;
+        let mut out = Vec::new();
+        cli.command.expect("a subcommand").execute(&mut out)?;
+        Ok(String::from_utf8(out)?)
+    }
+
+    #[test]
+    fn arguments() {
+        assert!(Cli::try_parse_from(["rg-chess", "--fen", "8/8", "--pgn", "game.pgn"]).is_err());
+        assert!(Cli::try_parse_from(["rg-chess", "--join", "host:7878", "--host", "x"]).is_err());
+        assert!(Cli::try_parse_from(["rg-chess", "--white", "level:master"]).is_err());
+        assert!(execute(&["analyse", "--fen", "8/8", "--pgn", "game.pgn"]).is_err());
+        assert!(execute(&["perft"]).is_err());
+    }
+
+    #[test]
+    fn validate_fen() {
+        let fen = "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1";
+        assert_eq!(execute(&["validate-fen", fen]).unwrap(), format!("{fen}\n"));
+        assert!(execute(&["validate-fen", "4k3/8/8 w - - 0 1"]).is_err());
+        // A valid FEN but an unplayable position
+        let error = execute(&["validate-fen", "8/8/8/8/8/8/8/8 w - - 0 1"]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "The white side must have exactly one king"
+        );
+    }
+
+    #[test]
+    fn perft() {
+        let output = execute(&["perft", "2"]).unwrap();
+        assert!(output.starts_with("Nodes: 400\n"), "{output}");
+        let output = execute(&["perft", "1", "--divide"]).unwrap();
+        assert!(output.contains("e2e4: 1\n"), "{output}");
+        assert!(output.contains("Nodes: 20\n"), "{output}");
+        let kingless = ["perft", "1", "--fen", "8/8/8/8/8/8/4P3/8 w - - 0 1"];
+        assert!(execute(&kingless).is_err());
+    }
+
+    #[test]
+    fn pgn2fen() {
+        let path = std::env::temp_dir().join("rg-chess-test-cli-pgn2fen.pgn");
+        fs::write(&path, "1. e4 e5 *\n").unwrap();
+        let output = execute(&["pgn2fen", path.to_str().unwrap(), "--all"]).unwrap();
+        let fens: Vec<&str> = output.lines().collect();
+        assert_eq!(fens.len(), 3);
+        assert_eq!(fens[0], Board::default().to_string());
+        assert_eq!(
+            fens[2],
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2"
+        );
+
+        fs::write(&path, "1. e4 e4 *\n").unwrap();
+        assert!(execute(&["pgn2fen", path.to_str().unwrap()]).is_err());
+        fs::remove_file(&path).unwrap();
+        let error = execute(&["pgn2fen", path.to_str().unwrap()]).unwrap_err();
+        assert!(error.to_string().starts_with("Can't read"), "{error}");
+    }
+
+    #[test]
+    fn analyse() {
+        let mate = [
+            "analyse",
+            "--fen",
+            "7k/8/6K1/8/8/8/8/R7 w - - 0 1",
+            "--depth",
+            "2",
+        ];
+        let output = execute(&mate).unwrap();
+        assert!(output.contains("Best:     Ra8#\n"), "{output}");
+        let kingless = ["analyse", "--fen", "7k/8/8/8/8/8/8/R7 w - - 0 1"];
+        let error = execute(&kingless).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "The white side must have exactly one king"
+        );
+    }
+
+    #[test]
+    fn correspondence() {
+        let dir = std::env::temp_dir().join("rg-chess-test-cli-correspondence");
+        fs::create_dir_all(&dir).unwrap();
+        let (white, black) = (dir.join("white.pgn"), dir.join("black.pgn"));
+        let (white, black) = (white.to_str().unwrap(), black.to_str().unwrap());
+
+        assert!(execute(&["correspondence", "play", white, "e5"]).is_err());
+        let output = execute(&["correspondence", "play", white, "e4"]).unwrap();
+        let file = dir.join("white-1.pgn");
+        assert_eq!(output, format!("Played e4, send {}\n", file.display()));
+        let file = file.to_str().unwrap();
+        let output = execute(&["correspondence", "import", black, file]).unwrap();
+        assert_eq!(output, "The opponent played e4\n");
+        // The same move can't be imported twice
+        assert!(execute(&["correspondence", "import", black, file]).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
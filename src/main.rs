@@ -1,11 +1,21 @@
 //! chess game executable
 
-use chess::{run, ChessGui};
+use std::process::ExitCode;
 
-fn main() {
+use clap::Parser;
+
+mod cli;
+
+fn main() -> ExitCode {
     // Init the logger
     env_logger::init();
 
-    // Create and run the game
-    run(ChessGui::default());
+    // Parse the arguments, then run the command or the game
+    match cli::Cli::parse().execute() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,33 @@
+//! chess game server executable
+
+use std::net::TcpListener;
+use std::process::ExitCode;
+
+use clap::Parser;
+
+/// Host chess games for WebSocket clients (browsers or bots), in JSON.
+#[derive(Parser, Debug)]
+#[command(version, about)]
+struct Cli {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    address: String,
+}
+
+fn main() -> ExitCode {
+    // Init the logger
+    env_logger::init();
+
+    let cli = Cli::parse();
+    let result = TcpListener::bind(&cli.address).and_then(|listener| {
+        println!("Listening on ws://{}", listener.local_addr()?);
+        chess::serve(listener)
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
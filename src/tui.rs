@@ -0,0 +1,77 @@
+//! chess terminal executable
+
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::str::FromStr;
+
+use chess::{Board, Chess, ChessTui, Color, Pgn, Player, TimeControl};
+use clap::Parser;
+
+/// Chess game in the terminal (e.g. over SSH without a display).
+#[derive(Parser, Debug)]
+#[command(version, about)]
+struct Cli {
+    /// Start from a position in FEN
+    #[arg(long, conflicts_with = "pgn")]
+    fen: Option<String>,
+
+    /// Start from the end of a PGN game
+    #[arg(long)]
+    pgn: Option<PathBuf>,
+
+    /// Time control of the game: <minutes>+<seconds>
+    #[arg(long)]
+    time_control: Option<String>,
+
+    /// Who plays white: human, engine, engine:<depth>, level:<level>, elo:<rating> or the path
+    /// of an UCI engine
+    #[arg(long, default_value = "human")]
+    white: Player,
+
+    /// Who plays black: human, engine, engine:<depth>, level:<level>, elo:<rating> or the path
+    /// of an UCI engine
+    #[arg(long, default_value = "human")]
+    black: Player,
+
+    /// Show black at the bottom
+    #[arg(long)]
+    flip: bool,
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    let mut chess = match (cli.fen, cli.pgn) {
+        (Some(fen), _) => {
+            let board = Board::from_str(&fen)?;
+            // An unplayable position (e.g. without king) is refused
+            board.validate()?;
+            Chess::new(board)
+        }
+        (None, Some(path)) => {
+            let content = fs::read_to_string(&path)
+                .map_err(|error| format!("Can't read {}: {}", path.display(), error))?;
+            Pgn::from_str(&content)?.to_chess()?
+        }
+        (None, None) => Chess::default(),
+    };
+    if let Some(time_control) = cli.time_control {
+        chess.set_time_control(Some(TimeControl::from_str(&time_control)?));
+    }
+    let mut game = ChessTui::new(chess);
+    game.set_player(Color::White, &cli.white)?;
+    game.set_player(Color::Black, &cli.black)?;
+    game.set_flipped(cli.flip);
+    game.run()?;
+    Ok(())
+}